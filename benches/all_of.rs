@@ -0,0 +1,39 @@
+//! Benchmark the optimisation of schemas with nested `allOf` keywords
+//!
+//! Reference timings (depth 2 / 4 / 8) measured while flattening `allOf` without cloning the parent schema:
+//!  * cloning the parent schema: 10.67 ms / 51.50 ms / 1.21 s
+//!  * without cloning the parent schema: 5.20 ms / 14.71 ms / 54.64 ms
+//!  * without cloning the parent schema, and hoisting the nested `allOf` items: 96.9 µs / 295 µs / 661 µs
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use jsonschema_equivalent::jsonschema_equivalent;
+use serde_json::{json, Value};
+
+/// Schema with `depth` levels of `allOf`, each level defining a few properties
+fn nested_all_of_schema(depth: usize) -> Value {
+    (0..depth).fold(json!({"type": "object"}), |schema, level| {
+        json!({
+            "allOf": [
+                schema,
+                {"minProperties": level, "properties": {format!("property_{}", level): {"type": "string", "minimum": 1}}},
+                {"type": ["object", "string"], "required": [format!("property_{}", level)]},
+            ],
+            "properties": {
+                format!("nested_{}", level): {"allOf": [{"type": "integer"}, {"maximum": level}]},
+            },
+        })
+    })
+}
+
+fn bench_nested_all_of(c: &mut Criterion) {
+    let mut group = c.benchmark_group("nested_all_of");
+    for depth in &[2, 4, 8] {
+        let schema = nested_all_of_schema(*depth);
+        let _ = group.bench_with_input(BenchmarkId::from_parameter(depth), &schema, |b, schema| {
+            b.iter(|| jsonschema_equivalent(black_box(schema.clone())))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_nested_all_of);
+criterion_main!(benches);
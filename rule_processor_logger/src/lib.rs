@@ -59,7 +59,7 @@ use syn::{parse_macro_input, FnArg, ItemFn, Pat};
 ///
 ///     {log::info!("{}", serde_json::json!({
 ///         "method": "foo",
-///         "elapsed_time_s": (std::time::Instant::now() - start),
+///         "elapsed_time_s": start.elapsed(),
 ///         "input_schema": original_schema,
 ///         "output_schema": schema,
 ///         "is_schema_updated": result
@@ -91,7 +91,7 @@ use syn::{parse_macro_input, FnArg, ItemFn, Pat};
 ///     #[cfg(feature = "my-feature")]
 ///     {log::info!("{}", serde_json::json!({
 ///         "method": "foo",
-///         "elapsed_time_s": (std::time::Instant::now() - start),
+///         "elapsed_time_s": start.elapsed(),
 ///         "input_schema": original_schema,
 ///         "output_schema": schema,
 ///         "is_schema_updated": result
@@ -145,7 +145,7 @@ pub fn log_processing(attr: TokenStream, item: TokenStream) -> TokenStream {
             {
                 log::info!("{}", serde_json::json!({
                     "method": #method_name,
-                    "elapsed_time_s": format!("{:.9}", (std::time::Instant::now() - start).as_secs_f64()),
+                    "elapsed_time_s": format!("{:.9}", start.elapsed().as_secs_f64()),
                     "input_schema": input_schema,
                     "output_schema": #input_param_name,
                     "is_schema_updated": is_schema_updated
@@ -0,0 +1,701 @@
+//! Bundling of external references.
+//!
+//! Schemas split across multiple documents (ie. `{"$ref": "common.json#/definitions/Id"}`) can not be
+//! optimised as a whole. Bundling retrieves the referenced documents, via a [`Resolver`], and embeds them
+//! into the `definitions` of the schema. All the references are then rewritten as JSON Pointers within
+//! the bundled schema, so the `$id` (and Draft4 `id`) keywords of the embedded schemas are not needed anymore.
+use crate::{
+    helpers::{pointer, subschemas::for_each_subschema_with_pointer},
+    try_jsonschema_equivalent_with_options, Error, Options,
+};
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::path::{Component, Path, PathBuf};
+
+/// Retrieval of the documents referenced by a schema
+pub trait Resolver {
+    /// Retrieve the document identified by the absolute `uri` (without fragment).
+    ///
+    /// # Errors
+    ///
+    /// A description of the failure if the document can not be retrieved.
+    fn resolve(&self, uri: &str) -> Result<Value, String>;
+}
+
+/// [`Resolver`] of documents registered in memory, by their absolute URI
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct InMemoryResolver {
+    documents: HashMap<String, Value>,
+}
+
+impl InMemoryResolver {
+    /// Create a resolver with no documents
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register `document` as the document identified by `uri`
+    #[must_use]
+    pub fn with_document(mut self, uri: &str, document: Value) -> Self {
+        let _ = self
+            .documents
+            .insert(split_fragment(uri).0.to_string(), document);
+        self
+    }
+}
+
+impl Resolver for InMemoryResolver {
+    fn resolve(&self, uri: &str) -> Result<Value, String> {
+        self.documents
+            .get(uri)
+            .cloned()
+            .ok_or_else(|| "Document not registered".to_string())
+    }
+}
+
+/// [`Resolver`] of documents stored in a local directory (no network access is performed).
+///
+/// The URIs starting with the base URI (`file:///` by default) are mapped to the files of the directory
+/// (ie. `file:///common/id.json` is mapped to `<directory>/common/id.json`).
+#[derive(Clone, Debug, PartialEq)]
+pub struct FileSystemResolver {
+    directory: PathBuf,
+    base_uri: String,
+}
+
+impl FileSystemResolver {
+    /// Create a resolver of the documents stored in `directory`
+    #[must_use]
+    pub fn new(directory: impl Into<PathBuf>) -> Self {
+        Self {
+            directory: directory.into(),
+            base_uri: "file:///".to_string(),
+        }
+    }
+
+    /// Map the URIs starting with `base_uri` (ie. `https://example.com/schemas/`) to the files of the directory
+    #[must_use]
+    pub fn with_base_uri(mut self, base_uri: &str) -> Self {
+        self.base_uri = base_uri.to_string();
+        self
+    }
+}
+
+impl Resolver for FileSystemResolver {
+    fn resolve(&self, uri: &str) -> Result<Value, String> {
+        let relative_path = uri
+            .strip_prefix(self.base_uri.as_str())
+            .ok_or_else(|| format!("URI is not within {:?}", self.base_uri))?;
+        let relative_path = Path::new(relative_path.trim_start_matches('/'));
+        if !relative_path
+            .components()
+            .all(|component| matches!(component, Component::Normal(_)))
+        {
+            return Err("URI is not within the directory".to_string());
+        }
+        let content = std::fs::read_to_string(self.directory.join(relative_path))
+            .map_err(|error| error.to_string())?;
+        serde_json::from_str(&content).map_err(|error| error.to_string())
+    }
+}
+
+/// Split `uri` into the URI without fragment and the fragment (without `#`)
+fn split_fragment(uri: &str) -> (&str, &str) {
+    uri.split_once('#').unwrap_or((uri, ""))
+}
+
+/// Remove the `.` and `..` segments of `path` (RFC 3986, section 5.2.4)
+fn remove_dot_segments(path: &str) -> String {
+    let mut segments: Vec<&str> = Vec::new();
+    let mut path_segments = path.split('/').peekable();
+    while let Some(segment) = path_segments.next() {
+        match segment {
+            "." | ".." => {
+                if segment == ".." && segments.len() > 1 {
+                    let _ = segments.pop();
+                }
+                if path_segments.peek().is_none() {
+                    // The path refers to a directory
+                    segments.push("");
+                }
+            }
+            _ => segments.push(segment),
+        }
+    }
+    segments.join("/")
+}
+
+/// Checks if `uri` defines a scheme (ie. `https:`), so it is an absolute URI
+fn has_scheme(uri: &str) -> bool {
+    uri.split_once(':').is_some_and(|(scheme, _)| {
+        scheme.starts_with(|character: char| character.is_ascii_alphabetic())
+            && scheme.chars().all(|character| {
+                character.is_ascii_alphanumeric() || matches!(character, '+' | '-' | '.')
+            })
+    })
+}
+
+/// Resolve the URI `reference` against the `base` URI (RFC 3986, section 5.2.2)
+fn resolve_uri(base: &str, reference: &str) -> String {
+    if has_scheme(reference) {
+        let (scheme, rest) = reference.split_once(':').unwrap_or(("", reference));
+        return format!("{scheme}:{}", remove_dot_segments(rest));
+    }
+    let base = split_fragment(base).0;
+    if reference.is_empty() || reference.starts_with('#') {
+        return format!("{base}{reference}");
+    }
+    let (scheme, hierarchical_part) = if has_scheme(base) {
+        base.split_once(':').unwrap_or(("", base))
+    } else {
+        ("", base)
+    };
+    let scheme_prefix = if scheme.is_empty() {
+        String::new()
+    } else {
+        format!("{scheme}:")
+    };
+    if reference.starts_with("//") {
+        return format!("{scheme_prefix}{reference}");
+    }
+    let (authority, path) = match hierarchical_part.strip_prefix("//") {
+        Some(rest) => {
+            let path_start = rest.find('/').unwrap_or(rest.len());
+            (format!("//{}", &rest[..path_start]), &rest[path_start..])
+        }
+        None => (String::new(), hierarchical_part),
+    };
+    let merged_path = if reference.starts_with('/') {
+        reference.to_string()
+    } else if let Some(directory_end) = path.rfind('/') {
+        format!("{}{reference}", &path[..=directory_end])
+    } else if authority.is_empty() {
+        reference.to_string()
+    } else {
+        format!("/{reference}")
+    };
+    format!(
+        "{scheme_prefix}{authority}{}",
+        remove_dot_segments(&merged_path)
+    )
+}
+
+/// Value of the keyword defining the base URI of `schema`, if any
+fn schema_id(schema: &Value) -> Option<&str> {
+    schema
+        .get("$id")
+        .and_then(Value::as_str)
+        .or_else(|| schema.get("id").and_then(Value::as_str))
+}
+
+/// Visit `schema` and its subschemas (including the ones in `definitions`) with their base URI
+fn index_schema(
+    schema: &Value,
+    schema_pointer: &str,
+    base_uri: &str,
+    options: &Options,
+    bundler: &mut Bundler<'_>,
+) {
+    let mut base_uri = base_uri.to_string();
+    if let Some(id) = schema_id(schema) {
+        let uri = resolve_uri(&base_uri, id);
+        let (resource_uri, fragment) = split_fragment(&uri);
+        if fragment.is_empty() {
+            let _ = bundler
+                .resources
+                .insert(resource_uri.to_string(), schema_pointer.to_string());
+        } else {
+            // Plain name fragments identify the schema (ie. `{"$id": "#address"}`)
+            let _ = bundler
+                .anchors
+                .insert(uri.clone(), schema_pointer.to_string());
+        }
+        base_uri = resource_uri.to_string();
+    }
+    for_each_subschema_with_pointer(
+        schema,
+        schema_pointer,
+        &|keyword| options.is_custom_subschema_keyword(keyword),
+        &mut |subschema_pointer, subschema| {
+            index_schema(subschema, &subschema_pointer, &base_uri, options, bundler);
+        },
+    );
+}
+
+/// State of the bundling process
+struct Bundler<'r> {
+    resolver: &'r dyn Resolver,
+    /// JSON Pointer, within the bundled schema, of the documents (and embedded schemas with `$id`) by URI
+    resources: HashMap<String, String>,
+    /// JSON Pointer, within the bundled schema, of the schemas identified by a plain name fragment, by URI
+    anchors: HashMap<String, String>,
+    /// Names of the `definitions` of the bundled schema
+    definition_names: Vec<String>,
+    /// Retrieved documents, with their URI and `definitions` name, not yet embedded into the bundled schema
+    pending_documents: Vec<(String, String, Value)>,
+    /// Rewritten references, with the JSON Pointer of the schema containing them and their URI, whose target
+    /// has to be verified once all the documents are embedded into the bundled schema
+    rewritten_references: Vec<(String, String, String)>,
+}
+
+impl Bundler<'_> {
+    /// Name, not yet used by the `definitions` of the bundled schema, of the document identified by `uri`
+    fn definition_name(&mut self, uri: &str) -> String {
+        let file_name = uri
+            .rsplit('/')
+            .find(|segment| !segment.is_empty())
+            .unwrap_or("external");
+        let file_name = file_name.strip_suffix(".json").unwrap_or(file_name);
+        let mut name = file_name.to_string();
+        let mut counter: usize = 1;
+        while self.definition_names.contains(&name) {
+            counter = counter.saturating_add(1);
+            name = format!("{file_name}_{counter}");
+        }
+        self.definition_names.push(name.clone());
+        name
+    }
+
+    /// JSON Pointer, within the bundled schema, of the document identified by `uri` (without fragment).
+    /// The document is retrieved via the resolver if it is not yet part of the bundled schema.
+    fn resource_pointer(
+        &mut self,
+        uri: &str,
+        schema_pointer: &str,
+        options: &Options,
+    ) -> Result<String, Error> {
+        if let Some(resource_pointer) = self.resources.get(uri) {
+            return Ok(resource_pointer.clone());
+        }
+        let document =
+            self.resolver
+                .resolve(uri)
+                .map_err(|reason| Error::UnresolvableReference {
+                    pointer: schema_pointer.to_string(),
+                    reference: uri.to_string(),
+                    reason,
+                })?;
+        let name = self.definition_name(uri);
+        let resource_pointer = pointer::append("/definitions", &name);
+        let _ = self
+            .resources
+            .insert(uri.to_string(), resource_pointer.clone());
+        index_schema(&document, &resource_pointer, uri, options, self);
+        self.pending_documents
+            .push((uri.to_string(), name, document));
+        Ok(resource_pointer)
+    }
+
+    /// Rewrite the `$ref` of `schema` as JSON Pointer within the bundled schema
+    fn rewrite_reference(
+        &mut self,
+        reference: &str,
+        base_uri: &str,
+        schema_pointer: &str,
+        options: &Options,
+    ) -> Result<String, Error> {
+        let uri = resolve_uri(base_uri, reference);
+        let (resource_uri, fragment) = split_fragment(&uri);
+        let resource_pointer = self.resource_pointer(resource_uri, schema_pointer, options)?;
+        if fragment.is_empty() || fragment.starts_with('/') {
+            let reference = format!("#{resource_pointer}{fragment}");
+            self.rewritten_references.push((
+                schema_pointer.to_string(),
+                uri.clone(),
+                reference.clone(),
+            ));
+            Ok(reference)
+        } else if let Some(anchor_pointer) = self.anchors.get(&uri) {
+            Ok(format!("#{anchor_pointer}"))
+        } else {
+            Err(Error::UnresolvableReference {
+                pointer: schema_pointer.to_string(),
+                reference: uri.clone(),
+                reason: "Plain name fragment not defined".to_string(),
+            })
+        }
+    }
+
+    /// Rewrite the references of `schema` and its subschemas (including the ones in `definitions`)
+    /// as JSON Pointers within the bundled schema.
+    /// The `$id` keywords are removed, as they are not needed anymore, unless `is_root`.
+    fn rewrite_schema(
+        &mut self,
+        schema: &mut Value,
+        schema_pointer: &str,
+        base_uri: &str,
+        is_root: bool,
+        options: &Options,
+    ) -> Result<(), Error> {
+        let mut base_uri = base_uri.to_string();
+        if let Some(id) = schema_id(schema) {
+            base_uri = split_fragment(&resolve_uri(&base_uri, id)).0.to_string();
+        }
+        let Value::Object(schema_object) = schema else {
+            return Ok(());
+        };
+        if !is_root {
+            let _ = schema_object.remove("$id");
+            if schema_object.get("id").is_some_and(Value::is_string) {
+                let _ = schema_object.remove("id");
+            }
+        }
+        if let Some(Value::String(reference)) = schema_object.get_mut("$ref") {
+            *reference = self.rewrite_reference(reference, &base_uri, schema_pointer, options)?;
+        }
+
+        for (keyword, value) in schema_object.iter_mut() {
+            let keyword_pointer = pointer::append(schema_pointer, keyword);
+            let is_direct_subschema = crate::constants::KEYWORDS_WITH_DIRECT_SUBSCHEMAS
+                .contains(keyword.as_str())
+                || options.is_custom_subschema_keyword(keyword)
+                || (keyword == "items" && !value.is_array());
+            if is_direct_subschema {
+                self.rewrite_schema(value, &keyword_pointer, &base_uri, false, options)?;
+            } else if crate::constants::KEYWORDS_WITH_SUBSCHEMAS.contains(keyword.as_str())
+                || keyword == "definitions"
+            {
+                match value {
+                    Value::Object(object) => {
+                        for (key, subschema) in object {
+                            let subschema_pointer = pointer::append(&keyword_pointer, key);
+                            self.rewrite_schema(
+                                subschema,
+                                &subschema_pointer,
+                                &base_uri,
+                                false,
+                                options,
+                            )?;
+                        }
+                    }
+                    Value::Array(array) => {
+                        for (index, subschema) in array.iter_mut().enumerate() {
+                            let subschema_pointer =
+                                pointer::append(&keyword_pointer, &index.to_string());
+                            self.rewrite_schema(
+                                subschema,
+                                &subschema_pointer,
+                                &base_uri,
+                                false,
+                                options,
+                            )?;
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Bundle the documents referenced by `schema`, as [`bundle`], considering the keywords registered in `options`.
+///
+/// # Errors
+///
+/// See [`bundle`].
+pub fn bundle_with_options(
+    mut schema: Value,
+    base_uri: &str,
+    resolver: &dyn Resolver,
+    options: &Options,
+) -> Result<Value, Error> {
+    let base_uri = schema_id(&schema).map_or_else(
+        || split_fragment(base_uri).0.to_string(),
+        |id| split_fragment(&resolve_uri(base_uri, id)).0.to_string(),
+    );
+    let mut bundler = Bundler {
+        resolver,
+        resources: HashMap::new(),
+        anchors: HashMap::new(),
+        definition_names: schema
+            .get("definitions")
+            .and_then(Value::as_object)
+            .map(|definitions| definitions.keys().cloned().collect())
+            .unwrap_or_default(),
+        pending_documents: Vec::new(),
+        rewritten_references: Vec::new(),
+    };
+    index_schema(&schema, "", &base_uri, options, &mut bundler);
+    let _ = bundler.resources.insert(base_uri.clone(), String::new());
+
+    bundler.rewrite_schema(&mut schema, "", &base_uri, true, options)?;
+    let mut bundled_definitions = Map::new();
+    while let Some((uri, name, mut document)) = bundler.pending_documents.pop() {
+        let document_pointer = pointer::append("/definitions", &name);
+        bundler.rewrite_schema(&mut document, &document_pointer, &uri, false, options)?;
+        let _ = bundled_definitions.insert(name, document);
+    }
+
+    if !bundled_definitions.is_empty() {
+        if let Value::Object(schema_object) = &mut schema {
+            if let Value::Object(definitions) = schema_object
+                .entry("definitions")
+                .or_insert_with(|| Value::Object(Map::new()))
+            {
+                definitions.extend(bundled_definitions);
+            }
+        }
+    }
+
+    // The fragments are verified on the bundled schema, as the retrieved documents are embedded into it
+    for (schema_pointer, uri, reference) in bundler.rewritten_references {
+        if schema.pointer(&reference[1..]).is_none() {
+            return Err(Error::UnresolvableReference {
+                pointer: schema_pointer,
+                reference: uri,
+                reason: "Location not found".to_string(),
+            });
+        }
+    }
+    Ok(schema)
+}
+
+/// Bundle the documents referenced by `schema` into its `definitions`, retrieving them via `resolver`.
+///
+/// The references are resolved against `base_uri` (the URI of `schema`), as eventually modified by the `$id`
+/// (and Draft4 `id`) keywords, and rewritten as JSON Pointers within the bundled schema.
+/// ```rust
+/// use jsonschema_equivalent::{bundle, InMemoryResolver};
+/// use serde_json::json;
+///
+/// let resolver = InMemoryResolver::new().with_document(
+///     "https://example.com/common.json",
+///     json!({"definitions": {"Id": {"type": "integer", "minimum": 1}}}),
+/// );
+/// let schema = json!({"properties": {"id": {"$ref": "common.json#/definitions/Id"}}});
+/// assert_eq!(
+///     bundle(schema, "https://example.com/order.json", &resolver),
+///     Ok(json!({
+///         "properties": {"id": {"$ref": "#/definitions/common/definitions/Id"}},
+///         "definitions": {"common": {"definitions": {"Id": {"type": "integer", "minimum": 1}}}},
+///     })),
+/// );
+/// ```
+///
+/// # Errors
+///
+/// * [`Error::UnresolvableReference`] if a referenced document can not be retrieved, or the referenced location
+///   (ie. `#/definitions/Address`) is not present in the document, or a plain name fragment (ie. `#address`) is not defined.
+pub fn bundle(schema: Value, base_uri: &str, resolver: &dyn Resolver) -> Result<Value, Error> {
+    bundle_with_options(schema, base_uri, resolver, &Options::default())
+}
+
+/// Bundle the documents referenced by `schema` (see [`bundle`]) and optimise the bundled schema
+/// (see [`try_jsonschema_equivalent_with_options`]).
+///
+/// # Errors
+///
+/// See [`bundle`] and [`try_jsonschema_equivalent_with_options`].
+pub fn try_jsonschema_equivalent_with_resolver(
+    schema: Value,
+    base_uri: &str,
+    resolver: &dyn Resolver,
+    options: &Options,
+) -> Result<Value, Error> {
+    try_jsonschema_equivalent_with_options(
+        bundle_with_options(schema, base_uri, resolver, options)?,
+        options,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        bundle, resolve_uri, try_jsonschema_equivalent_with_resolver, FileSystemResolver,
+        InMemoryResolver, Resolver,
+    };
+    use crate::{Error, Options};
+    use serde_json::{json, Value};
+    use std::{
+        path::PathBuf,
+        sync::atomic::{AtomicUsize, Ordering},
+    };
+    use test_case::test_case;
+
+    /// Directory, unique to each test case, removed (with its content) once dropped
+    struct TemporaryDirectory(PathBuf);
+
+    impl TemporaryDirectory {
+        fn new() -> Self {
+            static COUNTER: AtomicUsize = AtomicUsize::new(0);
+            let path = std::env::temp_dir().join(format!(
+                "jsonschema-equivalent-file-system-resolver-{}-{}",
+                std::process::id(),
+                COUNTER.fetch_add(1, Ordering::Relaxed)
+            ));
+            std::fs::create_dir_all(&path).expect("Failed to create the directory");
+            Self(path)
+        }
+    }
+
+    impl Drop for TemporaryDirectory {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.0);
+        }
+    }
+
+    #[test_case("http://a/b/c/d;p?q", "g" => "http://a/b/c/g")]
+    #[test_case("http://a/b/c/d;p?q", "./g" => "http://a/b/c/g"; "current directory reference")]
+    #[test_case("http://a/b/c/d;p?q", "g/" => "http://a/b/c/g/"; "directory reference")]
+    #[test_case("http://a/b/c/d;p?q", "/g" => "http://a/g")]
+    #[test_case("http://a/b/c/d;p?q", "//g" => "http://g"; "network-path reference")]
+    #[test_case("http://a/b/c/d;p?q", "#s" => "http://a/b/c/d;p?q#s")]
+    #[test_case("http://a/b/c/d;p?q", "" => "http://a/b/c/d;p?q")]
+    #[test_case("http://a/b/c/d;p?q", "." => "http://a/b/c/")]
+    #[test_case("http://a/b/c/d;p?q", ".." => "http://a/b/")]
+    #[test_case("http://a/b/c/d;p?q", "../g" => "http://a/b/g")]
+    #[test_case("http://a/b/c/d;p?q", "../../../g" => "http://a/g"; "reference above the root")]
+    #[test_case("http://a/b/c/d;p?q", "g:h" => "g:h")]
+    #[test_case("http://a/b/c/d#f", "#/definitions/a" => "http://a/b/c/d#/definitions/a")]
+    #[test_case("file:///schemas/order.json", "common/id.json#/a" => "file:///schemas/common/id.json#/a")]
+    #[test_case("urn:example:a", "#b" => "urn:example:a#b")]
+    fn test_resolve_uri(base: &str, reference: &str) -> String {
+        resolve_uri(base, reference)
+    }
+
+    fn resolver() -> InMemoryResolver {
+        InMemoryResolver::new()
+            .with_document(
+                "https://example.com/common.json",
+                json!({"definitions": {"Id": {"type": "integer"}, "Ids": {"items": {"$ref": "#/definitions/Id"}}}}),
+            )
+            .with_document(
+                "https://example.com/nested/address.json",
+                json!({"$id": "https://example.com/nested/address.json", "properties": {"id": {"$ref": "../common.json#/definitions/Id"}}}),
+            )
+            .with_document(
+                "https://example.com/anchors.json",
+                json!({"definitions": {"street": {"$id": "#street", "type": "string"}}}),
+            )
+    }
+
+    #[test_case(&json!({"$ref": "#/definitions/a", "definitions": {"a": {}}}) => Ok(json!({"$ref": "#/definitions/a", "definitions": {"a": {}}})))]
+    #[test_case(
+        &json!({"items": {"$ref": "common.json#/definitions/Ids"}})
+        => Ok(json!({
+            "items": {"$ref": "#/definitions/common/definitions/Ids"},
+            "definitions": {"common": {"definitions": {"Id": {"type": "integer"}, "Ids": {"items": {"$ref": "#/definitions/common/definitions/Id"}}}}},
+        }))
+    )]
+    #[test_case(
+        &json!({"definitions": {"common": {}}, "not": {"$ref": "common.json"}})
+        => Ok(json!({
+            "not": {"$ref": "#/definitions/common_2"},
+            "definitions": {"common": {}, "common_2": {"definitions": {"Id": {"type": "integer"}, "Ids": {"items": {"$ref": "#/definitions/common_2/definitions/Id"}}}}},
+        }))
+    )]
+    #[test_case(
+        &json!({"$id": "https://example.com/nested/order.json", "properties": {"address": {"$ref": "address.json"}}})
+        => Ok(json!({
+            "$id": "https://example.com/nested/order.json",
+            "properties": {"address": {"$ref": "#/definitions/address"}},
+            "definitions": {
+                "address": {"properties": {"id": {"$ref": "#/definitions/common/definitions/Id"}}},
+                "common": {"definitions": {"Id": {"type": "integer"}, "Ids": {"items": {"$ref": "#/definitions/common/definitions/Id"}}}},
+            },
+        }))
+    )]
+    #[test_case(
+        &json!({"properties": {"a": {"$id": "https://other.com/a.json", "definitions": {"b": {}}, "items": {"$ref": "#/definitions/b"}}}})
+        => Ok(json!({"properties": {"a": {"definitions": {"b": {}}, "items": {"$ref": "#/properties/a/definitions/b"}}}}))
+    )]
+    #[test_case(
+        &json!({"not": {"$ref": "anchors.json#street"}})
+        => Ok(json!({
+            "not": {"$ref": "#/definitions/anchors/definitions/street"},
+            "definitions": {"anchors": {"definitions": {"street": {"type": "string"}}}},
+        }))
+    )]
+    #[test_case(
+        &json!({"not": {"$ref": "anchors.json#missing"}})
+        => Err(Error::UnresolvableReference {pointer: "/not".to_string(), reference: "https://example.com/anchors.json#missing".to_string(), reason: "Plain name fragment not defined".to_string()})
+    )]
+    #[test_case(
+        &json!({"not": {"$ref": "common.json#/definitions/Missing"}})
+        => Err(Error::UnresolvableReference {pointer: "/not".to_string(), reference: "https://example.com/common.json#/definitions/Missing".to_string(), reason: "Location not found".to_string()})
+    )]
+    #[test_case(
+        &json!({"not": {"$ref": "#/definitions/Missing"}})
+        => Err(Error::UnresolvableReference {pointer: "/not".to_string(), reference: "https://example.com/order.json#/definitions/Missing".to_string(), reason: "Location not found".to_string()})
+    )]
+    #[test_case(
+        &json!({"properties": {"a": {"$ref": "missing.json"}}})
+        => Err(Error::UnresolvableReference {pointer: "/properties/a".to_string(), reference: "https://example.com/missing.json".to_string(), reason: "Document not registered".to_string()})
+    )]
+    fn test_bundle(schema: &Value) -> Result<Value, Error> {
+        bundle(
+            schema.clone(),
+            "https://example.com/order.json",
+            &resolver(),
+        )
+    }
+
+    #[test]
+    fn test_bundle_preserves_validation() {
+        let schema = json!({"$id": "https://example.com/nested/order.json", "properties": {"address": {"$ref": "address.json"}}});
+        let bundled_schema =
+            bundle(schema, "https://example.com/order.json", &resolver()).expect("Bundle failed");
+        for (instance, is_valid) in &[
+            (json!({"address": {"id": 1}}), true),
+            (json!({"address": {"id": "1"}}), false),
+        ] {
+            assert_eq!(
+                jsonschema::is_valid(&bundled_schema, instance),
+                *is_valid,
+                "{bundled_schema} on {instance}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_try_jsonschema_equivalent_with_resolver() {
+        let schema = json!({"allOf": [{"$ref": "common.json#/definitions/Id"}], "minLength": 1});
+        assert_eq!(
+            try_jsonschema_equivalent_with_resolver(
+                schema,
+                "https://example.com/order.json",
+                &resolver(),
+                &Options::default()
+            ),
+            Ok(json!({
+                "$ref": "#/definitions/common/definitions/Id",
+                "minLength": 1,
+                "definitions": {"common": {"definitions": {"Id": {"type": "integer"}, "Ids": {"items": {"$ref": "#/definitions/common/definitions/Id"}}}}},
+            }))
+        );
+    }
+
+    #[test_case("file:///common.json" => Ok(json!({"type": "integer"})))]
+    #[test_case("file:///nested/../common.json" => Err("URI is not within the directory".to_string()))]
+    #[test_case("https://example.com/common.json" => Err(r#"URI is not within "file:///""#.to_string()))]
+    fn test_file_system_resolver(uri: &str) -> Result<Value, String> {
+        let directory = TemporaryDirectory::new();
+        std::fs::write(directory.0.join("common.json"), r#"{"type": "integer"}"#)
+            .expect("Failed to write the document");
+        FileSystemResolver::new(directory.0.clone()).resolve(uri)
+    }
+
+    #[test]
+    fn test_file_system_resolver_with_base_uri() {
+        let directory = TemporaryDirectory::new();
+        std::fs::create_dir_all(directory.0.join("common"))
+            .expect("Failed to create the directory");
+        std::fs::write(
+            directory.0.join("common").join("id.json"),
+            r#"{"type": "integer"}"#,
+        )
+        .expect("Failed to write the document");
+        let resolver = FileSystemResolver::new(directory.0.clone())
+            .with_base_uri("https://example.com/schemas/");
+        assert_eq!(
+            bundle(
+                json!({"$ref": "common/id.json"}),
+                "https://example.com/schemas/order.json",
+                &resolver
+            ),
+            Ok(json!({"$ref": "#/definitions/id", "definitions": {"id": {"type": "integer"}}}))
+        );
+    }
+}
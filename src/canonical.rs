@@ -0,0 +1,144 @@
+//! Canonical form of JSON Schemas.
+//!
+//! Equivalent schemas might differ only by the order of items whose order is not relevant for the
+//! validation (ie. the items of `required`). The canonical form sorts such items, as well as the keys
+//! of the objects, in order to make them structurally comparable and to serialise them identically.
+use crate::{constants::KEYWORDS_WITH_DIRECT_SUBSCHEMAS, helpers::pointer};
+use serde_json::{Map, Value};
+
+/// Keywords whose value is an array of schemas
+static KEYWORDS_WITH_ARRAY_OF_SUBSCHEMAS: &[&str] = &["allOf", "anyOf", "items", "oneOf"];
+
+/// Keywords whose value is an object with schemas as values
+static KEYWORDS_WITH_OBJECT_OF_SUBSCHEMAS: &[&str] = &[
+    "definitions",
+    "dependencies",
+    "patternProperties",
+    "properties",
+];
+
+/// Sort `values` according to their serialisation, eventually removing the duplicated ones
+fn sort_values(values: &mut Vec<Value>, deduplicate: bool) {
+    values.sort_by_cached_key(ToString::to_string);
+    if deduplicate {
+        values.dedup();
+    }
+}
+
+/// Sort the keys of all the objects contained in `value`.
+///
+/// NOTE: This is a no-op if `serde_json` is not compiled with the `preserve_order` feature
+/// as the keys of `Map` are already sorted.
+fn sort_keys(value: &mut Value) {
+    match value {
+        Value::Array(items) => items.iter_mut().for_each(sort_keys),
+        Value::Object(object) => {
+            let mut entries: Vec<(String, Value)> = std::mem::take(object).into_iter().collect();
+            entries.sort_by(|(key1, _), (key2, _)| key1.cmp(key2));
+            *object = entries.into_iter().collect::<Map<String, Value>>();
+            object.values_mut().for_each(sort_keys);
+        }
+        _ => {}
+    }
+}
+
+/// Sort the items of the keywords whose order is not relevant for the validation.
+///
+/// The items of arrays containing the targets of local references (ie. `#/anyOf/0`) are not sorted,
+/// as moving them would alter the subschemas resolved by the references.
+fn sort_keywords_values(schema: &mut Value, schema_pointer: &str, reference_targets: &[String]) {
+    let Value::Object(schema_object) = schema else {
+        return;
+    };
+    for (keyword, value) in schema_object.iter_mut() {
+        let keyword_pointer = pointer::append(schema_pointer, keyword);
+        if KEYWORDS_WITH_DIRECT_SUBSCHEMAS.contains(keyword.as_str()) {
+            sort_keywords_values(value, &keyword_pointer, reference_targets);
+        } else if KEYWORDS_WITH_ARRAY_OF_SUBSCHEMAS.contains(&keyword.as_str()) {
+            if let Value::Array(subschemas) = value {
+                for (index, subschema) in subschemas.iter_mut().enumerate() {
+                    let subschema_pointer = pointer::append(&keyword_pointer, &index.to_string());
+                    sort_keywords_values(subschema, &subschema_pointer, reference_targets);
+                }
+            } else {
+                // `items` could be a single schema
+                sort_keywords_values(value, &keyword_pointer, reference_targets);
+            }
+        } else if KEYWORDS_WITH_OBJECT_OF_SUBSCHEMAS.contains(&keyword.as_str()) {
+            if let Value::Object(subschemas) = value {
+                for (name, subschema) in subschemas.iter_mut() {
+                    let subschema_pointer = pointer::append(&keyword_pointer, name);
+                    sort_keywords_values(subschema, &subschema_pointer, reference_targets);
+                }
+            }
+        }
+
+        if pointer::is_targeted(&keyword_pointer, reference_targets) {
+            continue;
+        }
+        match (keyword.as_str(), value) {
+            // The order of the items is not relevant and duplicates are not altering the validation
+            ("allOf" | "anyOf" | "enum" | "required" | "type", Value::Array(items)) => {
+                sort_values(items, true);
+            }
+            // Duplicated branches are relevant for `oneOf` as they make the matching instances invalid
+            ("oneOf", Value::Array(items)) => sort_values(items, false),
+            _ => {}
+        }
+    }
+}
+
+/// Transform `schema`, in-place, into its canonical form.
+///
+/// The canonical form sorts
+/// * the keys of all the objects
+/// * the items of `required`, `type` and `enum` (removing duplicates)
+/// * the branches of `allOf` and `anyOf` (removing duplicates) and `oneOf`
+///
+/// The canonicalisation does not alter the validation outcome of `schema`, so two schemas that differ
+/// only on the order of such items are serialised identically after canonicalisation.
+/// The items of the arrays targeted by local references (ie. `#/anyOf/0`) are left in their original order.
+/// ```rust
+/// use jsonschema_equivalent::canonicalise;
+/// use serde_json::json;
+///
+/// let mut schema = json!({"required": ["b", "a"], "anyOf": [{"minimum": 1}, {"type": "string"}]});
+/// canonicalise(&mut schema);
+/// assert_eq!(schema, json!({"anyOf": [{"minimum": 1}, {"type": "string"}], "required": ["a", "b"]}));
+/// ```
+pub fn canonicalise(schema: &mut Value) {
+    sort_keys(schema);
+    let mut reference_targets = Vec::new();
+    pointer::collect_reference_targets(schema, &mut reference_targets);
+    sort_keywords_values(schema, "", &reference_targets);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::canonicalise;
+    use serde_json::{json, Value};
+    use test_case::test_case;
+
+    #[test_case(json!(true) => json!(true))]
+    #[test_case(json!({"type": ["string", "null"]}) => json!({"type": ["null", "string"]}))]
+    #[test_case(json!({"required": ["b", "a", "b"]}) => json!({"required": ["a", "b"]}))]
+    #[test_case(json!({"enum": [2, "a", 1, 2]}) => json!({"enum": ["a", 1, 2]}))]
+    #[test_case(json!({"anyOf": [{"type": "string"}, {"type": "null"}]}) => json!({"anyOf": [{"type": "null"}, {"type": "string"}]}))]
+    #[test_case(json!({"oneOf": [{"type": "string"}, {"type": "null"}, {"type": "string"}]}) => json!({"oneOf": [{"type": "null"}, {"type": "string"}, {"type": "string"}]}))]
+    #[test_case(json!({"properties": {"a": {"required": ["b", "a"]}}}) => json!({"properties": {"a": {"required": ["a", "b"]}}}))]
+    #[test_case(json!({"items": [{"type": ["string", "null"]}, true]}) => json!({"items": [{"type": ["null", "string"]}, true]}))]
+    #[test_case(json!({"const": {"required": ["b", "a"]}}) => json!({"const": {"required": ["b", "a"]}}))]
+    #[test_case(json!({"dependencies": {"a": ["c", "b"]}}) => json!({"dependencies": {"a": ["c", "b"]}}))]
+    #[test_case(
+        json!({"anyOf": [{"type": "string"}, {"type": "integer"}], "properties": {"a": {"$ref": "#/anyOf/0"}}})
+        => json!({"anyOf": [{"type": "string"}, {"type": "integer"}], "properties": {"a": {"$ref": "#/anyOf/0"}}})
+    )]
+    #[test_case(
+        json!({"properties": {"a": {"allOf": [{"type": "string"}, {"type": "string"}, {"required": ["b", "a"]}]}, "b": {"$ref": "#/properties/a/allOf/2"}}})
+        => json!({"properties": {"a": {"allOf": [{"type": "string"}, {"type": "string"}, {"required": ["a", "b"]}]}, "b": {"$ref": "#/properties/a/allOf/2"}}})
+    )]
+    fn test_canonicalise(mut schema: Value) -> Value {
+        canonicalise(&mut schema);
+        schema
+    }
+}
@@ -0,0 +1,599 @@
+//! Backward-compatibility analysis of schema evolutions.
+//!
+//! A change from an old schema to a new schema is considered breaking if there are instances
+//! that are valid against the old schema and are not valid against the new one.
+//!
+//! Both the schemas are optimised, via [`crate::jsonschema_equivalent`], before
+//! being compared. This makes the comparison robust against cosmetic rewrites (ie. `{"type": ["integer", "number"]}`
+//! and `{"type": "number"}` are equivalent).
+//!
+//! ```rust
+//! use jsonschema_equivalent::compat::{breaking_changes, BreakingChangeKind};
+//! use serde_json::json;
+//!
+//! let old = json!({"type": "object", "properties": {"age": {"type": "number"}}});
+//! let new = json!({"type": "object", "properties": {"age": {"type": "integer"}}, "required": ["age"]});
+//! let changes = breaking_changes(&old, &new);
+//! assert_eq!(changes.len(), 2);
+//! assert_eq!(changes[0].pointer, "");
+//! assert_eq!(changes[0].kind, BreakingChangeKind::NewRequiredProperty { property: "age".to_string() });
+//! assert_eq!(changes[1].pointer, "/properties/age");
+//! assert_eq!(changes[1].kind, BreakingChangeKind::NarrowedType { removed_types: vec!["number".to_string()] });
+//! ```
+use crate::{
+    helpers::{is, pointer, types::PrimitiveTypesBitMap},
+    inclusion::{is_subschema, Inclusion},
+    jsonschema_equivalent,
+    primitive_type::PrimitiveType,
+    satisfiability::same_value,
+};
+use serde_json::{Map, Value};
+use std::{cmp::Ordering, collections::BTreeSet, fmt};
+
+/// Kind of breaking change
+#[derive(Clone, Debug, PartialEq)]
+pub enum BreakingChangeKind {
+    /// Some primitive types are no longer accepted
+    NarrowedType {
+        /// Primitive types that are no longer accepted (`number` represents the non integer numbers)
+        removed_types: Vec<String>,
+    },
+    /// A property became required
+    NewRequiredProperty {
+        /// Name of the required property
+        property: String,
+    },
+    /// A property is no longer allowed, as it has been removed from `properties` and `additionalProperties` is `false`
+    RemovedProperty {
+        /// Name of the removed property
+        property: String,
+    },
+    /// `additionalProperties` became `false`
+    AdditionalPropertiesForbidden,
+    /// A bound keyword (ie. `maximum` or `minLength`) became more restrictive
+    TightenedBound {
+        /// Keyword that defines the new bound
+        keyword: String,
+        /// Value of the bound in the old schema, if defined
+        old_value: Option<Value>,
+        /// Value of the bound in the new schema
+        new_value: Value,
+    },
+    /// A value is no longer listed in `enum` (or `const`)
+    RemovedEnumValue {
+        /// Value no longer accepted
+        value: Value,
+    },
+    /// The schema accepts less instances, but the change is not recognised as one of the other kinds
+    Narrowed {
+        /// Instance valid against the old schema and not valid against the new one, if found
+        counterexample: Option<Value>,
+    },
+    /// It was not possible to verify that the change is backward-compatible
+    Unknown,
+}
+
+/// Breaking change of a schema, located by the JSON Pointer of the affected (new) subschema
+#[derive(Clone, Debug, PartialEq)]
+pub struct BreakingChange {
+    /// JSON Pointer of the subschema affected by the change
+    pub pointer: String,
+    /// Description of the change
+    pub kind: BreakingChangeKind,
+}
+
+impl fmt::Display for BreakingChange {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(formatter, "{}: ", self.pointer)?;
+        match &self.kind {
+            BreakingChangeKind::NarrowedType { removed_types } => {
+                write!(formatter, "types {removed_types:?} are no longer accepted")
+            }
+            BreakingChangeKind::NewRequiredProperty { property } => {
+                write!(formatter, "property {property:?} is now required")
+            }
+            BreakingChangeKind::RemovedProperty { property } => {
+                write!(formatter, "property {property:?} is no longer allowed")
+            }
+            BreakingChangeKind::AdditionalPropertiesForbidden => {
+                write!(formatter, "additional properties are no longer allowed")
+            }
+            BreakingChangeKind::TightenedBound {
+                keyword,
+                old_value: Some(old_value),
+                new_value,
+            } => write!(
+                formatter,
+                "{keyword} changed from {old_value} to {new_value}"
+            ),
+            BreakingChangeKind::TightenedBound {
+                keyword,
+                old_value: None,
+                new_value,
+            } => write!(formatter, "{keyword} set to {new_value}"),
+            BreakingChangeKind::RemovedEnumValue { value } => {
+                write!(formatter, "value {value} is no longer accepted")
+            }
+            BreakingChangeKind::Narrowed {
+                counterexample: Some(counterexample),
+            } => write!(formatter, "instance {counterexample} is no longer accepted"),
+            BreakingChangeKind::Narrowed {
+                counterexample: None,
+            } => write!(formatter, "some instances are no longer accepted"),
+            BreakingChangeKind::Unknown => write!(
+                formatter,
+                "the change could not be verified to be backward-compatible"
+            ),
+        }
+    }
+}
+
+/// Keywords defining an upper bound (the most restrictive value is the smallest one)
+static UPPER_BOUND_KEYWORDS: &[&str] = &["maxItems", "maxLength", "maxProperties"];
+
+/// Keywords defining a lower bound (the most restrictive value is the biggest one)
+static LOWER_BOUND_KEYWORDS: &[&str] = &["minItems", "minLength", "minProperties"];
+
+/// Numeric bound defined by `maximum`/`exclusiveMaximum` or `minimum`/`exclusiveMinimum` keywords
+#[derive(Clone, Copy, Debug)]
+struct NumericBound<'s> {
+    keyword: &'static str,
+    value: &'s Value,
+    exclusive: bool,
+}
+
+impl NumericBound<'_> {
+    /// Extract the most restrictive numeric bound defined by `schema_object`.
+    /// Draft4 boolean `exclusiveMaximum` and `exclusiveMinimum` are considered as modifiers
+    fn from_schema_object(
+        schema_object: &Map<String, Value>,
+        upper: bool,
+    ) -> Option<NumericBound<'_>> {
+        let (keyword, exclusive_keyword) = if upper {
+            ("maximum", "exclusiveMaximum")
+        } else {
+            ("minimum", "exclusiveMinimum")
+        };
+        let inclusive_bound = schema_object.get(keyword).map(|value| NumericBound {
+            keyword,
+            value,
+            exclusive: schema_object.get(exclusive_keyword) == Some(&Value::Bool(true)),
+        });
+        let exclusive_bound = schema_object
+            .get(exclusive_keyword)
+            .filter(|value| value.is_number())
+            .map(|value| NumericBound {
+                keyword: exclusive_keyword,
+                value,
+                exclusive: true,
+            });
+        match (inclusive_bound, exclusive_bound) {
+            (Some(inclusive_bound), Some(exclusive_bound)) => {
+                if exclusive_bound.is_tighter_than(&inclusive_bound, upper) {
+                    Some(exclusive_bound)
+                } else {
+                    Some(inclusive_bound)
+                }
+            }
+            (bound, None) | (None, bound) => bound,
+        }
+    }
+
+    fn is_tighter_than(&self, other: &Self, upper: bool) -> bool {
+        match (self.value.as_f64(), other.value.as_f64()) {
+            (Some(value), Some(other_value)) => match value.partial_cmp(&other_value) {
+                Some(Ordering::Equal) => self.exclusive && !other.exclusive,
+                Some(ordering) => (ordering == Ordering::Less) == upper,
+                None => false,
+            },
+            _ => false,
+        }
+    }
+}
+
+/// Values accepted according to `const` and `enum` keywords
+fn enumerated_values(schema_object: &Map<String, Value>) -> Option<Vec<&Value>> {
+    if let Some(value) = schema_object.get("const") {
+        Some(vec![value])
+    } else if let Some(Value::Array(values)) = schema_object.get("enum") {
+        Some(values.iter().collect())
+    } else {
+        None
+    }
+}
+
+/// Types accepted according to `type` keyword, restricted to the types of the values accepted by `const` and `enum` keywords
+fn allowed_types(schema_object: &Map<String, Value>) -> PrimitiveTypesBitMap {
+    let types = PrimitiveTypesBitMap::from_schema_value(schema_object.get("type"));
+    enumerated_values(schema_object).map_or(types, |values| {
+        values
+            .iter()
+            .fold(PrimitiveTypesBitMap::default(), |values_types, value| {
+                values_types | PrimitiveTypesBitMap::from(PrimitiveType::from_serde_value(value))
+            })
+            & types
+    })
+}
+
+/// Collect the breaking changes related to the validation keywords of the schema
+fn keywords_breaking_changes(
+    old_schema_object: &Map<String, Value>,
+    new_schema_object: &Map<String, Value>,
+    pointer: &str,
+    changes: &mut Vec<BreakingChange>,
+) {
+    let mut add_change = |kind| {
+        changes.push(BreakingChange {
+            pointer: pointer.to_string(),
+            kind,
+        });
+    };
+
+    let old_types = allowed_types(old_schema_object);
+    let new_types = allowed_types(new_schema_object);
+    let mut removed_types = old_types;
+    removed_types.remove_all(new_types);
+    if !removed_types.is_empty() {
+        add_change(BreakingChangeKind::NarrowedType {
+            removed_types: BTreeSet::<PrimitiveType>::from(removed_types)
+                .iter()
+                .map(ToString::to_string)
+                .collect(),
+        });
+    }
+    let common_types = old_types & new_types;
+
+    if common_types.contains(PrimitiveType::Integer) || common_types.contains(PrimitiveType::Number)
+    {
+        for upper in &[true, false] {
+            if let Some(new_bound) = NumericBound::from_schema_object(new_schema_object, *upper) {
+                let old_bound = NumericBound::from_schema_object(old_schema_object, *upper);
+                if old_bound.is_none_or(|old_bound| new_bound.is_tighter_than(&old_bound, *upper)) {
+                    add_change(BreakingChangeKind::TightenedBound {
+                        keyword: new_bound.keyword.to_string(),
+                        old_value: old_bound.map(|old_bound| old_bound.value.clone()),
+                        new_value: new_bound.value.clone(),
+                    });
+                }
+            }
+        }
+    }
+
+    for (keywords, upper) in &[(UPPER_BOUND_KEYWORDS, true), (LOWER_BOUND_KEYWORDS, false)] {
+        for keyword in *keywords {
+            if let Some(new_value) = new_schema_object.get(*keyword) {
+                let old_value = old_schema_object.get(*keyword);
+                let is_tightened = match (old_value.and_then(Value::as_f64), new_value.as_f64()) {
+                    (Some(old), Some(new)) => {
+                        new.partial_cmp(&old)
+                            == Some(if *upper {
+                                Ordering::Less
+                            } else {
+                                Ordering::Greater
+                            })
+                    }
+                    (None, _) => true,
+                    _ => false,
+                };
+                if is_tightened {
+                    add_change(BreakingChangeKind::TightenedBound {
+                        keyword: (*keyword).to_string(),
+                        old_value: old_value.cloned(),
+                        new_value: new_value.clone(),
+                    });
+                }
+            }
+        }
+    }
+
+    if let (Some(old_values), Some(new_values)) = (
+        enumerated_values(old_schema_object),
+        enumerated_values(new_schema_object),
+    ) {
+        for old_value in old_values {
+            if !new_values
+                .iter()
+                .any(|new_value| same_value(old_value, new_value))
+            {
+                add_change(BreakingChangeKind::RemovedEnumValue {
+                    value: old_value.clone(),
+                });
+            }
+        }
+    }
+
+    if common_types.contains(PrimitiveType::Object) {
+        object_keywords_breaking_changes(old_schema_object, new_schema_object, pointer, changes);
+    }
+}
+
+/// Collect the breaking changes related to the validation keywords of objects
+fn object_keywords_breaking_changes(
+    old_schema_object: &Map<String, Value>,
+    new_schema_object: &Map<String, Value>,
+    pointer: &str,
+    changes: &mut Vec<BreakingChange>,
+) {
+    let mut add_change = |kind| {
+        changes.push(BreakingChange {
+            pointer: pointer.to_string(),
+            kind,
+        });
+    };
+
+    let old_required: BTreeSet<&str> = old_schema_object
+        .get("required")
+        .and_then(Value::as_array)
+        .map(|required| required.iter().filter_map(Value::as_str).collect())
+        .unwrap_or_default();
+    if let Some(Value::Array(new_required)) = new_schema_object.get("required") {
+        for property in new_required.iter().filter_map(Value::as_str) {
+            if !old_required.contains(property) {
+                add_change(BreakingChangeKind::NewRequiredProperty {
+                    property: property.to_string(),
+                });
+            }
+        }
+    }
+
+    let has_new_pattern_properties = new_schema_object
+        .get("patternProperties")
+        .and_then(Value::as_object)
+        .is_some_and(|pattern_properties| !pattern_properties.is_empty());
+    if new_schema_object.get("additionalProperties") == Some(&Value::Bool(false))
+        && !has_new_pattern_properties
+    {
+        if old_schema_object.get("additionalProperties") != Some(&Value::Bool(false)) {
+            add_change(BreakingChangeKind::AdditionalPropertiesForbidden);
+        }
+        let new_properties = new_schema_object
+            .get("properties")
+            .and_then(Value::as_object);
+        if let Some(Value::Object(old_properties)) = old_schema_object.get("properties") {
+            for property in old_properties.keys() {
+                if !new_properties
+                    .is_some_and(|new_properties| new_properties.contains_key(property))
+                {
+                    add_change(BreakingChangeKind::RemovedProperty {
+                        property: property.clone(),
+                    });
+                }
+            }
+        }
+    }
+}
+
+/// Collect the breaking changes of the subschemas (`properties`, `items`, etc.)
+fn subschemas_breaking_changes(
+    old_schema_object: &Map<String, Value>,
+    new_schema_object: &Map<String, Value>,
+    pointer: &str,
+    changes: &mut Vec<BreakingChange>,
+) {
+    let true_schema = Value::Bool(true);
+    let old_additional_properties = old_schema_object
+        .get("additionalProperties")
+        .unwrap_or(&true_schema);
+    let has_old_pattern_properties = old_schema_object
+        .get("patternProperties")
+        .and_then(Value::as_object)
+        .is_some_and(|pattern_properties| !pattern_properties.is_empty());
+
+    if let Some(Value::Object(new_properties)) = new_schema_object.get("properties") {
+        let properties_pointer = pointer::append(pointer, "properties");
+        for (property, new_property_schema) in new_properties {
+            let old_property_schema = old_schema_object
+                .get("properties")
+                .and_then(|old_properties| old_properties.get(property));
+            let old_property_schema = match old_property_schema {
+                Some(old_property_schema) => old_property_schema,
+                // We don't know which `patternProperties` would apply
+                None if has_old_pattern_properties => continue,
+                None => old_additional_properties,
+            };
+            collect_breaking_changes(
+                old_property_schema,
+                new_property_schema,
+                &pointer::append(&properties_pointer, property),
+                changes,
+            );
+        }
+    }
+    if let Some(new_additional_properties) = new_schema_object.get("additionalProperties") {
+        if new_additional_properties.is_object() {
+            collect_breaking_changes(
+                old_additional_properties,
+                new_additional_properties,
+                &pointer::append(pointer, "additionalProperties"),
+                changes,
+            );
+        }
+    }
+
+    match (
+        old_schema_object.get("items"),
+        new_schema_object.get("items"),
+    ) {
+        (Some(Value::Array(old_items)), Some(Value::Array(new_items))) => {
+            let items_pointer = pointer::append(pointer, "items");
+            for (index, (old_item, new_item)) in old_items.iter().zip(new_items).enumerate() {
+                collect_breaking_changes(
+                    old_item,
+                    new_item,
+                    &pointer::append(&items_pointer, &index.to_string()),
+                    changes,
+                );
+            }
+        }
+        (Some(old_items), Some(new_items)) if !old_items.is_array() && !new_items.is_array() => {
+            collect_breaking_changes(
+                old_items,
+                new_items,
+                &pointer::append(pointer, "items"),
+                changes,
+            );
+        }
+        (None, Some(new_items)) if !new_items.is_array() => {
+            collect_breaking_changes(
+                &true_schema,
+                new_items,
+                &pointer::append(pointer, "items"),
+                changes,
+            );
+        }
+        _ => {}
+    }
+}
+
+fn collect_breaking_changes(
+    old_schema: &Value,
+    new_schema: &Value,
+    pointer: &str,
+    changes: &mut Vec<BreakingChange>,
+) {
+    let inclusion = is_subschema(old_schema, new_schema);
+    if inclusion == Inclusion::Yes {
+        return;
+    }
+
+    let changes_count = changes.len();
+    if let (Value::Object(old_schema_object), Value::Object(new_schema_object)) =
+        (old_schema, new_schema)
+    {
+        keywords_breaking_changes(old_schema_object, new_schema_object, pointer, changes);
+        subschemas_breaking_changes(old_schema_object, new_schema_object, pointer, changes);
+    } else if is::true_schema(old_schema) {
+        if let Value::Object(new_schema_object) = new_schema {
+            keywords_breaking_changes(&Map::new(), new_schema_object, pointer, changes);
+            subschemas_breaking_changes(&Map::new(), new_schema_object, pointer, changes);
+        }
+    }
+
+    if changes.len() == changes_count {
+        // No specific change has been recognised, so we report the outcome of the inclusion analysis
+        changes.push(BreakingChange {
+            pointer: pointer.to_string(),
+            kind: match inclusion {
+                Inclusion::No(counterexample) => BreakingChangeKind::Narrowed { counterexample },
+                _ => BreakingChangeKind::Unknown,
+            },
+        });
+    }
+}
+
+/// Report the breaking changes introduced by evolving `old_schema` into `new_schema`.
+///
+/// An empty result means that all the instances valid against `old_schema` are valid against `new_schema`.
+#[must_use]
+pub fn breaking_changes(old_schema: &Value, new_schema: &Value) -> Vec<BreakingChange> {
+    let old_schema = jsonschema_equivalent(old_schema.clone());
+    let new_schema = jsonschema_equivalent(new_schema.clone());
+    let mut changes = Vec::new();
+    collect_breaking_changes(&old_schema, &new_schema, "", &mut changes);
+    changes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{breaking_changes, BreakingChange, BreakingChangeKind};
+    use serde_json::{json, Value};
+    use test_case::test_case;
+
+    fn change(pointer: &str, kind: BreakingChangeKind) -> BreakingChange {
+        BreakingChange {
+            pointer: pointer.to_string(),
+            kind,
+        }
+    }
+
+    #[test_case(&json!({"type": "string"}), &json!({"type": "string"}))]
+    #[test_case(&json!({"type": ["integer", "number"]}), &json!({"type": "number"}))]
+    #[test_case(&json!({"type": "integer"}), &json!({"type": "number"}))]
+    #[test_case(&json!({"maximum": 10}), &json!({"exclusiveMaximum": 11}))]
+    #[test_case(&json!({"type": "number", "minimum": 2e-17}), &json!({"type": "number", "minimum": 1e-17}))]
+    #[test_case(&json!({"required": ["a", "b"]}), &json!({"required": ["a"]}))]
+    #[test_case(&json!({"enum": [1, 2]}), &json!({"enum": [1, 2, 3]}))]
+    #[test_case(&json!({"enum": ["a"]}), &json!({"const": "a", "type": "string"}))]
+    #[test_case(&json!({"properties": {"a": {"type": "string", "minimum": 1}}}), &json!({"properties": {"a": {"type": "string"}}}))]
+    fn test_breaking_changes_compatible(old_schema: &Value, new_schema: &Value) {
+        crate::init_logger();
+        assert_eq!(breaking_changes(old_schema, new_schema), vec![]);
+    }
+
+    #[test_case(
+        &json!({"type": ["number", "string"]}),
+        &json!({"type": "integer"})
+        => vec![change("", BreakingChangeKind::NarrowedType {removed_types: vec!["number".to_string(), "string".to_string()]})]
+    )]
+    #[test_case(
+        &json!({"type": "object", "required": ["a"]}),
+        &json!({"type": "object", "required": ["a", "b"]})
+        => vec![change("", BreakingChangeKind::NewRequiredProperty {property: "b".to_string()})]
+    )]
+    #[test_case(
+        &json!({"type": "number", "maximum": 10}),
+        &json!({"type": "number", "maximum": 5})
+        => vec![change("", BreakingChangeKind::TightenedBound {keyword: "maximum".to_string(), old_value: Some(json!(10)), new_value: json!(5)})]
+    )]
+    #[test_case(
+        &json!({"type": "number", "minimum": 1e-17}),
+        &json!({"type": "number", "minimum": 2e-17})
+        => vec![change("", BreakingChangeKind::TightenedBound {keyword: "minimum".to_string(), old_value: Some(json!(1e-17)), new_value: json!(2e-17)})]
+    )]
+    #[test_case(
+        &json!({"type": "number", "maximum": 10}),
+        &json!({"type": "number", "exclusiveMaximum": 10})
+        => vec![change("", BreakingChangeKind::TightenedBound {keyword: "exclusiveMaximum".to_string(), old_value: Some(json!(10)), new_value: json!(10)})]
+    )]
+    #[test_case(
+        &json!({"type": "string"}),
+        &json!({"type": "string", "minLength": 1})
+        => vec![change("", BreakingChangeKind::TightenedBound {keyword: "minLength".to_string(), old_value: None, new_value: json!(1)})]
+    )]
+    #[test_case(
+        &json!({"enum": ["a", "b", "c"]}),
+        &json!({"enum": ["a"]})
+        => vec![change("", BreakingChangeKind::RemovedEnumValue {value: json!("b")}), change("", BreakingChangeKind::RemovedEnumValue {value: json!("c")})]
+    )]
+    #[test_case(
+        &json!({"type": "object", "properties": {"a": true, "b": true}}),
+        &json!({"type": "object", "properties": {"a": true}, "additionalProperties": false})
+        => vec![change("", BreakingChangeKind::AdditionalPropertiesForbidden), change("", BreakingChangeKind::RemovedProperty {property: "b".to_string()})]
+    )]
+    #[test_case(
+        &json!({"type": "object", "properties": {"a/b": {"type": "array", "items": {"type": "number"}}}}),
+        &json!({"type": "object", "properties": {"a/b": {"type": "array", "items": {"type": "integer"}}}})
+        => vec![change("/properties/a~1b/items", BreakingChangeKind::NarrowedType {removed_types: vec!["number".to_string()]})]
+    )]
+    #[test_case(
+        &json!({"type": "string"}),
+        &json!({"type": "string", "not": {"const": ""}})
+        => vec![change("", BreakingChangeKind::Narrowed {counterexample: Some(json!(""))})]
+    )]
+    #[test_case(
+        &json!({"type": "string"}),
+        &json!({"type": "string", "pattern": "^a"})
+        => vec![change("", BreakingChangeKind::Unknown)]
+    )]
+    #[test_case(
+        &json!({"$ref": "#/definitions/e", "definitions": {"e": {"required": ["kind"], "type": "object"}}, "type": "string"}),
+        &json!({"required": ["a"]})
+        => vec![change("", BreakingChangeKind::Unknown)]
+    )]
+    fn test_breaking_changes(old_schema: &Value, new_schema: &Value) -> Vec<BreakingChange> {
+        crate::init_logger();
+        breaking_changes(old_schema, new_schema)
+    }
+
+    #[test]
+    fn test_breaking_change_display() {
+        let changes = breaking_changes(
+            &json!({"properties": {"a": {"maxLength": 5, "type": "string"}}}),
+            &json!({"properties": {"a": {"maxLength": 3, "type": "string"}}}),
+        );
+        assert_eq!(
+            changes.iter().map(ToString::to_string).collect::<Vec<_>>(),
+            vec!["/properties/a: maxLength changed from 5 to 3"]
+        );
+    }
+}
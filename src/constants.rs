@@ -47,42 +47,27 @@ lazy_static::lazy_static! {
     /// * > The value of "..." MUST be an object. Each value of this object MUST be a valid JSON Schema.
     /// * > This keyword's value MUST be a non-empty array.  Each item of the array MUST be a valid JSON Schema.
     /// * > The value of "..." MUST be either a valid JSON Schema or an array of valid JSON Schemas.
+    ///
+    /// NOTE: Only the subschemas are visited, and not the keyword values holding them (ie. the `properties` object),
+    /// as the other values are not schemas. Optimising them would alter their meaning: the `enum`/`const` values
+    /// would not be matched anymore, and the properties named as keywords (ie. `{"properties": {"type": {}}}`)
+    /// would be treated as keywords of a schema.
     pub(crate) static ref KEYWORDS_WITH_SUBSCHEMAS: HashSet<&'static str> = [
         "additionalItems",
         "additionalProperties",
         "allOf",
         "anyOf",
-        "const",
         "contains",
-        "contentEncoding",
-        "contentMediaType",
         "dependencies",
         "else",
-        "enum",
-        "exclusiveMaximum",
-        "exclusiveMinimum",
-        "format",
         "if",
         "items",
-        "maxItems",
-        "maxLength",
-        "maxProperties",
-        "maximum",
-        "minItems",
-        "minLength",
-        "minProperties",
-        "minimum",
-        "multipleOf",
         "not",
         "oneOf",
-        "pattern",
         "patternProperties",
         "properties",
         "propertyNames",
-        "required",
         "then",
-        "type",
-        "uniqueItems",
     ].iter().cloned().collect();
 
     /// Keywords value MUST be a valid JSON Schema
@@ -99,4 +84,28 @@ lazy_static::lazy_static! {
         "propertyNames",
         "then",
     ].iter().cloned().collect();
+
+    /// Annotation keywords of Draft4, Draft6 and Draft7.
+    ///
+    /// Annotations do not alter the validation outcome of a schema.
+    pub(crate) static ref ANNOTATION_KEYWORDS: HashSet<&'static str> = [
+        "$comment",
+        "default",
+        "description",
+        "examples",
+        "readOnly",
+        "title",
+        "writeOnly",
+    ].iter().copied().collect();
+
+    /// Core keywords of Draft4, Draft6 and Draft7, identifying and referencing schemas.
+    ///
+    /// The keywords are not processed by the optimisation rules, but they are not unknown keywords.
+    pub(crate) static ref CORE_KEYWORDS: HashSet<&'static str> = [
+        "$id",
+        "$ref",
+        "$schema",
+        "definitions",
+        "id",
+    ].iter().copied().collect();
 }
@@ -0,0 +1,106 @@
+//! Equivalence analysis of JSON Schemas.
+//!
+//! Two schemas are equivalent if they accept exactly the same instances.
+use crate::{
+    canonical::canonicalise,
+    inclusion::{is_subschema, Inclusion},
+    jsonschema_equivalent,
+};
+use serde_json::Value;
+
+/// Outcome of the equivalence analysis of two schemas.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Equivalence {
+    /// The schemas accept exactly the same instances
+    Yes,
+    /// There are instances accepted by only one of the schemas.
+    /// If possible, one of such instances is reported as counterexample.
+    No(Option<Value>),
+    /// The analysis was not able to determine if the schemas are equivalent
+    Unknown,
+}
+
+/// Optimised schema in its canonical form
+fn normalise(schema: &Value) -> Value {
+    let mut schema = jsonschema_equivalent(schema.clone());
+    canonicalise(&mut schema);
+    schema
+}
+
+/// Determine if `schema` and `other_schema` accept exactly the same instances.
+///
+/// The schemas are optimised and transformed in their canonical form before being structurally compared.
+/// If they are not structurally equal the equivalence is verified via mutual inclusion (see [`is_subschema`]).
+/// ```rust
+/// use jsonschema_equivalent::{are_equivalent, Equivalence};
+/// use serde_json::json;
+///
+/// assert_eq!(are_equivalent(&json!({"type": ["integer", "number"]}), &json!({"type": "number"})), Equivalence::Yes);
+/// assert_eq!(are_equivalent(&json!({"type": "integer"}), &json!({"type": "number"})), Equivalence::No(Some(json!(0.5))));
+/// ```
+#[must_use]
+pub fn are_equivalent(schema: &Value, other_schema: &Value) -> Equivalence {
+    let schema = normalise(schema);
+    let other_schema = normalise(other_schema);
+    if schema == other_schema {
+        return Equivalence::Yes;
+    }
+
+    match (
+        is_subschema(&schema, &other_schema),
+        is_subschema(&other_schema, &schema),
+    ) {
+        (Inclusion::Yes, Inclusion::Yes) => Equivalence::Yes,
+        (Inclusion::No(Some(counterexample)), _) | (_, Inclusion::No(Some(counterexample))) => {
+            Equivalence::No(Some(counterexample))
+        }
+        (Inclusion::No(None), _) | (_, Inclusion::No(None)) => Equivalence::No(None),
+        _ => Equivalence::Unknown,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{are_equivalent, Equivalence};
+    use serde_json::{json, Value};
+    use test_case::test_case;
+
+    #[test_case(&json!({"type": "string", "minimum": 1}), &json!({"type": "string"}))]
+    #[test_case(&json!({"required": ["a", "b"]}), &json!({"required": ["b", "a"]}))]
+    #[test_case(&json!({"anyOf": [{"type": "string"}, {"minimum": 1}]}), &json!({"anyOf": [{"minimum": 1}, {"type": "string"}]}))]
+    #[test_case(&json!({"type": "number", "maximum": 10}), &json!({"type": "number", "maximum": 10, "exclusiveMaximum": 11}))]
+    #[test_case(&json!({"type": "string", "pattern": "^a"}), &json!({"allOf": [{"type": "string"}, {"pattern": "^a"}]}))]
+    #[test_case(&json!({"type": "string", "minLength": 2, "maxLength": 1}), &json!(false))]
+    #[test_case(&json!({"type": "object", "properties": {"a": true}, "patternProperties": {"^a$": {"type": "string"}}}), &json!({"type": "object", "properties": {"a": {"type": "string"}}}))]
+    fn test_are_equivalent_yes(schema: &Value, other_schema: &Value) {
+        crate::init_logger();
+        assert_eq!(are_equivalent(schema, other_schema), Equivalence::Yes);
+    }
+
+    #[test_case(&json!({"type": "integer"}), &json!({"type": "number"}))]
+    #[test_case(&json!({"type": "number"}), &json!({"type": "integer"}))]
+    #[test_case(&json!({"type": "object"}), &json!({"type": "object", "required": ["a"]}))]
+    #[test_case(&json!({"enum": [1, 2]}), &json!({"enum": [2, 3]}))]
+    #[test_case(&json!({"type": "number", "minimum": 1e-17}), &json!({"type": "number", "minimum": 2e-17}))]
+    #[test_case(&json!({"type": "object", "properties": {"a": true}, "patternProperties": {"^b$": {"type": "string"}}}), &json!({"type": "object", "properties": {"a": {"type": "string"}}}))]
+    #[test_case(&json!({"allOf": [{"if": {"type": "string"}, "else": {"required": ["a"]}, "properties": {"b": {}}}]}), &json!({"minimum": 3}))]
+    #[test_case(&json!({"minimum": 3}), &json!({"allOf": [{"if": {"type": "string"}, "else": {"required": ["a"]}, "properties": {"b": {}}}]}))]
+    fn test_are_equivalent_no(schema: &Value, other_schema: &Value) {
+        crate::init_logger();
+        if let Equivalence::No(Some(counterexample)) = are_equivalent(schema, other_schema) {
+            assert_ne!(
+                jsonschema::is_valid(schema, &counterexample),
+                jsonschema::is_valid(other_schema, &counterexample),
+                "{counterexample} is not a counterexample"
+            );
+        } else {
+            panic!("A counterexample is expected");
+        }
+    }
+
+    #[test_case(&json!({"type": "string", "pattern": "^a"}), &json!({"type": "string", "pattern": "^b"}))]
+    fn test_are_equivalent_unknown(schema: &Value, other_schema: &Value) {
+        crate::init_logger();
+        assert_eq!(are_equivalent(schema, other_schema), Equivalence::Unknown);
+    }
+}
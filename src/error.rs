@@ -0,0 +1,119 @@
+//! Errors reported by the fallible APIs.
+use std::fmt;
+
+/// Error reported by [`try_jsonschema_equivalent_with_options`](crate::try_jsonschema_equivalent_with_options)
+/// and [`bundle`](crate::bundle).
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum Error {
+    /// The schema contains a keyword that is neither defined by the specifications nor registered
+    /// via [`Options::register_keyword`](crate::Options::register_keyword)
+    UnknownKeyword {
+        /// JSON Pointer of the schema containing the keyword
+        pointer: String,
+        /// Unknown keyword
+        keyword: String,
+    },
+    /// The schema is neither an object nor a boolean
+    InvalidSchema {
+        /// JSON Pointer of the schema
+        pointer: String,
+    },
+    /// The value of a keyword does not satisfy the rules of the meta-schema (ie. `{"minLength": -1}`)
+    InvalidKeywordValue {
+        /// JSON Pointer of the schema containing the keyword
+        pointer: String,
+        /// Keyword with the invalid value
+        keyword: String,
+        /// Description of the expected value (ie. `"a non-negative integer"`)
+        expected: &'static str,
+    },
+    /// `type` contains a name that is not a primitive type (ie. `{"type": "strnig"}`)
+    UnknownPrimitiveType {
+        /// JSON Pointer of the schema containing `type`
+        pointer: String,
+        /// Unknown primitive type name
+        primitive_type: String,
+    },
+    /// The items of a keyword, that MUST be unique, are duplicated (ie. `{"required": ["a", "a"]}`)
+    DuplicatedItem {
+        /// JSON Pointer of the schema containing the keyword
+        pointer: String,
+        /// Keyword with the duplicated items
+        keyword: String,
+        /// Duplicated item
+        item: String,
+    },
+    /// The target of a `$ref` could not be retrieved by the [`Resolver`](crate::Resolver), it is not
+    /// present in the retrieved document or it can not be bundled (see [`extract`](crate::extract))
+    UnresolvableReference {
+        /// JSON Pointer of the schema containing `$ref`
+        pointer: String,
+        /// Absolute URI of the reference (or the fragment, for references within the same document)
+        reference: String,
+        /// Description of the failure
+        reason: String,
+    },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownKeyword { pointer, keyword } => {
+                write!(f, "Unknown keyword {keyword:?} at {pointer:?}")
+            }
+            Self::InvalidSchema { pointer } => {
+                write!(
+                    f,
+                    "Expected a schema (an object or a boolean) at {pointer:?}"
+                )
+            }
+            Self::InvalidKeywordValue {
+                pointer,
+                keyword,
+                expected,
+            } => write!(
+                f,
+                "Expected {expected} as value of {keyword:?} at {pointer:?}"
+            ),
+            Self::UnknownPrimitiveType {
+                pointer,
+                primitive_type,
+            } => write!(
+                f,
+                "Unknown primitive type {primitive_type:?} at {pointer:?}"
+            ),
+            Self::DuplicatedItem {
+                pointer,
+                keyword,
+                item,
+            } => write!(f, "Duplicated item {item:?} of {keyword:?} at {pointer:?}"),
+            Self::UnresolvableReference {
+                pointer,
+                reference,
+                reason,
+            } => write!(
+                f,
+                "Unresolvable reference {reference:?} at {pointer:?}: {reason}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+#[cfg(test)]
+mod tests {
+    use super::Error;
+    use test_case::test_case;
+
+    #[test_case(&Error::UnknownKeyword { pointer: "/properties/a".to_string(), keyword: "minimun".to_string() } => r#"Unknown keyword "minimun" at "/properties/a""#)]
+    #[test_case(&Error::InvalidSchema { pointer: "/not".to_string() } => r#"Expected a schema (an object or a boolean) at "/not""#)]
+    #[test_case(&Error::InvalidKeywordValue { pointer: String::new(), keyword: "minLength".to_string(), expected: "a non-negative integer" } => r#"Expected a non-negative integer as value of "minLength" at """#)]
+    #[test_case(&Error::UnknownPrimitiveType { pointer: String::new(), primitive_type: "strnig".to_string() } => r#"Unknown primitive type "strnig" at """#)]
+    #[test_case(&Error::DuplicatedItem { pointer: String::new(), keyword: "required".to_string(), item: "a".to_string() } => r#"Duplicated item "a" of "required" at """#)]
+    #[test_case(&Error::UnresolvableReference { pointer: "/not".to_string(), reference: "file:///a.json".to_string(), reason: "not found".to_string() } => r#"Unresolvable reference "file:///a.json" at "/not": not found"#)]
+    fn test_display(error: &Error) -> String {
+        error.to_string()
+    }
+}
@@ -0,0 +1,437 @@
+//! Extraction of standalone subschemas.
+//!
+//! A subschema (ie. `#/definitions/Address`) can not be validated on its own if it references other
+//! locations of the schema (ie. `definitions` entries). The extraction bundles such locations into the
+//! `definitions` of the extracted subschema, and rewrites the references to them, so it can be compiled
+//! independently of the schema containing it.
+use crate::{
+    helpers::{pointer, subschemas::for_each_subschema_with_pointer},
+    jsonschema_equivalent_with_options, Error, Options,
+};
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+
+/// JSON Pointer of the location bundled in order to resolve a reference to `target`.
+/// The `definitions` entries are bundled as a whole (ie. for `/definitions/Address/properties/street`).
+fn bundled_location(target: &str) -> String {
+    let mut tokens = pointer::tokens(target);
+    match (tokens.next().as_deref(), tokens.next()) {
+        (Some("definitions"), Some(name)) => pointer::append("/definitions", &name),
+        _ => target.to_string(),
+    }
+}
+
+/// Checks if the value referenced by `pointer` is, or contains, the value referenced by `other_pointer`
+fn contains(pointer: &str, other_pointer: &str) -> bool {
+    pointer == other_pointer || pointer::is_ancestor(pointer, other_pointer)
+}
+
+/// Checks if any schema containing the location referenced by `location_pointer` (the schema itself excluded)
+/// defines a new base URI (`$id`)
+fn is_within_base_uri(schema: &Value, location_pointer: &str) -> bool {
+    let mut ancestor_pointer = String::new();
+    pointer::tokens(location_pointer).any(|token| {
+        ancestor_pointer = pointer::append(&ancestor_pointer, &token);
+        ancestor_pointer != location_pointer
+            && schema
+                .pointer(&ancestor_pointer)
+                .and_then(|ancestor| ancestor.get("$id"))
+                .is_some()
+    })
+}
+
+/// Add to `references` the references, within the same document, of `schema` and its subschemas.
+/// The references are indexed by the JSON Pointer, within the original schema, of the schema defining them.
+fn collect_local_references(
+    schema: &Value,
+    schema_pointer: &str,
+    options: &Options,
+    is_within_base_uri: bool,
+    references: &mut BTreeMap<String, String>,
+) -> Result<(), Error> {
+    let is_within_base_uri =
+        is_within_base_uri || (!schema_pointer.is_empty() && schema.get("$id").is_some());
+    if let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+        if let Some(fragment) = reference.strip_prefix('#') {
+            let error = |reason: &str| Error::UnresolvableReference {
+                pointer: schema_pointer.to_string(),
+                reference: reference.to_string(),
+                reason: reason.to_string(),
+            };
+            if is_within_base_uri {
+                return Err(error("Resolved against the base URI defined by `$id`"));
+            }
+            if !fragment.is_empty() && !fragment.starts_with('/') {
+                return Err(error("Plain name fragment not supported"));
+            }
+            let _ = references.insert(schema_pointer.to_string(), fragment.to_string());
+        }
+    }
+    let mut result = Ok(());
+    for_each_subschema_with_pointer(
+        schema,
+        schema_pointer,
+        &|keyword| options.is_custom_subschema_keyword(keyword),
+        &mut |subschema_pointer, subschema| {
+            if result.is_ok() {
+                result = collect_local_references(
+                    subschema,
+                    &subschema_pointer,
+                    options,
+                    is_within_base_uri,
+                    references,
+                );
+            }
+        },
+    );
+    result
+}
+
+/// Locations of `schema` to bundle into the subschema at `subschema_pointer`
+struct Bundle<'s> {
+    schema: &'s Value,
+    subschema_pointer: &'s str,
+    /// JSON Pointers of the bundled locations, and the names of the `definitions` entries holding them
+    locations: Vec<(String, String)>,
+    /// Local references of the subschema and of the bundled locations, by JSON Pointer of the schema defining them
+    references: BTreeMap<String, String>,
+}
+
+impl<'s> Bundle<'s> {
+    /// Collect the locations (transitively) referenced by the subschema at `subschema_pointer`.
+    /// `definition_names` are the names of the `definitions` entries already defined by the subschema.
+    fn new(
+        schema: &'s Value,
+        subschema_pointer: &'s str,
+        mut definition_names: Vec<String>,
+        options: &Options,
+    ) -> Result<Self, Error> {
+        let mut references = BTreeMap::new();
+        let mut locations: Vec<String> = Vec::new();
+        let mut pending_locations = vec![subschema_pointer.to_string()];
+        while let Some(location_pointer) = pending_locations.pop() {
+            let mut location_references = BTreeMap::new();
+            if let Some(location) = schema.pointer(&location_pointer) {
+                collect_local_references(
+                    location,
+                    &location_pointer,
+                    options,
+                    is_within_base_uri(schema, &location_pointer),
+                    &mut location_references,
+                )?;
+            }
+            for (schema_pointer, target) in &location_references {
+                if schema.pointer(target).is_none() {
+                    return Err(Error::UnresolvableReference {
+                        pointer: schema_pointer.clone(),
+                        reference: format!("#{target}"),
+                        reason: "Location not found".to_string(),
+                    });
+                }
+                let bundled_location = bundled_location(target);
+                if !contains(subschema_pointer, target)
+                    && !locations
+                        .iter()
+                        .any(|location| contains(location, &bundled_location))
+                {
+                    locations.push(bundled_location.clone());
+                    pending_locations.push(bundled_location);
+                }
+            }
+            references.extend(location_references);
+        }
+
+        // Locations contained by other bundled locations are reachable via the containing ones
+        let locations: Vec<String> = locations
+            .iter()
+            .filter(|location| {
+                !locations
+                    .iter()
+                    .any(|other_location| pointer::is_ancestor(other_location, location))
+            })
+            .cloned()
+            .collect();
+        let locations = locations
+            .into_iter()
+            .map(|location| {
+                let token = pointer::tokens(&location)
+                    .last()
+                    .unwrap_or_else(|| "root".to_string());
+                let mut name = token.clone();
+                let mut counter: usize = 1;
+                while definition_names.contains(&name) {
+                    counter = counter.saturating_add(1);
+                    name = format!("{token}_{counter}");
+                }
+                definition_names.push(name.clone());
+                (location, name)
+            })
+            .collect();
+
+        Ok(Self {
+            schema,
+            subschema_pointer,
+            locations,
+            references,
+        })
+    }
+
+    /// Reference, within the extracted subschema, to the location referenced by `target` in the original schema
+    fn rewritten_reference(&self, target: &str) -> String {
+        if contains(self.subschema_pointer, target) {
+            return format!("#{}", &target[self.subschema_pointer.len()..]);
+        }
+        self.locations
+            .iter()
+            .find(|(location, _)| contains(location, target))
+            .map_or_else(
+                || format!("#{target}"),
+                |(location, name)| {
+                    format!(
+                        "#{}{}",
+                        pointer::append("/definitions", name),
+                        &target[location.len()..]
+                    )
+                },
+            )
+    }
+
+    /// Copy of the value at `location_pointer`, with its references rewritten to resolve within the extracted subschema
+    fn rewritten_location(&self, location_pointer: &str) -> Value {
+        let mut location = self
+            .schema
+            .pointer(location_pointer)
+            .cloned()
+            .unwrap_or(Value::Bool(true));
+        for (schema_pointer, target) in &self.references {
+            if !contains(location_pointer, schema_pointer) {
+                continue;
+            }
+            if let Some(Value::Object(schema_object)) =
+                location.pointer_mut(&schema_pointer[location_pointer.len()..])
+            {
+                let _ = schema_object.insert(
+                    "$ref".to_string(),
+                    Value::String(self.rewritten_reference(target)),
+                );
+            }
+        }
+        location
+    }
+}
+
+/// Extract the subschema at `pointer`, as [`extract`], applying only the rules enabled by `options`.
+///
+/// # Errors
+///
+/// * [`Error::UnresolvableReference`] if `pointer`, or a location referenced by the subschema, is not present
+///   in `schema`, or if a reference can not be bundled (see [`extract`]).
+pub fn extract_with_options(
+    schema: &Value,
+    pointer: &str,
+    options: &Options,
+) -> Result<Value, Error> {
+    let pointer = pointer.strip_prefix('#').unwrap_or(pointer);
+    let Some(subschema) = schema.pointer(pointer) else {
+        return Err(Error::UnresolvableReference {
+            pointer: String::new(),
+            reference: format!("#{pointer}"),
+            reason: "Location not found".to_string(),
+        });
+    };
+    if pointer.is_empty() {
+        return Ok(jsonschema_equivalent_with_options(
+            subschema.clone(),
+            options,
+        ));
+    }
+
+    let definition_names = subschema
+        .get("definitions")
+        .and_then(Value::as_object)
+        .map(|definitions| definitions.keys().cloned().collect())
+        .unwrap_or_default();
+    let bundle = Bundle::new(schema, pointer, definition_names, options)?;
+    let mut subschema = bundle.rewritten_location(pointer);
+    if !bundle.locations.is_empty() {
+        if let Value::Object(subschema_object) = &mut subschema {
+            if let Value::Object(subschema_definitions) = subschema_object
+                .entry("definitions")
+                .or_insert_with(|| Value::Object(Map::new()))
+            {
+                for (location, name) in &bundle.locations {
+                    // The optimisation does not visit `definitions`, so the bundled ones are optimised on their own
+                    let _ = subschema_definitions.insert(
+                        name.clone(),
+                        jsonschema_equivalent_with_options(
+                            bundle.rewritten_location(location),
+                            options,
+                        ),
+                    );
+                }
+            }
+        }
+    }
+
+    Ok(jsonschema_equivalent_with_options(subschema, options))
+}
+
+/// Extract the subschema at `pointer` (ie. `#/definitions/Address` or `/properties/items/items`) as a standalone
+/// optimised schema.
+///
+/// The locations (transitively) referenced by the subschema (ie. `#/definitions/Street` or `#/properties/a`) are
+/// bundled into its `definitions` keyword, and the references are rewritten so they resolve as in the original schema.
+/// The `definitions` entries are bundled as a whole, and keep their name unless the subschema already defines it.
+/// References to other documents are left as they are.
+///
+/// # Errors
+///
+/// * [`Error::UnresolvableReference`] if `pointer`, or a location referenced by the subschema, is not present
+///   in `schema`, or if a reference can not be bundled (plain name fragments, ie. `#address`, and references
+///   within subschemas defining a new base URI via `$id`).
+/// ```rust
+/// use jsonschema_equivalent::extract;
+/// use serde_json::json;
+///
+/// let schema = json!({
+///     "definitions": {
+///         "Address": {"type": "object", "properties": {"street": {"$ref": "#/definitions/Street"}}},
+///         "Street": {"type": "string", "minimum": 1},
+///         "Unused": {"type": "integer"},
+///     },
+///     "properties": {"address": {"$ref": "#/definitions/Address"}},
+/// });
+/// assert_eq!(
+///     extract(&schema, "#/definitions/Address"),
+///     Ok(json!({
+///         "type": "object",
+///         "properties": {"street": {"$ref": "#/definitions/Street"}},
+///         "definitions": {
+///             "Street": {"type": "string"},
+///         },
+///     })),
+/// );
+/// ```
+pub fn extract(schema: &Value, pointer: &str) -> Result<Value, Error> {
+    extract_with_options(schema, pointer, &Options::default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{bundled_location, extract};
+    use crate::Error;
+    use serde_json::{json, Value};
+    use test_case::test_case;
+
+    #[test_case("/definitions/a" => "/definitions/a")]
+    #[test_case("/definitions/a~1b/properties/c" => "/definitions/a~1b")]
+    #[test_case("/properties/a" => "/properties/a")]
+    #[test_case("" => "")]
+    fn test_bundled_location(target: &str) -> String {
+        bundled_location(target)
+    }
+
+    fn unresolvable_reference(pointer: &str, reference: &str, reason: &str) -> Error {
+        Error::UnresolvableReference {
+            pointer: pointer.to_string(),
+            reference: reference.to_string(),
+            reason: reason.to_string(),
+        }
+    }
+
+    #[test_case(&json!({"type": "string"}), "/properties/a" => Err(unresolvable_reference("", "#/properties/a", "Location not found")))]
+    #[test_case(&json!({"type": "string", "minimum": 1}), "" => Ok(json!({"type": "string"})))]
+    #[test_case(&json!({"type": "string", "minimum": 1}), "#" => Ok(json!({"type": "string"})); "fragment of the whole schema")]
+    #[test_case(
+        &json!({"properties": {"items": {"items": {"type": "integer", "minLength": 1}}}}),
+        "#/properties/items/items"
+        => Ok(json!({"type": "integer"}))
+    )]
+    #[test_case(
+        &json!({"definitions": {"a": {"$ref": "#/definitions/b"}, "b": {"items": {"$ref": "#/definitions/a"}}, "c": {}}, "not": {"$ref": "#/definitions/a"}}),
+        "/not"
+        => Ok(json!({"$ref": "#/definitions/a", "definitions": {"a": {"$ref": "#/definitions/b"}, "b": {"items": {"$ref": "#/definitions/a"}}}}))
+    )]
+    #[test_case(
+        &json!({"definitions": {"a": {"type": "string"}}, "properties": {"a": {"definitions": {"a": {"type": "integer"}, "b": {}}, "$ref": "#/definitions/a"}}}),
+        "/properties/a"
+        => Ok(json!({"definitions": {"a": {"type": "integer"}, "a_2": {"type": "string"}, "b": {}}, "$ref": "#/definitions/a_2"}))
+        ; "definitions already defined by the subschema"
+    )]
+    #[test_case(
+        &json!({"properties": {"a": {"type": "integer"}, "child": {"properties": {"a": {"type": "string"}, "b": {"$ref": "#/properties/a"}}}}}),
+        "/properties/child"
+        => Ok(json!({"properties": {"a": {"type": "string"}, "b": {"$ref": "#/definitions/a"}}, "definitions": {"a": {"type": "integer"}}}))
+        ; "reference to a location outside of definitions"
+    )]
+    #[test_case(
+        &json!({"properties": {"child": {"properties": {"a": {"type": "string"}, "b": {"$ref": "#/properties/child/properties/a"}}}}}),
+        "/properties/child"
+        => Ok(json!({"properties": {"a": {"type": "string"}, "b": {"$ref": "#/properties/a"}}}))
+        ; "reference within the subschema"
+    )]
+    #[test_case(
+        &json!({"type": "object", "properties": {"next": {"anyOf": [{"type": "null"}, {"$ref": "#"}]}}}),
+        "/properties/next"
+        => Ok(json!({"anyOf": [{"type": "null"}, {"$ref": "#/definitions/root"}], "definitions": {"root": {"type": "object", "properties": {"next": {"anyOf": [{"type": "null"}, {"$ref": "#/definitions/root"}]}}}}}))
+        ; "reference to the whole schema"
+    )]
+    #[test_case(
+        &json!({"properties": {"a": {"$ref": "#/definitions/missing"}, "b": {"$ref": "#/properties/a"}}}),
+        "/properties/b"
+        => Err(unresolvable_reference("/properties/a", "#/definitions/missing", "Location not found"))
+    )]
+    #[test_case(
+        &json!({"definitions": {"a": {"type": "string"}}, "properties": {"a": {"$ref": "#/definitions/missing"}}}),
+        "/properties/a"
+        => Err(unresolvable_reference("/properties/a", "#/definitions/missing", "Location not found"))
+    )]
+    #[test_case(
+        &json!({"definitions": {"a": {"$id": "#address"}}, "properties": {"a": {"$ref": "#address"}}}),
+        "/properties/a"
+        => Err(unresolvable_reference("/properties/a", "#address", "Plain name fragment not supported"))
+    )]
+    #[test_case(
+        &json!({"properties": {"a": {"$id": "a.json", "properties": {"b": {"$ref": "#/definitions/c"}}, "definitions": {"c": {}}}}}),
+        "/properties/a/properties/b"
+        => Err(unresolvable_reference("/properties/a/properties/b", "#/definitions/c", "Resolved against the base URI defined by `$id`"))
+    )]
+    #[test_case(
+        &json!({"properties": {"a": {"$ref": "other.json#/definitions/a"}}}),
+        "/properties/a"
+        => Ok(json!({"$ref": "other.json#/definitions/a"}))
+        ; "reference to other documents"
+    )]
+    fn test_extract(schema: &Value, pointer: &str) -> Result<Value, Error> {
+        crate::init_logger();
+        extract(schema, pointer)
+    }
+
+    #[test_case(
+        &json!({
+            "definitions": {
+                "Address": {"type": "object", "properties": {"street": {"$ref": "#/definitions/Street"}, "next": {"$ref": "#/definitions/Address"}}},
+                "Street": {"type": "string", "minLength": 1},
+            },
+            "properties": {"address": {"$ref": "#/definitions/Address"}},
+        }),
+        "address",
+        &[json!({}), json!({"street": ""}), json!({"street": "a"}), json!({"next": {"street": ""}}), json!(1)]
+    )]
+    #[test_case(
+        &json!({"properties": {"a": {"type": "integer"}, "child": {"properties": {"a": {"type": "string"}, "b": {"$ref": "#/properties/a"}}}}}),
+        "child",
+        &[json!({"b": 1}), json!({"b": "1"}), json!({"a": "1", "b": 1}), json!({"a": 1})]
+    )]
+    fn test_extract_preserves_validation(schema: &Value, property: &str, instances: &[Value]) {
+        let extracted_schema = extract(schema, &format!("/properties/{property}"))
+            .expect("The subschema should be extracted");
+        for instance in instances {
+            assert_eq!(
+                jsonschema::is_valid(schema, &json!({ property: instance })),
+                jsonschema::is_valid(&extracted_schema, instance),
+                "{schema} and {extracted_schema} disagree on {instance}"
+            );
+        }
+    }
+}
@@ -0,0 +1,125 @@
+//! Structural fingerprint of JSON Schemas.
+//!
+//! The fingerprint is the 128 bits [FNV-1a](https://en.wikipedia.org/wiki/Fowler%E2%80%93Noll%E2%80%93Vo_hash_function)
+//! hash of the serialisation of the optimised schema in its canonical form, with the numbers normalised
+//! (ie. `1.0` is hashed as `1`). The numbers are normalised before the canonicalisation, so that the
+//! sorting and the deduplication of the `enum` values and of the `anyOf` branches are not affected by their representation.
+//!
+//! The fingerprint is computed over the optimised schema, so it depends on the optimisation rules as well.
+//! Fingerprints are guaranteed to be comparable only if computed by the same crate version: [`FINGERPRINT_FORMAT_VERSION`]
+//! is bumped every time that the canonical form or the hashing are modified, or that a rule change alters the
+//! fingerprints pinned by the tests, but a rule change might alter the fingerprint of other schemas without a bump.
+use crate::{canonicalise, jsonschema_equivalent_ref};
+use serde_json::{Number, Value};
+
+/// Version of the fingerprint format. It is hashed together with the schema.
+pub const FINGERPRINT_FORMAT_VERSION: u32 = 1;
+
+/// FNV-1a 128 bits offset basis
+static FNV_OFFSET_BASIS: u128 = 0x6c62_272e_07bb_0142_62b8_2175_6295_c58d;
+/// FNV-1a 128 bits prime
+static FNV_PRIME: u128 = 0x0000_0000_0100_0000_0000_0000_0000_013b;
+
+/// Update `hash` with the FNV-1a hash of `bytes`
+fn fnv1a(hash: u128, bytes: &[u8]) -> u128 {
+    bytes.iter().fold(hash, |hash, byte| {
+        (hash ^ u128::from(*byte)).wrapping_mul(FNV_PRIME)
+    })
+}
+
+/// Replace the numbers with an integral value (ie. `1.0`) with their integer representation, so equal numbers
+/// are serialised identically
+fn normalise_numbers(value: &mut Value) {
+    match value {
+        Value::Number(number) if !number.is_i64() && !number.is_u64() => {
+            if let Some(float) = number.as_f64() {
+                #[allow(clippy::cast_possible_truncation)]
+                let integer = float as i64;
+                #[allow(clippy::cast_precision_loss, clippy::float_cmp)]
+                if float.fract() == 0.0 && integer as f64 == float {
+                    *number = Number::from(integer);
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(normalise_numbers),
+        Value::Object(object) => object.values_mut().for_each(normalise_numbers),
+        _ => {}
+    }
+}
+
+/// Compute the fingerprint of `schema`.
+///
+/// Equivalent schemas that are optimised and canonicalised to the same schema (see [`jsonschema_equivalent_canonical`](crate::jsonschema_equivalent_canonical)),
+/// once their numbers are normalised, are guaranteed to have the same fingerprint.
+/// ```rust
+/// use jsonschema_equivalent::fingerprint;
+/// use serde_json::json;
+///
+/// assert_eq!(
+///     fingerprint(&json!({"type": ["number", "integer"], "required": ["a"]})),
+///     fingerprint(&json!({"type": "number"})),
+/// );
+/// ```
+#[must_use]
+pub fn fingerprint(schema: &Value) -> u128 {
+    // Same steps as `jsonschema_equivalent_canonical_ref`, normalising the numbers before each canonicalisation
+    let mut canonical_schema = schema.clone();
+    normalise_numbers(&mut canonical_schema);
+    canonicalise(&mut canonical_schema);
+    let _ = jsonschema_equivalent_ref(&mut canonical_schema);
+    normalise_numbers(&mut canonical_schema);
+    canonicalise(&mut canonical_schema);
+    let hash = fnv1a(FNV_OFFSET_BASIS, &FINGERPRINT_FORMAT_VERSION.to_be_bytes());
+    fnv1a(hash, canonical_schema.to_string().as_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{fingerprint, fnv1a, FNV_OFFSET_BASIS};
+    use serde_json::{json, Value};
+    use test_case::test_case;
+
+    // Reference values from http://www.isthe.com/chongo/src/fnv/test_fnv.c
+    #[test_case(b"" => 0x6c62_272e_07bb_0142_62b8_2175_6295_c58d)]
+    #[test_case(b"a" => 0xd228_cb69_6f1a_8caf_7891_2b70_4e4a_8964 ; "a")]
+    fn test_fnv1a(bytes: &[u8]) -> u128 {
+        fnv1a(FNV_OFFSET_BASIS, bytes)
+    }
+
+    #[test_case(&json!({"type": ["number", "integer"]}), &json!({"type": "number"}))]
+    #[test_case(&json!({"required": ["a", "b"]}), &json!({"required": ["b", "a"]}))]
+    #[test_case(&json!({"anyOf": [{"type": "string"}, {"minimum": 1}]}), &json!({"anyOf": [{"minimum": 1}, {"type": "string"}]}))]
+    #[test_case(&json!({"type": "string", "minimum": 1}), &json!({"type": "string"}))]
+    #[test_case(&json!({"type": "number", "minimum": 1}), &json!({"type": "number", "minimum": 1.0}))]
+    #[test_case(&json!({"enum": [1, 2.0]}), &json!({"enum": [2, 1.0]}))]
+    #[test_case(&json!({"enum": [{"a": 1.0}, {"a": 10}]}), &json!({"enum": [{"a": 10}, {"a": 1}]}))]
+    #[test_case(&json!({"enum": [100.0, 100]}), &json!({"enum": [100]}))]
+    #[test_case(
+        &json!({"allOf": [{"anyOf": [{"type": "string"}, {"type": "integer"}]}, {"anyOf": [{"type": "integer"}, {"type": "string"}]}, {"anyOf": [{"type": "string", "minLength": 1}, {"type": "integer"}]}]}),
+        &json!({"allOf": [{"anyOf": [{"type": "string", "minLength": 1}, {"type": "integer"}]}, {"anyOf": [{"type": "integer"}, {"type": "string"}]}, {"anyOf": [{"type": "string"}, {"type": "integer"}]}]})
+    )]
+    fn test_fingerprint_equal(schema: &Value, other_schema: &Value) {
+        crate::init_logger();
+        assert_eq!(fingerprint(schema), fingerprint(other_schema));
+    }
+
+    #[test_case(&json!({"type": "integer"}), &json!({"type": "number"}))]
+    #[test_case(&json!({"required": ["a"]}), &json!({"required": ["b"]}))]
+    #[test_case(&json!({"type": "number", "minimum": 1}), &json!({"type": "number", "minimum": 1.5}))]
+    fn test_fingerprint_different(schema: &Value, other_schema: &Value) {
+        crate::init_logger();
+        assert_ne!(fingerprint(schema), fingerprint(other_schema));
+    }
+
+    // The fingerprints are expected to be stable, update them only together with `FINGERPRINT_FORMAT_VERSION`.
+    // The schemas are optimised by most of the rules, so that a rule change altering their output is noticed.
+    #[test_case(&json!(true) => 0x951b_37c5_e265_995b_e47d_d896_3a76_3c66)]
+    #[test_case(&json!({"type": "string", "minLength": 1}) => 0x160a_2077_1a64_0659_43e4_5f89_7b2c_997c)]
+    #[test_case(&json!({"type": ["integer", "string"], "minimum": 1, "minLength": 0, "required": [], "allOf": [{"type": "integer"}, {"maximum": 10.0}]}) => 0xab3b_0db4_1109_25a4_165e_2b18_05c1_93a0)]
+    #[test_case(&json!({"type": "object", "properties": {"a": {"enum": ["a", 1], "type": "string"}, "b": {"if": true, "then": {"minLength": 2, "maxLength": 1}}}, "propertyNames": {"minimum": 1}, "minProperties": 0}) => 0xd76c_e868_aace_a9b8_6f3e_1c30_2244_0a60)]
+    #[test_case(&json!({"type": "array", "additionalItems": false, "items": [{"anyOf": [{"const": "a"}, {"const": "b"}]}, {"const": 1, "enum": [1, 2]}], "oneOf": [{"type": "array", "minItems": 1}, {"type": "array", "maxItems": 3}]}) => 0xd511_3e53_1c22_bfdf_b4be_3cb0_3763_e524)]
+    fn test_fingerprint_stability(schema: &Value) -> u128 {
+        crate::init_logger();
+        fingerprint(schema)
+    }
+}
@@ -0,0 +1,188 @@
+use crate::constants::ANNOTATION_KEYWORDS;
+use crate::helpers::{edits::Edits, is, subschemas::for_each_subschema_mut_with_location};
+use crate::options::Options;
+use serde_json::{map::Entry, Map, Value};
+
+/// Remove the annotations (including the registered ones) from `schema` and all its subschemas
+/// (including the ones in `definitions`).
+/// The method returns true if a schema modification occurred.
+pub(crate) fn strip(schema: &mut Value, options: &Options, edits: &mut Edits) -> bool {
+    let mut updated_schema = false;
+    if let Value::Object(schema_object) = schema {
+        for keyword in detach(schema_object, options).keys() {
+            edits.remove(keyword);
+            updated_schema = true;
+        }
+        if let Some(Value::Object(definitions)) = schema_object.get_mut("definitions") {
+            edits.within("definitions", |edits| {
+                for (name, definition) in definitions.iter_mut() {
+                    updated_schema |= edits.within(name, |edits| strip(definition, options, edits));
+                }
+            });
+        }
+    }
+    for_each_subschema_mut_with_location(
+        schema,
+        &|keyword| options.is_custom_subschema_keyword(keyword),
+        &mut |location, subschema| {
+            updated_schema |=
+                edits.within_location(location, |edits| strip(subschema, options, edits));
+        },
+    );
+    updated_schema
+}
+
+/// Remove, and return, the annotations (including the registered ones) of the schema (the subschemas are not modified)
+pub(crate) fn detach(
+    schema_object: &mut Map<String, Value>,
+    options: &Options,
+) -> Map<String, Value> {
+    let annotation_keys: Vec<String> = schema_object
+        .keys()
+        .filter(|key| options.is_annotation_keyword(key))
+        .cloned()
+        .collect();
+    annotation_keys
+        .into_iter()
+        .filter_map(|key| schema_object.remove_entry(&key))
+        .collect()
+}
+
+/// Checks if `schema` holds only keywords registered as annotations (see [`CustomKeyword::annotation`](crate::CustomKeyword::annotation)).
+/// Such keywords do not alter the validation outcome, so the schema is equivalent to a `true` schema.
+pub(crate) fn has_only_registered_annotations(schema: &Value, options: &Options) -> bool {
+    schema.as_object().is_some_and(|schema_object| {
+        !schema_object.is_empty()
+            && schema_object.keys().all(|keyword| {
+                !ANNOTATION_KEYWORDS.contains(keyword.as_str())
+                    && options.is_annotation_keyword(keyword)
+            })
+    })
+}
+
+/// Checks if `schema`, once its annotations are detached, is the representation of a `true` or
+/// `false` schema that [`attach`] produces while attaching annotations to boolean schemas.
+pub(crate) fn is_annotated_boolean_schema(schema: &Value) -> bool {
+    match schema {
+        Value::Object(schema_object) => {
+            schema_object.is_empty()
+                || (schema_object.len() == 1
+                    && schema_object.get("not").is_some_and(is::true_schema))
+        }
+        _ => false,
+    }
+}
+
+/// Attach `annotations` to `schema`.
+///
+/// The annotations already present in `schema` (ie. merged from `allOf` items) are overridden,
+/// with the exception of `examples` which are concatenated (without duplicates).
+/// As boolean schemas cannot hold annotations, `true` is represented as `{}` and `false` as `{"not": true}`.
+///
+/// The annotations are expected to be removed via [`detach`], whose removal is not recorded into `edits`, so
+/// only the annotations overriding the values defined while they were detached are recorded.
+pub(crate) fn attach(schema: &mut Value, annotations: Map<String, Value>, edits: &mut Edits) {
+    if annotations.is_empty() {
+        return;
+    }
+    let schema_object = match schema {
+        Value::Object(schema_object) => schema_object,
+        Value::Bool(true) => {
+            *schema = Value::Object(Map::new());
+            attach(schema, annotations, &mut Edits::default());
+            edits.replace_value(schema);
+            return;
+        }
+        _ => {
+            *schema = serde_json::json!({"not": true});
+            attach(schema, annotations, &mut Edits::default());
+            edits.replace_value(schema);
+            return;
+        }
+    };
+
+    for (key, value) in annotations {
+        match (schema_object.entry(key), value) {
+            (Entry::Occupied(mut entry), Value::Array(mut examples))
+                if entry.key() == "examples" =>
+            {
+                if let Value::Array(merged_examples) = entry.get() {
+                    for merged_example in merged_examples {
+                        if !examples.contains(merged_example) {
+                            examples.push(merged_example.clone());
+                        }
+                    }
+                }
+                let examples = Value::Array(examples);
+                edits.replace(entry.key(), &examples);
+                let _ = entry.insert(examples);
+            }
+            (Entry::Occupied(mut entry), value) => {
+                edits.replace(entry.key(), &value);
+                let _ = entry.insert(value);
+            }
+            (Entry::Vacant(entry), value) => {
+                let _ = entry.insert(value);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{attach, detach, is_annotated_boolean_schema, strip};
+    use crate::helpers::edits::Edits;
+    use crate::options::{CustomKeyword, Options};
+    use serde_json::{json, Value};
+    use test_case::test_case;
+
+    #[test_case(json!(true) => json!(true))]
+    #[test_case(json!({"title": "a", "type": "string"}) => json!({"type": "string"}))]
+    #[test_case(json!({"properties": {"title": {"title": "a"}}}) => json!({"properties": {"title": {}}}))]
+    #[test_case(json!({"definitions": {"a": {"default": 1, "minimum": 1}}}) => json!({"definitions": {"a": {"minimum": 1}}}))]
+    #[test_case(json!({"const": {"title": "a"}}) => json!({"const": {"title": "a"}}))]
+    #[test_case(json!({"x-doc": "a", "x-schema": {"title": "a"}}) => json!({"x-schema": {}}))]
+    fn test_strip(mut schema: Value) -> Value {
+        let options = Options::new()
+            .register_keyword("x-doc", CustomKeyword::annotation())
+            .register_keyword("x-schema", CustomKeyword::assertion().with_subschema());
+        let original_schema = schema.clone();
+        let mut edits = Edits::recording();
+        let _ = strip(&mut schema, &options, &mut edits);
+        crate::assert_edits_transform(&original_schema, edits, &schema);
+        schema
+    }
+
+    #[test_case(json!({"title": "a", "type": "string"}) => (json!({"type": "string"}), json!({"title": "a"})))]
+    #[test_case(json!({"type": "string"}) => (json!({"type": "string"}), json!({})))]
+    fn test_detach(mut schema: Value) -> (Value, Value) {
+        let annotations = detach(
+            schema.as_object_mut().expect("It should be there"),
+            &Options::default(),
+        );
+        (schema, Value::Object(annotations))
+    }
+
+    #[test_case(&json!({}) => true)]
+    #[test_case(&json!({"not": true}) => true)]
+    #[test_case(&json!({"not": {}}) => true)]
+    #[test_case(&json!({"not": {"type": "string"}}) => false)]
+    #[test_case(&json!(true) => false)]
+    fn test_is_annotated_boolean_schema(schema: &Value) -> bool {
+        is_annotated_boolean_schema(schema)
+    }
+
+    #[test_case(json!({"type": "string"}), &json!({}) => json!({"type": "string"}))]
+    #[test_case(json!(true), &json!({"title": "a"}) => json!({"title": "a"}))]
+    #[test_case(json!(false), &json!({"title": "a"}) => json!({"not": true, "title": "a"}))]
+    #[test_case(json!({"title": "b", "type": "string"}), &json!({"title": "a"}) => json!({"title": "a", "type": "string"}))]
+    #[test_case(json!({"examples": ["b", "a"]}), &json!({"examples": ["a"]}) => json!({"examples": ["a", "b"]}))]
+    fn test_attach(mut schema: Value, annotations: &Value) -> Value {
+        attach(
+            &mut schema,
+            annotations.as_object().expect("It should be there").clone(),
+            &mut Edits::default(),
+        );
+        schema
+    }
+}
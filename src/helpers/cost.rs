@@ -0,0 +1,291 @@
+//! Estimation of the cost of validating an instance against a schema.
+//!
+//! Equivalent schemas are not equally fast to validate: validators short-circuit `allOf` on the first
+//! failing subschema and `anyOf` on the first passing subschema. The estimation allows to order the
+//! subschemas so that the ones deciding the outcome cheaply are validated first.
+//!
+//! The values are relative (an unit is roughly the cost of checking the `type` of an instance) and
+//! they are not meant to be precise, but only to rank subschemas.
+use crate::{
+    helpers::{edits::Edits, types::PrimitiveTypesBitMap},
+    primitive_type::PrimitiveType,
+};
+use serde_json::Value;
+
+/// Expected number of items/properties of a JSON array/object, used to weight the subschemas applied to each of them
+const EXPECTED_CHILDREN: f64 = 4.0;
+/// Expected number of primitive types an instance can have (`integer` is a subset of `number`)
+const PRIMITIVE_TYPES: f64 = 6.0;
+/// Probability that an instance satisfies a single constraint (ie. `minimum`), in absence of better knowledge
+const CONSTRAINT_ACCEPTANCE: f64 = 0.5;
+/// Probability that an instance is equal to a given value (`const` or `enum` item)
+const VALUE_ACCEPTANCE: f64 = 0.01;
+
+/// Cost estimation of the validation of an instance against a schema
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct Estimate {
+    /// Relative cost of the validation
+    pub(crate) cost: f64,
+    /// Probability (between 0 and 1) that an instance is valid against the schema
+    pub(crate) acceptance: f64,
+}
+
+impl Estimate {
+    /// Ranking of the schema as `allOf` subschema: lower ranks are validated first
+    /// as they are cheap and likely to fail (ending the `allOf` validation).
+    pub(crate) fn all_of_rank(self) -> f64 {
+        self.cost / (1.0 - self.acceptance).max(f64::EPSILON)
+    }
+
+    /// Ranking of the schema as `anyOf` subschema: lower ranks are validated first
+    /// as they are cheap and likely to succeed (ending the `anyOf` validation).
+    pub(crate) fn any_of_rank(self) -> f64 {
+        self.cost / self.acceptance.max(f64::EPSILON)
+    }
+}
+
+/// Size of a JSON value, used as cost of comparing an instance with it
+fn value_size(value: &Value) -> f64 {
+    match value {
+        Value::Array(items) => items.iter().map(value_size).sum::<f64>() + 1.0,
+        Value::Object(object) => object.values().map(value_size).sum::<f64>() + 1.0,
+        _ => 1.0,
+    }
+}
+
+/// Number of items of a JSON array, 1 otherwise
+#[allow(clippy::cast_precision_loss)]
+fn items_count(value: &Value) -> f64 {
+    value.as_array().map_or(1.0, |items| items.len() as f64)
+}
+
+/// Estimations of the schemas contained by a JSON array or object
+fn subschemas_estimates(value: &Value) -> Vec<Estimate> {
+    match value {
+        Value::Array(items) => items.iter().map(estimate).collect(),
+        Value::Object(object) => object.values().map(estimate).collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Estimate the cost of validating an instance against `schema`
+pub(crate) fn estimate(schema: &Value) -> Estimate {
+    let schema_object = match schema {
+        Value::Bool(value) => {
+            return Estimate {
+                cost: 0.0,
+                acceptance: if *value { 1.0 } else { 0.0 },
+            }
+        }
+        Value::Object(schema_object) => schema_object,
+        _ => {
+            return Estimate {
+                cost: 0.0,
+                acceptance: 1.0,
+            }
+        }
+    };
+
+    schema_object.iter().fold(
+        Estimate {
+            cost: 0.0,
+            acceptance: 1.0,
+        },
+        |schema_estimate, (keyword, value)| {
+            let keyword_estimate = keyword_estimate(keyword, value);
+            Estimate {
+                cost: schema_estimate.cost + keyword_estimate.cost,
+                acceptance: schema_estimate.acceptance * keyword_estimate.acceptance,
+            }
+        },
+    )
+}
+
+/// Probability that an instance is valid against `type` keyword with value `value`
+fn type_acceptance(value: &Value) -> f64 {
+    let primitive_types = PrimitiveTypesBitMap::from_schema_value(Some(value));
+    let types_count: f64 = [
+        PrimitiveType::Array,
+        PrimitiveType::Boolean,
+        PrimitiveType::Null,
+        PrimitiveType::Number,
+        PrimitiveType::Object,
+        PrimitiveType::String,
+    ]
+    .iter()
+    .map(|primitive_type| {
+        if primitive_types.contains(*primitive_type) {
+            1.0
+        } else if *primitive_type == PrimitiveType::Number
+            && primitive_types.contains(PrimitiveType::Integer)
+        {
+            CONSTRAINT_ACCEPTANCE
+        } else {
+            0.0
+        }
+    })
+    .sum();
+    types_count / PRIMITIVE_TYPES
+}
+
+/// Estimate the cost of validating an instance against the keyword `keyword`, with value `value`
+pub(crate) fn keyword_estimate(keyword: &str, value: &Value) -> Estimate {
+    let (cost, acceptance) = match keyword {
+        "type" => (1.0, type_acceptance(value)),
+        "const" => (value_size(value), VALUE_ACCEPTANCE),
+        "enum" => (
+            value_size(value),
+            (items_count(value) * VALUE_ACCEPTANCE).min(1.0),
+        ),
+        "pattern" => (20.0, CONSTRAINT_ACCEPTANCE),
+        "uniqueItems" => (EXPECTED_CHILDREN * EXPECTED_CHILDREN, CONSTRAINT_ACCEPTANCE),
+        "required" => (items_count(value), CONSTRAINT_ACCEPTANCE),
+        "$ref" | "format" => (10.0, CONSTRAINT_ACCEPTANCE),
+        "allOf" | "dependencies" | "items" | "properties"
+            if keyword != "items" || value.is_array() =>
+        {
+            let estimates = subschemas_estimates(value);
+            (
+                estimates.iter().map(|estimate| estimate.cost + 1.0).sum(),
+                estimates
+                    .iter()
+                    .map(|estimate| estimate.acceptance)
+                    .product(),
+            )
+        }
+        "patternProperties" => {
+            let estimates = subschemas_estimates(value);
+            (
+                estimates
+                    .iter()
+                    .map(|estimate| 20.0 * EXPECTED_CHILDREN + estimate.cost)
+                    .sum(),
+                estimates
+                    .iter()
+                    .map(|estimate| estimate.acceptance)
+                    .product(),
+            )
+        }
+        "additionalItems" | "additionalProperties" | "contains" | "items" | "propertyNames" => {
+            let subschema_estimate = estimate(value);
+            (
+                EXPECTED_CHILDREN * subschema_estimate.cost,
+                subschema_estimate.acceptance.max(CONSTRAINT_ACCEPTANCE),
+            )
+        }
+        "anyOf" | "oneOf" => {
+            let estimates = subschemas_estimates(value);
+            (
+                estimates.iter().map(|estimate| estimate.cost + 1.0).sum(),
+                estimates
+                    .iter()
+                    .map(|estimate| estimate.acceptance)
+                    .sum::<f64>()
+                    .min(1.0),
+            )
+        }
+        "not" => {
+            let subschema_estimate = estimate(value);
+            (subschema_estimate.cost, 1.0 - subschema_estimate.acceptance)
+        }
+        "if" | "then" | "else" => {
+            let subschema_estimate = estimate(value);
+            (subschema_estimate.cost, 1.0)
+        }
+        "maxItems" | "maxLength" | "maxProperties" | "maximum" | "exclusiveMaximum"
+        | "minItems" | "minLength" | "minProperties" | "minimum" | "exclusiveMinimum"
+        | "multipleOf" => (1.0, CONSTRAINT_ACCEPTANCE),
+        // Annotations and unknown keywords do not affect the validation
+        _ => (0.0, 1.0),
+    };
+    Estimate { cost, acceptance }
+}
+
+/// Sort `subschemas` by increasing `rank` of their estimates.
+/// The sort is stable, so subschemas with the same rank preserve their relative order.
+/// The method returns true if the order of the subschemas has been modified.
+/// The subschemas moved to a different position are recorded, into `edits`, as replaced.
+pub(crate) fn sort_by_rank(
+    subschemas: &mut Vec<Value>,
+    rank: fn(Estimate) -> f64,
+    edits: &mut Edits,
+) -> bool {
+    let mut ranked_subschemas: Vec<(usize, f64)> = subschemas
+        .iter()
+        .map(|subschema| rank(estimate(subschema)))
+        .enumerate()
+        .collect();
+    ranked_subschemas.sort_by(|(_, rank1), (_, rank2)| rank1.total_cmp(rank2));
+    if ranked_subschemas
+        .iter()
+        .enumerate()
+        .all(|(position, (index, _))| position == *index)
+    {
+        return false;
+    }
+    let mut unsorted_subschemas: Vec<Option<Value>> = subschemas.drain(..).map(Some).collect();
+    for (position, (index, _)) in ranked_subschemas.into_iter().enumerate() {
+        if let Some(subschema) = unsorted_subschemas[index].take() {
+            if position != index {
+                edits.replace(position, &subschema);
+            }
+            subschemas.push(subschema);
+        }
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{estimate, sort_by_rank, Estimate};
+    use crate::helpers::edits::Edits;
+    use serde_json::{json, Value};
+    use test_case::test_case;
+
+    #[test_case(&json!(true), 1.0)]
+    #[test_case(&json!(false), 0.0)]
+    #[test_case(&json!({}), 1.0)]
+    #[test_case(&json!({"title": "a"}), 1.0)]
+    #[test_case(&json!({"not": {}}), 0.0)]
+    #[test_case(&json!({"type": ["integer", "string"]}), 0.25)]
+    fn test_estimate_acceptance(schema: &Value, expected_acceptance: f64) {
+        assert!((estimate(schema).acceptance - expected_acceptance).abs() < f64::EPSILON);
+    }
+
+    #[test_case(&json!({"type": "string"}), &json!({"type": "string", "pattern": "^a"}))]
+    #[test_case(&json!({"const": 1}), &json!({"properties": {"a": {"type": "string"}, "b": {"type": "integer"}}}))]
+    #[test_case(&json!({"enum": [1, 2, 3]}), &json!({"anyOf": [{"const": 1}, {"const": 2}, {"const": 3}]}))]
+    #[test_case(&json!({"pattern": "^a"}), &json!({"propertyNames": {"pattern": "^a"}}))]
+    fn test_estimate_cost_is_lower(schema: &Value, other_schema: &Value) {
+        assert!(
+            estimate(schema).cost < estimate(other_schema).cost,
+            "{:?} is not cheaper than {:?}",
+            estimate(schema),
+            estimate(other_schema),
+        );
+    }
+
+    #[test_case(&json!({"type": "string"}), &json!({"type": ["integer", "string"]}))]
+    #[test_case(&json!({"const": 1}), &json!({"enum": [1, 2]}))]
+    #[test_case(&json!({"type": "string", "minLength": 1}), &json!({"type": "string"}))]
+    fn test_estimate_acceptance_is_lower(schema: &Value, other_schema: &Value) {
+        assert!(estimate(schema).acceptance < estimate(other_schema).acceptance);
+    }
+
+    #[test_case(json!([{"type": "string", "pattern": "^a"}, {"type": "string"}]), Estimate::all_of_rank => json!([{"type": "string"}, {"type": "string", "pattern": "^a"}]))]
+    #[test_case(json!([{"type": "string"}, {"type": "integer"}]), Estimate::all_of_rank => json!([{"type": "integer"}, {"type": "string"}]))]
+    #[test_case(json!([{"type": "string"}, {"type": "boolean"}]), Estimate::all_of_rank => json!([{"type": "string"}, {"type": "boolean"}]))]
+    #[test_case(json!([{"const": 1}, {"type": ["integer", "string"]}]), Estimate::any_of_rank => json!([{"type": ["integer", "string"]}, {"const": 1}]))]
+    #[test_case(json!([{"const": 1}, {"type": ["integer", "string"]}]), Estimate::all_of_rank => json!([{"const": 1}, {"type": ["integer", "string"]}]))]
+    fn test_sort_by_rank(mut subschemas: Value, rank: fn(Estimate) -> f64) -> Value {
+        let subschemas_array = subschemas.as_array_mut().expect("It should be there");
+        let original_subschemas = subschemas_array.clone();
+        let mut edits = Edits::recording();
+        let updated = sort_by_rank(subschemas_array, rank, &mut edits);
+        assert_eq!(updated, &original_subschemas != subschemas_array);
+        // The sort is stable, so sorting again does not modify the order
+        assert!(!sort_by_rank(subschemas_array, rank, &mut Edits::default()));
+        crate::assert_edits_transform(&Value::Array(original_subschemas), edits, &subschemas);
+        subschemas
+    }
+}
@@ -0,0 +1,185 @@
+//! Journal of the edits performed by the rules, expressed as [RFC 6902](https://tools.ietf.org/html/rfc6902) operations.
+//!
+//! The rules report their edits through the helpers they use to modify the schema (ie. [`replace::with_false_schema`]
+//! or [`preserve_keys`]), which record the operations into the journal provided by the caller.
+//!
+//! [`replace::with_false_schema`]: crate::helpers::replace::with_false_schema
+//! [`preserve_keys`]: crate::helpers::preserve_keys
+use crate::{
+    helpers::{pointer, subschemas::Location},
+    patch::PatchOperation,
+};
+use serde_json::Value;
+use std::fmt::Display;
+
+/// Journal of the edits performed on a schema, as JSON Patch operations relative to the schema.
+///
+/// The operations are recorded only if the journal is created via [`Edits::recording`], so that
+/// the rules do not pay the cost of recording them if nobody observes them.
+#[derive(Debug, Default)]
+pub(crate) struct Edits {
+    /// Recorded operations (`None` if the operations are not recorded)
+    operations: Option<Vec<PatchOperation>>,
+    /// JSON Pointer, relative to the schema, of the value currently edited
+    pointer: String,
+}
+
+impl Edits {
+    /// Journal recording the operations
+    pub(crate) fn recording() -> Self {
+        Self {
+            operations: Some(Vec::new()),
+            pointer: String::new(),
+        }
+    }
+
+    /// Recorded operations, in the order they have to be applied
+    pub(crate) fn into_operations(self) -> Vec<PatchOperation> {
+        self.operations.unwrap_or_default()
+    }
+
+    /// `true` if the recorded operations replace the whole edited value
+    pub(crate) fn replaces_value(&self) -> bool {
+        self.operations.as_ref().is_some_and(|operations| {
+            operations
+                .iter()
+                .any(|operation| operation.path() == self.pointer)
+        })
+    }
+
+    /// Run `edit` recording its edits relative to the value at `token` (a keyword or an index) of the value currently edited
+    pub(crate) fn within<R>(
+        &mut self,
+        token: impl Display,
+        edit: impl FnOnce(&mut Self) -> R,
+    ) -> R {
+        if self.operations.is_none() {
+            return edit(self);
+        }
+        let pointer_len = self.pointer.len();
+        self.pointer = pointer::append(&self.pointer, &token.to_string());
+        let result = edit(self);
+        self.pointer.truncate(pointer_len);
+        result
+    }
+
+    /// Run `edit` recording its edits relative to the subschema at `location` of the value currently edited
+    pub(crate) fn within_location<R>(
+        &mut self,
+        location: Location<'_>,
+        edit: impl FnOnce(&mut Self) -> R,
+    ) -> R {
+        match location {
+            Location::Keyword(keyword) => self.within(keyword, edit),
+            Location::Entry(keyword, key) => self.within(keyword, |edits| edits.within(key, edit)),
+            Location::Item(keyword, index) => {
+                self.within(keyword, |edits| edits.within(index, edit))
+            }
+        }
+    }
+
+    /// Record `operations`, relative to the edited value
+    #[cfg(test)]
+    pub(crate) fn extend(&mut self, operations: Vec<PatchOperation>) {
+        let pointer = &self.pointer;
+        if let Some(recorded_operations) = &mut self.operations {
+            recorded_operations.extend(
+                operations
+                    .into_iter()
+                    .map(|operation| operation.within(pointer)),
+            );
+        }
+    }
+
+    /// Record the operation built, via `operation`, from the JSON Pointer of `token` (or of the edited value if `None`)
+    fn push(
+        &mut self,
+        token: Option<&dyn Display>,
+        operation: impl FnOnce(String) -> PatchOperation,
+    ) {
+        if let Some(operations) = &mut self.operations {
+            let path = match token {
+                Some(token) => pointer::append(&self.pointer, &token.to_string()),
+                None => self.pointer.clone(),
+            };
+            operations.push(operation(path));
+        }
+    }
+
+    /// Record the addition of `value` at `token` (ie. a keyword, or an index of an array) of the edited value
+    pub(crate) fn add(&mut self, token: impl Display, value: &Value) {
+        self.push(Some(&token), |path| PatchOperation::Add {
+            path,
+            value: value.clone(),
+        });
+    }
+
+    /// Record the removal of `token` (ie. a keyword, or an index of an array) from the edited value
+    pub(crate) fn remove(&mut self, token: impl Display) {
+        self.push(Some(&token), |path| PatchOperation::Remove { path });
+    }
+
+    /// Record the replacement of the value at `token` of the edited value with `value`
+    pub(crate) fn replace(&mut self, token: impl Display, value: &Value) {
+        self.push(Some(&token), |path| PatchOperation::Replace {
+            path,
+            value: value.clone(),
+        });
+    }
+
+    /// Record the replacement of the edited value with `value`
+    pub(crate) fn replace_value(&mut self, value: &Value) {
+        self.push(None, |path| PatchOperation::Replace {
+            path,
+            value: value.clone(),
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Edits;
+    use crate::patch::PatchOperation;
+    use serde_json::json;
+
+    #[test]
+    fn test_edits_are_not_recorded_by_default() {
+        let mut edits = Edits::default();
+        edits.remove("minimum");
+        edits.within("allOf", |edits| edits.add(0, &json!(true)));
+        assert_eq!(edits.into_operations(), Vec::new());
+    }
+
+    #[test]
+    fn test_edits_are_relative_to_the_edited_value() {
+        let mut edits = Edits::recording();
+        edits.remove("minimum");
+        edits.within("properties", |edits| {
+            edits.within("a/b", |edits| edits.replace("type", &json!("string")));
+            edits.add("c", &json!(true));
+        });
+        edits.within("allOf", |edits| {
+            edits.within(1, |edits| edits.replace_value(&json!(false)));
+        });
+        assert_eq!(
+            edits.into_operations(),
+            vec![
+                PatchOperation::Remove {
+                    path: "/minimum".to_string()
+                },
+                PatchOperation::Replace {
+                    path: "/properties/a~1b/type".to_string(),
+                    value: json!("string")
+                },
+                PatchOperation::Add {
+                    path: "/properties/c".to_string(),
+                    value: json!(true)
+                },
+                PatchOperation::Replace {
+                    path: "/allOf/1".to_string(),
+                    value: json!(false)
+                },
+            ]
+        );
+    }
+}
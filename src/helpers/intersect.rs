@@ -1,5 +1,6 @@
 use crate::helpers::{
-    common_values_and_deduplicate, join_and_deduplicate, replace, types::PrimitiveTypesBitMap,
+    common_values_and_deduplicate, edits::Edits, join_and_deduplicate, replace,
+    types::PrimitiveTypesBitMap,
 };
 use serde_json::{map::Entry, Value};
 use std::ops::Deref;
@@ -92,17 +93,20 @@ fn handle_properties_related_keywords<'s>(
 ///     * The method might not be able to merge in `schema` all the restrictions imposed by `other_schema`.
 ///       This might happen because the logic has not been fully implemented yet as well as it is just not possible (`oneOf` for example cannot be merged).
 ///       For this reason you should check if the result is `IntersectStatus::Complete` or `IntersectStatus::Partial`.
+///
+/// The modifications of `schema` are recorded into `edits`.
 // The method body is very long, but I do argue that it is very simple to follow and creating helper methods would make understanding even harder
 #[allow(clippy::too_many_lines)]
 pub(crate) fn intersection_schema<'s>(
     schema: &'s mut Value,
     other_schema: &Value,
+    edits: &mut Edits,
 ) -> IntersectStatus<'s> {
     let other_schema_object = match other_schema {
         Value::Object(map) => map,
         Value::Bool(false) => {
             // if `other_schema` is a `false` schema then regrdless of `schema` all the values will be invalid. So the resulting schema is a `false` schema
-            let _ = replace::with_false_schema(schema);
+            let _ = replace::with_false_schema(schema, edits);
             return IntersectStatus::Complete {
                 schema,
                 updated_schema: true,
@@ -122,6 +126,7 @@ pub(crate) fn intersection_schema<'s>(
         Value::Bool(true) => {
             // if `self` is a `true` schema then only `other_schema` will contribute to validation constraints
             let _ = std::mem::replace(schema, other_schema.clone());
+            edits.replace_value(other_schema);
             return IntersectStatus::Complete {
                 schema,
                 updated_schema: true,
@@ -147,6 +152,7 @@ pub(crate) fn intersection_schema<'s>(
         }
         match schema_object.entry(key) {
             Entry::Vacant(entry) => {
+                edits.add(key, other_value);
                 let _ = entry.insert(other_value.clone());
                 updated_schema = true;
             }
@@ -160,12 +166,14 @@ pub(crate) fn intersection_schema<'s>(
                             if let (Value::Array(schema_items), Value::Array(other_items)) =
                                 (schema_value, other_value)
                             {
-                                updated_schema |= join_and_deduplicate(schema_items, other_items);
+                                updated_schema |= edits.within(key, |edits| {
+                                    join_and_deduplicate(schema_items, other_items, edits)
+                                });
                             }
                         }
                         "const" | "contentEncoding" | "contentMediaType" | "format" => {
                             if schema_value != other_value {
-                                let _ = replace::with_false_schema(schema);
+                                let _ = replace::with_false_schema(schema, edits);
                                 return IntersectStatus::Complete {
                                     schema,
                                     updated_schema: true,
@@ -173,17 +181,26 @@ pub(crate) fn intersection_schema<'s>(
                             }
                         }
                         "contains" | "propertyNames" => {
-                            updated_schema |=
-                                intersection_schema(schema_value, other_value).is_schema_updated();
+                            let (is_complete, is_updated) = edits.within(key, |edits| {
+                                let intersect_status =
+                                    intersection_schema(schema_value, other_value, edits);
+                                (
+                                    intersect_status.is_complete_intersection(),
+                                    intersect_status.is_schema_updated(),
+                                )
+                            });
+                            is_complete_intersection &= is_complete;
+                            updated_schema |= is_updated;
                         }
                         "enum" => {
                             if let (Value::Array(schema_items), Value::Array(other_items)) =
                                 (schema_value, other_value)
                             {
-                                updated_schema |=
-                                    common_values_and_deduplicate(schema_items, other_items);
+                                updated_schema |= edits.within(key, |edits| {
+                                    common_values_and_deduplicate(schema_items, other_items, edits)
+                                });
                                 if schema_items.is_empty() {
-                                    let _ = replace::with_false_schema(schema);
+                                    let _ = replace::with_false_schema(schema, edits);
                                     return IntersectStatus::Complete {
                                         schema,
                                         updated_schema: true,
@@ -194,6 +211,7 @@ pub(crate) fn intersection_schema<'s>(
                         "exclusiveMaximum" | "maxItems" | "maxLength" | "maxProperties"
                         | "maximum" => {
                             if other_value.as_f64() < schema_value.as_f64() {
+                                edits.replace(key, other_value);
                                 let _ = entry.insert(other_value.clone());
                                 updated_schema |= true;
                             }
@@ -201,6 +219,7 @@ pub(crate) fn intersection_schema<'s>(
                         "exclusiveMinimum" | "minItems" | "minLength" | "minProperties"
                         | "minimum" => {
                             if other_value.as_f64() > schema_value.as_f64() {
+                                edits.replace(key, other_value);
                                 let _ = entry.insert(other_value.clone());
                                 updated_schema |= true;
                             }
@@ -216,10 +235,10 @@ pub(crate) fn intersection_schema<'s>(
                                 schema_primitive_types & other_primitive_types;
                             if schema_primitive_types != final_primiive_types {
                                 updated_schema = true;
-                                if !replace::type_with(schema_object, final_primiive_types)
+                                if !replace::type_with(schema_object, final_primiive_types, edits)
                                     || schema_object.get("type") == None
                                 {
-                                    let _ = replace::with_false_schema(schema);
+                                    let _ = replace::with_false_schema(schema, edits);
                                     return IntersectStatus::Complete {
                                         schema,
                                         updated_schema: true,
@@ -230,7 +249,10 @@ pub(crate) fn intersection_schema<'s>(
                         "uniqueItems" => {
                             if &Value::Bool(true) == other_value {
                                 let old_value = entry.insert(Value::Bool(true));
-                                updated_schema |= old_value != Value::Bool(true);
+                                if old_value != Value::Bool(true) {
+                                    edits.replace(key, &Value::Bool(true));
+                                    updated_schema = true;
+                                }
                             }
                         }
 
@@ -288,6 +310,7 @@ pub(crate) fn intersection_schema<'s>(
 #[cfg(test)]
 mod tests {
     use super::intersection_schema;
+    use crate::helpers::edits::Edits;
     use serde_json::{json, Value};
     use test_case::test_case;
 
@@ -711,10 +734,14 @@ mod tests {
         test(
             schema,
             other,
-            &|mut schema, other| {
-                let intersect_status = intersection_schema(&mut schema, other);
+            &|schema, other| {
+                let original_schema = schema.clone();
+                let mut edits = Edits::recording();
+                let intersect_status = intersection_schema(schema, other, &mut edits);
                 assert_eq!(&*intersect_status, expected_schema);
-                intersect_status.is_schema_updated()
+                let is_schema_updated = intersect_status.is_schema_updated();
+                crate::assert_edits_transform(&original_schema, edits, schema);
+                is_schema_updated
             },
             expected_schema,
             valid,
@@ -0,0 +1,291 @@
+//! Checks of the schema against the rules of the Draft4, Draft6 and Draft7 meta-schemas.
+//!
+//! The checks accept the union of the drafts (ie. `exclusiveMaximum` could be a boolean or a number) and
+//! are limited to what the optimisation rules rely on (ie. `pattern` is not compiled).
+use crate::error::Error;
+use crate::helpers::subschemas::for_each_subschema_with_pointer;
+use crate::options::Options;
+use crate::primitive_type::PrimitiveType;
+use serde_json::Value;
+use std::convert::TryFrom;
+
+/// Checks if `value` is a schema (an object or a boolean)
+fn is_schema(value: &Value) -> bool {
+    value.is_object() || value.is_boolean()
+}
+
+/// Checks if `value` is a non-negative integer (ie. `1` or `1.0`)
+fn is_non_negative_integer(value: &Value) -> bool {
+    value.is_u64()
+        || value
+            .as_f64()
+            .is_some_and(|number| number >= 0.0 && number.fract() == 0.0)
+}
+
+/// Checks if `value` is an array of strings
+fn is_array_of_strings(value: &Value) -> bool {
+    value
+        .as_array()
+        .is_some_and(|items| items.iter().all(Value::is_string))
+}
+
+/// Description of the kind of value expected by `keyword`, if `value` is not of such kind
+fn unexpected_value_kind(keyword: &str, value: &Value) -> Option<&'static str> {
+    let (is_expected_kind, expected_kind) = match keyword {
+        "maxItems" | "maxLength" | "maxProperties" | "minItems" | "minLength" | "minProperties" => {
+            (is_non_negative_integer(value), "a non-negative integer")
+        }
+        "maximum" | "minimum" => (value.is_number(), "a number"),
+        "exclusiveMaximum" | "exclusiveMinimum" => (
+            value.is_number() || value.is_boolean(),
+            "a number or a boolean",
+        ),
+        "multipleOf" => (
+            value.as_f64().is_some_and(|number| number > 0.0),
+            "a number strictly greater than 0",
+        ),
+        "contentEncoding" | "contentMediaType" | "format" | "pattern" => {
+            (value.is_string(), "a string")
+        }
+        "uniqueItems" => (value.is_boolean(), "a boolean"),
+        "enum" => (value.is_array(), "an array"),
+        "required" => (is_array_of_strings(value), "an array of strings"),
+        "type" => (
+            value.is_string()
+                || (is_array_of_strings(value)
+                    && value.as_array().is_some_and(|items| !items.is_empty())),
+            "a string or a non-empty array of strings",
+        ),
+        "additionalItems"
+        | "additionalProperties"
+        | "contains"
+        | "else"
+        | "if"
+        | "not"
+        | "propertyNames"
+        | "then" => (is_schema(value), "a schema"),
+        "items" => (
+            is_schema(value)
+                || value
+                    .as_array()
+                    .is_some_and(|items| items.iter().all(is_schema)),
+            "a schema or an array of schemas",
+        ),
+        "allOf" | "anyOf" | "oneOf" => (
+            value
+                .as_array()
+                .is_some_and(|items| !items.is_empty() && items.iter().all(is_schema)),
+            "a non-empty array of schemas",
+        ),
+        "definitions" | "patternProperties" | "properties" => (
+            value
+                .as_object()
+                .is_some_and(|object| object.values().all(is_schema)),
+            "an object of schemas",
+        ),
+        "dependencies" => (
+            value.as_object().is_some_and(|dependencies| {
+                dependencies
+                    .values()
+                    .all(|dependency| is_schema(dependency) || is_array_of_strings(dependency))
+            }),
+            "an object of schemas or arrays of strings",
+        ),
+        _ => return None,
+    };
+    if is_expected_kind {
+        None
+    } else {
+        Some(expected_kind)
+    }
+}
+
+/// First duplicated string of `value`, if it is an array of strings
+fn duplicated_string(value: &Value) -> Option<&str> {
+    let items = value.as_array()?;
+    items.iter().enumerate().find_map(|(index, item)| {
+        let string = item.as_str()?;
+        if items[..index].contains(item) {
+            Some(string)
+        } else {
+            None
+        }
+    })
+}
+
+/// Checks the value of `keyword` of the schema referenced by `pointer`
+fn check_keyword(pointer: &str, keyword: &str, value: &Value) -> Result<(), Error> {
+    if let Some(expected) = unexpected_value_kind(keyword, value) {
+        return Err(Error::InvalidKeywordValue {
+            pointer: pointer.to_string(),
+            keyword: keyword.to_string(),
+            expected,
+        });
+    }
+
+    let duplicated_item = match (keyword, value) {
+        ("required" | "type", _) => duplicated_string(value),
+        ("dependencies", Value::Object(dependencies)) => {
+            dependencies.values().find_map(duplicated_string)
+        }
+        _ => None,
+    };
+    if let Some(item) = duplicated_item {
+        return Err(Error::DuplicatedItem {
+            pointer: pointer.to_string(),
+            keyword: keyword.to_string(),
+            item: item.to_string(),
+        });
+    }
+
+    if keyword == "type" {
+        check_primitive_type_names(pointer, value)?;
+    }
+    Ok(())
+}
+
+/// Report the first name, in the value of the `type` keyword, that is not a primitive type.
+/// `pointer` is the JSON Pointer of the schema containing `type`.
+fn check_primitive_type_names(pointer: &str, value: &Value) -> Result<(), Error> {
+    let type_names = match value {
+        Value::Array(items) => items.iter().collect(),
+        _ => vec![value],
+    };
+    if let Some(type_name) = type_names
+        .into_iter()
+        .find(|type_name| PrimitiveType::try_from(*type_name).is_err())
+    {
+        return Err(Error::UnknownPrimitiveType {
+            pointer: pointer.to_string(),
+            primitive_type: type_name.as_str().unwrap_or_default().to_string(),
+        });
+    }
+    Ok(())
+}
+
+/// Report the first name that is not a primitive type (ie. `{"type": "strnig"}`) in the `type` keyword of `schema`
+/// and all its subschemas (including the ones in `definitions`). `pointer` is the JSON Pointer of `schema`.
+///
+/// The rules consider the unknown names as not allowing any instance, so optimising such schemas would
+/// turn a typo into a different validation outcome.
+pub(crate) fn check_primitive_types(
+    schema: &Value,
+    pointer: &str,
+    options: &Options,
+) -> Result<(), Error> {
+    if let Some(value) = schema.get("type") {
+        check_primitive_type_names(pointer, value)?;
+    }
+    let mut result = Ok(());
+    for_each_subschema_with_pointer(
+        schema,
+        pointer,
+        &|keyword| options.is_custom_subschema_keyword(keyword),
+        &mut |subschema_pointer, subschema| {
+            if result.is_ok() {
+                result = check_primitive_types(subschema, &subschema_pointer, options);
+            }
+        },
+    );
+    result
+}
+
+/// Checks `schema`, and all its subschemas (including the ones in `definitions`), against the rules of the meta-schemas.
+/// `pointer` is the JSON Pointer of `schema`.
+pub(crate) fn check(schema: &Value, pointer: &str, options: &Options) -> Result<(), Error> {
+    let schema_object = match schema {
+        Value::Bool(_) => return Ok(()),
+        Value::Object(schema_object) => schema_object,
+        _ => {
+            return Err(Error::InvalidSchema {
+                pointer: pointer.to_string(),
+            })
+        }
+    };
+    for (keyword, value) in schema_object {
+        if options.is_custom_subschema_keyword(keyword) && !is_schema(value) {
+            return Err(Error::InvalidKeywordValue {
+                pointer: pointer.to_string(),
+                keyword: keyword.clone(),
+                expected: "a schema",
+            });
+        }
+        check_keyword(pointer, keyword, value)?;
+    }
+
+    let mut result = Ok(());
+    for_each_subschema_with_pointer(
+        schema,
+        pointer,
+        &|keyword| options.is_custom_subschema_keyword(keyword),
+        &mut |subschema_pointer, subschema| {
+            // The kind of the subschemas is already checked, so only the objects have to be checked
+            // (ie. the arrays of strings of `dependencies` are not schemas)
+            if result.is_ok() && subschema.is_object() {
+                result = check(subschema, &subschema_pointer, options);
+            }
+        },
+    );
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{check, check_primitive_types};
+    use crate::error::Error;
+    use crate::options::{CustomKeyword, Options};
+    use serde_json::{json, Value};
+    use test_case::test_case;
+
+    fn invalid_keyword_value(
+        pointer: &str,
+        keyword: &str,
+        expected: &'static str,
+    ) -> Result<(), Error> {
+        Err(Error::InvalidKeywordValue {
+            pointer: pointer.to_string(),
+            keyword: keyword.to_string(),
+            expected,
+        })
+    }
+
+    #[test_case(&json!(true) => Ok(()))]
+    #[test_case(&json!(1) => Err(Error::InvalidSchema { pointer: String::new() }))]
+    #[test_case(
+        &json!({
+            "type": ["integer", "string"], "minLength": 1.0, "maximum": 1.5, "exclusiveMinimum": true, "multipleOf": 0.5,
+            "required": [], "enum": [1, 1], "items": [true, {}], "allOf": [{}], "dependencies": {"a": ["b"], "b": {}},
+            "x-foo": 1,
+        })
+        => Ok(())
+    )]
+    #[test_case(&json!({"type": "strnig"}) => Err(Error::UnknownPrimitiveType { pointer: String::new(), primitive_type: "strnig".to_string() }))]
+    #[test_case(&json!({"properties": {"a": {"type": ["string", "strnig"]}}}) => Err(Error::UnknownPrimitiveType { pointer: "/properties/a".to_string(), primitive_type: "strnig".to_string() }))]
+    #[test_case(&json!({"type": []}) => invalid_keyword_value("", "type", "a string or a non-empty array of strings"))]
+    #[test_case(&json!({"type": ["string", "string"]}) => Err(Error::DuplicatedItem { pointer: String::new(), keyword: "type".to_string(), item: "string".to_string() }))]
+    #[test_case(&json!({"minLength": -1}) => invalid_keyword_value("", "minLength", "a non-negative integer"))]
+    #[test_case(&json!({"maxItems": 1.5}) => invalid_keyword_value("", "maxItems", "a non-negative integer"))]
+    #[test_case(&json!({"multipleOf": 0}) => invalid_keyword_value("", "multipleOf", "a number strictly greater than 0"))]
+    #[test_case(&json!({"required": ["a", "b", "a"]}) => Err(Error::DuplicatedItem { pointer: String::new(), keyword: "required".to_string(), item: "a".to_string() }))]
+    #[test_case(&json!({"required": "a"}) => invalid_keyword_value("", "required", "an array of strings"))]
+    #[test_case(&json!({"not": 1}) => invalid_keyword_value("", "not", "a schema"))]
+    #[test_case(&json!({"allOf": []}) => invalid_keyword_value("", "allOf", "a non-empty array of schemas"))]
+    #[test_case(&json!({"items": [{"minimum": "1"}]}) => invalid_keyword_value("/items/0", "minimum", "a number"))]
+    #[test_case(&json!({"dependencies": {"a": ["b", "b"]}}) => Err(Error::DuplicatedItem { pointer: String::new(), keyword: "dependencies".to_string(), item: "b".to_string() }))]
+    #[test_case(&json!({"definitions": {"a": {"pattern": 1}}}) => invalid_keyword_value("/definitions/a", "pattern", "a string"))]
+    #[test_case(&json!({"x-schema": 1}) => invalid_keyword_value("", "x-schema", "a schema"))]
+    #[test_case(&json!({"x-schema": {"uniqueItems": 1}}) => invalid_keyword_value("/x-schema", "uniqueItems", "a boolean"))]
+    fn test_check(schema: &Value) -> Result<(), Error> {
+        let options = Options::new()
+            .register_keyword("x-schema", CustomKeyword::assertion().with_subschema());
+        check(schema, "", &options)
+    }
+
+    #[test_case(&json!({"type": "string"}) => Ok(()))]
+    #[test_case(&json!({"type": ["string", "null"], "minimum": "1"}) => Ok(()))]
+    #[test_case(&json!({"type": "strnig"}) => Err(Error::UnknownPrimitiveType { pointer: String::new(), primitive_type: "strnig".to_string() }))]
+    #[test_case(&json!({"definitions": {"a": {"type": ["objcet"]}}}) => Err(Error::UnknownPrimitiveType { pointer: "/definitions/a".to_string(), primitive_type: "objcet".to_string() }))]
+    fn test_check_primitive_types(schema: &Value) -> Result<(), Error> {
+        check_primitive_types(schema, "", &Options::new())
+    }
+}
@@ -1,9 +1,17 @@
+pub(crate) mod annotations;
+pub(crate) mod cost;
+pub(crate) mod edits;
 pub(crate) mod intersect;
 pub(crate) mod is;
+pub(crate) mod meta_schema;
+pub(crate) mod pointer;
 pub(crate) mod replace;
+pub(crate) mod subschemas;
 pub(crate) mod types;
+pub(crate) mod unknown_keywords;
 
 use crate::constants::KEYWORDS;
+use edits::Edits;
 use serde_json::{map::Entry, Map, Value};
 use std::collections::HashSet;
 
@@ -18,6 +26,7 @@ fn keywords_to_remove(keywords_to_preserve: &HashSet<&'static str>) -> HashSet<&
 pub(crate) fn preserve_keys(
     map: &mut Map<String, Value>,
     keys_to_preserve: &HashSet<&'static str>,
+    edits: &mut Edits,
 ) -> bool {
     let remove_keywords: HashSet<&str> = keywords_to_remove(keys_to_preserve);
     let keys_to_remove: Vec<String> = map
@@ -31,6 +40,7 @@ pub(crate) fn preserve_keys(
         .filter(|key_to_remove| {
             if let Entry::Occupied(entry) = map.entry(*key_to_remove) {
                 let _ = entry.remove();
+                edits.remove(key_to_remove);
                 true
             } else {
                 false
@@ -49,13 +59,14 @@ pub(crate) fn preserve_keys(
 /// length is "small" and we can eventualy make this assumption. An alternative would be to convert all the items to
 /// something ordinable/hashable (like via `ToString::to_string`) but it would require more memory and still a
 /// linear loop for the set creation.
-fn join_and_deduplicate(schema: &mut Vec<Value>, other: &[Value]) -> bool {
+fn join_and_deduplicate(schema: &mut Vec<Value>, other: &[Value], edits: &mut Edits) -> bool {
     let mut added_values = false;
     for other_value in other {
         if !schema
             .iter()
             .any(|schema_value| schema_value == other_value)
         {
+            edits.add(schema.len(), other_value);
             schema.push(other_value.clone());
             added_values = true;
         }
@@ -72,8 +83,11 @@ fn join_and_deduplicate(schema: &mut Vec<Value>, other: &[Value]) -> bool {
 /// length is "small" and we can eventualy make this assumption. An alternative would be to convert all the items to
 /// something ordinable/hashable (like via `ToString::to_string`) but it would require more memory and still a
 /// linear loop for the set creation.
-fn common_values_and_deduplicate(schema: &mut Vec<Value>, other: &[Value]) -> bool {
-    let mut removed_values = false;
+fn common_values_and_deduplicate(
+    schema: &mut Vec<Value>,
+    other: &[Value],
+    edits: &mut Edits,
+) -> bool {
     let schema_indexes_to_remove: Vec<usize> = schema
         .iter()
         .enumerate()
@@ -85,18 +99,14 @@ fn common_values_and_deduplicate(schema: &mut Vec<Value>, other: &[Value]) -> bo
             }
         })
         .collect();
-    for index_to_remove in schema_indexes_to_remove.iter().rev() {
-        let _ = schema.remove(*index_to_remove);
-        removed_values = true;
-    }
-    removed_values
+    replace::remove_items(schema, &schema_indexes_to_remove, edits)
 }
 
 #[cfg(test)]
 mod tests {
     use super::{
-        common_values_and_deduplicate, join_and_deduplicate, keywords_to_remove, preserve_keys,
-        KEYWORDS,
+        common_values_and_deduplicate, edits::Edits, join_and_deduplicate, keywords_to_remove,
+        preserve_keys, KEYWORDS,
     };
     use serde_json::{json, Value};
     use std::collections::HashSet;
@@ -153,10 +163,14 @@ mod tests {
         mut map: Value,
         keywords_to_remove: &HashSet<&'static str>,
     ) -> Value {
+        let original_map = map.clone();
+        let mut edits = Edits::recording();
         let _ = preserve_keys(
             map.as_object_mut().expect("It should be there"),
             keywords_to_remove,
+            &mut edits,
         );
+        crate::assert_edits_transform(&original_map, edits, &map);
         map
     }
 
@@ -165,10 +179,14 @@ mod tests {
     #[test_case(json!([1, 3, 5]), &json!([3, 2, 1, 5]) => json!([1, 3, 5, 2]))]
     #[test_case(json!([]), &json!([3, 2, 1, 5]) => json!([3, 2, 1, 5]))]
     fn test_join_and_deduplicate(mut schema: Value, other: &Value) -> Value {
+        let original_schema = schema.clone();
+        let mut edits = Edits::recording();
         let _ = join_and_deduplicate(
             schema.as_array_mut().expect("It should be there"),
             other.as_array().expect("It should be there"),
+            &mut edits,
         );
+        crate::assert_edits_transform(&original_schema, edits, &schema);
         schema
     }
 
@@ -178,10 +196,14 @@ mod tests {
     #[test_case(json!([1, 1, 3, 5]), &json!([3, 2, 1, 5]) => json!([1, 1, 3, 5]))]
     #[test_case(json!([]), &json!([3, 2, 1, 5]) => json!([]))]
     fn test_common_values_and_deduplicate(mut schema: Value, other: &Value) -> Value {
+        let original_schema = schema.clone();
+        let mut edits = Edits::recording();
         let _ = common_values_and_deduplicate(
             schema.as_array_mut().expect("It should be there"),
             other.as_array().expect("It should be there"),
+            &mut edits,
         );
+        crate::assert_edits_transform(&original_schema, edits, &schema);
         schema
     }
 }
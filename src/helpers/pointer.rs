@@ -0,0 +1,108 @@
+//! Helpers to handle JSON Pointers as defined by [RFC 6901](https://tools.ietf.org/html/rfc6901)
+use serde_json::Value;
+
+/// Escape `token` in order to be used as reference token of a JSON Pointer
+pub(crate) fn escape(token: &str) -> String {
+    token.replace('~', "~0").replace('/', "~1")
+}
+
+/// Unescape the reference token of a JSON Pointer
+pub(crate) fn unescape(token: &str) -> String {
+    token.replace("~1", "/").replace("~0", "~")
+}
+
+/// Unescaped reference tokens of `pointer`
+pub(crate) fn tokens(pointer: &str) -> impl Iterator<Item = String> + '_ {
+    pointer.split('/').skip(1).map(unescape)
+}
+
+/// Checks if the value referenced by `pointer` contains the value referenced by `other_pointer`
+pub(crate) fn is_ancestor(pointer: &str, other_pointer: &str) -> bool {
+    other_pointer
+        .strip_prefix(pointer)
+        .is_some_and(|suffix| suffix.starts_with('/'))
+}
+
+/// Checks if the value referenced by `pointer`, or any of its descendants, is one of `targets`.
+/// Moving, or removing, such value would break the references targeting it.
+pub(crate) fn is_targeted(pointer: &str, targets: &[String]) -> bool {
+    targets
+        .iter()
+        .any(|target| target == pointer || is_ancestor(pointer, target))
+}
+
+/// Collect the JSON Pointers targeted by the local references (ie. `#/properties/a`) present in `value`
+pub(crate) fn collect_reference_targets(value: &Value, targets: &mut Vec<String>) {
+    match value {
+        Value::Object(object) => {
+            if let Some(Value::String(reference)) = object.get("$ref") {
+                if let Some(target) = reference.strip_prefix('#') {
+                    targets.push(target.to_string());
+                }
+            }
+            object
+                .values()
+                .for_each(|value| collect_reference_targets(value, targets));
+        }
+        Value::Array(items) => items
+            .iter()
+            .for_each(|value| collect_reference_targets(value, targets)),
+        _ => {}
+    }
+}
+
+/// Build the JSON Pointer referencing `token` within the value referenced by `pointer`
+pub(crate) fn append(pointer: &str, token: &str) -> String {
+    format!("{}/{}", pointer, escape(token))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{append, escape, is_ancestor, is_targeted, tokens, unescape};
+    use test_case::test_case;
+
+    #[test_case("property" => "property")]
+    #[test_case("a/b" => "a~1b")]
+    #[test_case("a~b" => "a~0b")]
+    #[test_case("~/" => "~0~1")]
+    fn test_escape(token: &str) -> String {
+        escape(token)
+    }
+
+    #[test_case("", "properties" => "/properties")]
+    #[test_case("/properties", "a/b" => "/properties/a~1b")]
+    #[test_case("/items", "0" => "/items/0")]
+    fn test_append(pointer: &str, token: &str) -> String {
+        append(pointer, token)
+    }
+
+    #[test_case("a~1b" => "a/b")]
+    #[test_case("~01" => "~1")]
+    fn test_unescape(token: &str) -> String {
+        unescape(token)
+    }
+
+    #[test_case("" => Vec::<String>::new())]
+    #[test_case("/properties/a~1b/0" => vec!["properties", "a/b", "0"])]
+    fn test_tokens(pointer: &str) -> Vec<String> {
+        tokens(pointer).collect()
+    }
+
+    #[test_case("", "/a" => true)]
+    #[test_case("", "" => false)]
+    #[test_case("/a", "/a/b" => true)]
+    #[test_case("/a", "/ab" => false)]
+    #[test_case("/a/b", "/a" => false)]
+    fn test_is_ancestor(pointer: &str, other_pointer: &str) -> bool {
+        is_ancestor(pointer, other_pointer)
+    }
+
+    #[test_case("/anyOf", &[] => false)]
+    #[test_case("/anyOf", &["/anyOf"] => true)]
+    #[test_case("/anyOf", &["/properties/a", "/anyOf/0"] => true)]
+    #[test_case("/anyOf/0", &["/anyOf"] => false)]
+    fn test_is_targeted(pointer: &str, targets: &[&str]) -> bool {
+        let targets: Vec<String> = targets.iter().map(ToString::to_string).collect();
+        is_targeted(pointer, &targets)
+    }
+}
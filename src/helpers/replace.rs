@@ -1,4 +1,4 @@
-use crate::helpers::types::PrimitiveTypesBitMap;
+use crate::helpers::{edits::Edits, types::PrimitiveTypesBitMap};
 
 use serde_json::{map::Entry, Map, Value};
 
@@ -12,11 +12,12 @@ use std::mem::replace;
 /// Using `std::mem::replace` ensures that the value stored in `schema` is dropped
 /// once leaving the scope of the method
 #[inline]
-pub(crate) fn with_false_schema(schema: &mut Value) -> bool {
+pub(crate) fn with_false_schema(schema: &mut Value, edits: &mut Edits) -> bool {
     if schema == &Value::Bool(false) {
         false
     } else {
         let _ = replace(schema, Value::Bool(false));
+        edits.replace_value(schema);
         true
     }
 }
@@ -29,11 +30,12 @@ pub(crate) fn with_false_schema(schema: &mut Value) -> bool {
 /// Using `std::mem::replace` ensures that the value stored in `schema` is dropped
 /// once leaving the scope of the method
 #[inline]
-pub(crate) fn with_true_schema(schema: &mut Value) -> bool {
+pub(crate) fn with_true_schema(schema: &mut Value, edits: &mut Edits) -> bool {
     if schema == &Value::Bool(true) {
         false
     } else {
         let _ = replace(schema, Value::Bool(true));
+        edits.replace_value(schema);
         true
     }
 }
@@ -47,32 +49,69 @@ pub(crate) fn with_true_schema(schema: &mut Value) -> bool {
 pub(crate) fn type_with(
     schema_object: &mut Map<String, Value>,
     primitive_types: PrimitiveTypesBitMap,
+    edits: &mut Edits,
 ) -> bool {
-    match schema_object.entry("type") {
+    match primitive_types.to_schema_value() {
+        Some(json_primitive_types) => {
+            keyword_with(schema_object, "type", json_primitive_types, edits)
+        }
+        None => remove_keyword(schema_object, "type", edits).is_some(),
+    }
+}
+
+/// Replace/Define the `keyword` of `schema_object` with `value`.
+/// The method returns true if a schema modification occurred.
+pub(crate) fn keyword_with(
+    schema_object: &mut Map<String, Value>,
+    keyword: &str,
+    value: Value,
+    edits: &mut Edits,
+) -> bool {
+    match schema_object.entry(keyword) {
         Entry::Vacant(entry) => {
-            if let Some(json_primitive_types) = primitive_types.to_schema_value() {
-                let _ = entry.insert(json_primitive_types);
-                true
-            } else {
-                false
-            }
+            edits.add(keyword, &value);
+            let _ = entry.insert(value);
+            true
         }
         Entry::Occupied(mut entry) => {
-            if let Some(json_primitive_types) = primitive_types.to_schema_value() {
-                let previous_value = entry.insert(json_primitive_types.clone());
-                previous_value != json_primitive_types
+            if entry.get() == &value {
+                false
             } else {
-                let _ = entry.remove();
+                edits.replace(keyword, &value);
+                let _ = entry.insert(value);
                 true
             }
         }
     }
 }
 
+/// Remove the `keyword` from `schema_object`, returning its value (if it was defined)
+pub(crate) fn remove_keyword(
+    schema_object: &mut Map<String, Value>,
+    keyword: &str,
+    edits: &mut Edits,
+) -> Option<Value> {
+    let value = schema_object.remove(keyword);
+    if value.is_some() {
+        edits.remove(keyword);
+    }
+    value
+}
+
+/// Remove the items of `items` in position `indexes` (sorted in ascending order).
+/// The method returns true if a schema modification occurred.
+pub(crate) fn remove_items(items: &mut Vec<Value>, indexes: &[usize], edits: &mut Edits) -> bool {
+    for index in indexes.iter().rev() {
+        let _ = items.remove(*index);
+        edits.remove(index);
+    }
+    !indexes.is_empty()
+}
+
 #[cfg(test)]
 mod tests {
-    use super::{type_with, with_false_schema, with_true_schema};
-    use crate::helpers::types::PrimitiveTypesBitMap;
+    use super::{keyword_with, remove_items, type_with, with_false_schema, with_true_schema};
+    use crate::helpers::{edits::Edits, types::PrimitiveTypesBitMap};
     use crate::primitive_type::PrimitiveType;
     use serde_json::{json, Value};
 
@@ -93,9 +132,15 @@ mod tests {
     #[test_case(json!(false))]
     #[test_case(json!(true))]
     fn test_with_false_schema(mut schema: Value) {
+        let original_schema = schema.clone();
         let was_a_false_schema = schema == Value::Bool(false);
-        assert_eq!(with_false_schema(&mut schema), !was_a_false_schema);
+        let mut edits = Edits::recording();
+        assert_eq!(
+            with_false_schema(&mut schema, &mut edits),
+            !was_a_false_schema
+        );
         assert_eq!(schema, Value::Bool(false));
+        crate::assert_edits_transform(&original_schema, edits, &schema);
     }
 
     #[test_case(json!({}))]
@@ -103,9 +148,15 @@ mod tests {
     #[test_case(json!(false))]
     #[test_case(json!(true))]
     fn test_with_true_schema(mut schema: Value) {
+        let original_schema = schema.clone();
         let was_simplest_true_schema = schema == Value::Bool(true);
-        assert_eq!(with_true_schema(&mut schema), !was_simplest_true_schema);
+        let mut edits = Edits::recording();
+        assert_eq!(
+            with_true_schema(&mut schema, &mut edits),
+            !was_simplest_true_schema
+        );
         assert_eq!(schema, Value::Bool(true));
+        crate::assert_edits_transform(&original_schema, edits, &schema);
     }
 
     #[test_case(json!({}), bit_map!(PrimitiveType::Boolean), true => json!({"type": "boolean"}))]
@@ -122,13 +173,47 @@ mod tests {
         primitive_types: PrimitiveTypesBitMap,
         is_modified: bool,
     ) -> Value {
+        let original_schema = schema.clone();
+        let mut edits = Edits::recording();
         assert_eq!(
             type_with(
                 &mut schema.as_object_mut().expect("It should be there"),
-                primitive_types
+                primitive_types,
+                &mut edits,
             ),
             is_modified
         );
+        crate::assert_edits_transform(&original_schema, edits, &schema);
+        schema
+    }
+
+    #[test_case(json!({}), "maxItems", json!(1) => json!({"maxItems": 1}))]
+    #[test_case(json!({"maxItems": 2}), "maxItems", json!(1) => json!({"maxItems": 1}))]
+    #[test_case(json!({"maxItems": 1}), "maxItems", json!(1) => json!({"maxItems": 1}))]
+    fn test_keyword_with(mut schema: Value, keyword: &str, value: Value) -> Value {
+        let original_schema = schema.clone();
+        let mut edits = Edits::recording();
+        let is_modified = keyword_with(
+            schema.as_object_mut().expect("It should be there"),
+            keyword,
+            value,
+            &mut edits,
+        );
+        assert_eq!(is_modified, original_schema != schema);
+        crate::assert_edits_transform(&original_schema, edits, &schema);
+        schema
+    }
+
+    #[test_case(json!({"enum": [1, 2, 3, 4]}), &[] => json!({"enum": [1, 2, 3, 4]}))]
+    #[test_case(json!({"enum": [1, 2, 3, 4]}), &[0, 2, 3] => json!({"enum": [2]}))]
+    fn test_remove_items(mut schema: Value, indexes: &[usize]) -> Value {
+        let original_schema = schema.clone();
+        let mut edits = Edits::recording();
+        if let Some(Value::Array(items)) = schema.get_mut("enum") {
+            let is_modified = edits.within("enum", |edits| remove_items(items, indexes, edits));
+            assert_eq!(is_modified, !indexes.is_empty());
+        }
+        crate::assert_edits_transform(&original_schema, edits, &schema);
         schema
     }
 }
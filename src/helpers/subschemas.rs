@@ -0,0 +1,206 @@
+//! Helpers to visit the subschemas directly contained by a schema.
+//!
+//! The subschemas are identified according to [`KEYWORDS_WITH_SUBSCHEMAS`] and [`KEYWORDS_WITH_DIRECT_SUBSCHEMAS`]
+//! (eventually extended by the keywords registered via [`Options::register_keyword`](crate::Options::register_keyword)).
+use crate::constants::{KEYWORDS_WITH_DIRECT_SUBSCHEMAS, KEYWORDS_WITH_SUBSCHEMAS};
+use crate::helpers::pointer;
+use serde_json::Value;
+
+/// Checks if the value of `keyword` MUST be a valid JSON Schema.
+/// `items` could be a valid JSON Schema or an array of valid JSON Schemas.
+#[inline]
+fn is_direct_subschema(keyword: &str, value: &Value) -> bool {
+    KEYWORDS_WITH_DIRECT_SUBSCHEMAS.contains(keyword) || (keyword == "items" && !value.is_array())
+}
+
+/// Call `visitor` on all the subschemas directly contained by `schema`
+pub(crate) fn for_each_subschema<'s>(schema: &'s Value, visitor: &mut impl FnMut(&'s Value)) {
+    let Value::Object(schema_object) = schema else {
+        return;
+    };
+    for (keyword, value) in schema_object {
+        if is_direct_subschema(keyword, value) {
+            // ie. `{"additionalProperties": {"type": "string"}}`
+            visitor(value);
+        } else if KEYWORDS_WITH_SUBSCHEMAS.contains(keyword.as_str()) {
+            match value {
+                // ie. `{"properties": {"property" {"type": "string"}}}`
+                Value::Object(object) => object.values().for_each(&mut *visitor),
+                // ie. `{"allOf": [{"type": "string"}]}`
+                Value::Array(array) => array.iter().for_each(&mut *visitor),
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Location of a subschema within the schema directly containing it
+#[derive(Clone, Copy, Debug)]
+pub(crate) enum Location<'k> {
+    /// ie. `{"not": {"type": "string"}}`
+    Keyword(&'k str),
+    /// ie. `{"properties": {"property": {"type": "string"}}}`
+    Entry(&'k str, &'k str),
+    /// ie. `{"allOf": [{"type": "string"}]}`
+    Item(&'k str, usize),
+}
+
+impl<'k> Location<'k> {
+    /// Keyword holding the subschema
+    pub(crate) fn keyword(self) -> &'k str {
+        match self {
+            Self::Keyword(keyword) | Self::Entry(keyword, _) | Self::Item(keyword, _) => keyword,
+        }
+    }
+
+    /// JSON Pointer of the subschema, given the JSON Pointer of the schema directly containing it
+    pub(crate) fn pointer(self, schema_pointer: &str) -> String {
+        match self {
+            Self::Keyword(keyword) => pointer::append(schema_pointer, keyword),
+            Self::Entry(keyword, key) => {
+                pointer::append(&pointer::append(schema_pointer, keyword), key)
+            }
+            Self::Item(keyword, index) => pointer::append(
+                &pointer::append(schema_pointer, keyword),
+                &index.to_string(),
+            ),
+        }
+    }
+}
+
+/// Call `visitor` on all the subschemas directly contained by `schema`, together with their [`Location`],
+/// allowing their modification.
+/// The values of the keywords satisfying `is_custom_subschema_keyword` are considered subschemas as well.
+pub(crate) fn for_each_subschema_mut_with_location<'s>(
+    schema: &'s mut Value,
+    is_custom_subschema_keyword: &impl Fn(&str) -> bool,
+    visitor: &mut impl FnMut(Location<'_>, &'s mut Value),
+) {
+    let Value::Object(schema_object) = schema else {
+        return;
+    };
+    for (keyword, value) in schema_object {
+        if is_direct_subschema(keyword, value) || is_custom_subschema_keyword(keyword) {
+            visitor(Location::Keyword(keyword), value);
+        } else if KEYWORDS_WITH_SUBSCHEMAS.contains(keyword.as_str()) {
+            match value {
+                Value::Object(object) => {
+                    for (key, subschema) in object {
+                        visitor(Location::Entry(keyword, key), subschema);
+                    }
+                }
+                Value::Array(array) => {
+                    for (index, subschema) in array.iter_mut().enumerate() {
+                        visitor(Location::Item(keyword, index), subschema);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Call `visitor` on all the subschemas directly contained by `schema` (including the ones in `definitions`),
+/// together with their JSON Pointer (built by appending their location within `schema` to `pointer`).
+/// The values of the keywords satisfying `is_custom_subschema_keyword` are considered subschemas as well.
+pub(crate) fn for_each_subschema_with_pointer<'s>(
+    schema: &'s Value,
+    pointer: &str,
+    is_custom_subschema_keyword: &impl Fn(&str) -> bool,
+    visitor: &mut impl FnMut(String, &'s Value),
+) {
+    let Value::Object(schema_object) = schema else {
+        return;
+    };
+    for (keyword, value) in schema_object {
+        let keyword_pointer = pointer::append(pointer, keyword);
+        if is_direct_subschema(keyword, value) || is_custom_subschema_keyword(keyword) {
+            visitor(keyword_pointer, value);
+        } else if KEYWORDS_WITH_SUBSCHEMAS.contains(keyword.as_str()) || keyword == "definitions" {
+            match value {
+                Value::Object(object) => {
+                    for (key, subschema) in object {
+                        visitor(pointer::append(&keyword_pointer, key), subschema);
+                    }
+                }
+                Value::Array(array) => {
+                    for (index, subschema) in array.iter().enumerate() {
+                        visitor(
+                            pointer::append(&keyword_pointer, &index.to_string()),
+                            subschema,
+                        );
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        for_each_subschema, for_each_subschema_mut_with_location, for_each_subschema_with_pointer,
+    };
+    use serde_json::{json, Value};
+    use test_case::test_case;
+
+    #[test_case(&json!(true) => Vec::<Value>::new())]
+    #[test_case(&json!({"const": {"type": "string"}, "enum": [{}], "required": ["a"]}) => Vec::<Value>::new())]
+    #[test_case(&json!({"not": true, "items": {"type": "string"}}) => vec![json!({"type": "string"}), json!(true)])]
+    #[test_case(&json!({"items": [{"type": "string"}, false]}) => vec![json!({"type": "string"}), json!(false)])]
+    #[test_case(&json!({"properties": {"a": {}}, "anyOf": [{"minimum": 1}]}) => vec![json!({"minimum": 1}), json!({})])]
+    #[test_case(&json!({"dependencies": {"a": ["b"], "b": {"required": ["a"]}}}) => vec![json!(["b"]), json!({"required": ["a"]})])]
+    fn test_for_each_subschema(schema: &Value) -> Vec<Value> {
+        let mut subschemas = Vec::new();
+        for_each_subschema(schema, &mut |subschema| subschemas.push(subschema.clone()));
+        subschemas
+    }
+
+    #[test_case(json!({"not": {}, "x-schema": {}, "x-other": {}}) => json!({"not": true, "x-schema": true, "x-other": {}}))]
+    fn test_for_each_subschema_mut_with(mut schema: Value) -> Value {
+        for_each_subschema_mut_with_location(
+            &mut schema,
+            &|keyword| keyword == "x-schema",
+            &mut |_, subschema| {
+                *subschema = Value::Bool(true);
+            },
+        );
+        schema
+    }
+
+    #[test_case(&json!(true) => Vec::<(String, Value)>::new())]
+    #[test_case(
+        &json!({"not": true, "items": [{}], "properties": {"a/b": {}}, "definitions": {"c": {}}, "x-schema": {}, "x-other": {}})
+        => vec![
+            ("/definitions/c".to_string(), json!({})),
+            ("/items/0".to_string(), json!({})),
+            ("/not".to_string(), json!(true)),
+            ("/properties/a~1b".to_string(), json!({})),
+            ("/x-schema".to_string(), json!({})),
+        ]
+    )]
+    fn test_for_each_subschema_with_pointer(schema: &Value) -> Vec<(String, Value)> {
+        let mut subschemas = Vec::new();
+        for_each_subschema_with_pointer(
+            schema,
+            "",
+            &|keyword| keyword == "x-schema",
+            &mut |pointer, subschema| {
+                subschemas.push((pointer, subschema.clone()));
+            },
+        );
+        subschemas
+    }
+
+    #[test_case(json!({"not": {}, "properties": {"a/b": {}}, "allOf": [{}], "x-schema": {}}) => vec!["/root/allOf/0", "/root/not", "/root/properties/a~1b", "/root/x-schema"])]
+    fn test_for_each_subschema_mut_with_location(mut schema: Value) -> Vec<String> {
+        let mut pointers = Vec::new();
+        for_each_subschema_mut_with_location(
+            &mut schema,
+            &|keyword| keyword == "x-schema",
+            &mut |location, _| pointers.push(location.pointer("/root")),
+        );
+        pointers
+    }
+}
@@ -3,7 +3,7 @@ use serde_json::Value;
 use std::collections::BTreeSet;
 use std::convert::TryFrom;
 use std::fmt;
-use std::ops::{BitAnd, BitOrAssign};
+use std::ops::{BitAnd, BitOr, BitOrAssign};
 
 /// Bitmap representing primitive types. Conceptually this is equivalent to a `BTreeSet<PrimitiveType>` or `HashSet<PrimitiveType>`
 /// but allows for a better memory and temporal characteristics.
@@ -37,12 +37,26 @@ impl BitAnd<PrimitiveTypesBitMap> for PrimitiveTypesBitMap {
     }
 }
 
+impl BitOr<PrimitiveTypesBitMap> for PrimitiveTypesBitMap {
+    type Output = Self;
+
+    fn bitor(self, value: PrimitiveTypesBitMap) -> Self::Output {
+        Self(self.0 | value.0)
+    }
+}
+
 impl BitOrAssign<PrimitiveType> for PrimitiveTypesBitMap {
     fn bitor_assign(&mut self, value: PrimitiveType) {
         self.0 |= value.to_bit_representation();
     }
 }
 
+impl BitOrAssign<PrimitiveTypesBitMap> for PrimitiveTypesBitMap {
+    fn bitor_assign(&mut self, value: PrimitiveTypesBitMap) {
+        self.0 |= value.0;
+    }
+}
+
 lazy_static::lazy_static! {
     static ref PRIMITIVE_TYPES_BIT_MAP_ALL_TYPES: u8 =
         PrimitiveType::Array.to_bit_representation() |
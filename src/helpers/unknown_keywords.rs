@@ -0,0 +1,109 @@
+use crate::error::Error;
+use crate::helpers::{
+    edits::Edits,
+    subschemas::{for_each_subschema_mut_with_location, for_each_subschema_with_pointer},
+};
+use crate::options::Options;
+use serde_json::Value;
+
+/// Report the first unknown keyword (see [`Options::is_unknown_keyword`]) of `schema` and all its subschemas
+/// (including the ones in `definitions`). `pointer` is the JSON Pointer of `schema`.
+pub(crate) fn check(schema: &Value, pointer: &str, options: &Options) -> Result<(), Error> {
+    if let Value::Object(schema_object) = schema {
+        if let Some(keyword) = schema_object
+            .keys()
+            .find(|keyword| options.is_unknown_keyword(keyword))
+        {
+            return Err(Error::UnknownKeyword {
+                pointer: pointer.to_string(),
+                keyword: keyword.clone(),
+            });
+        }
+    }
+    let mut result = Ok(());
+    for_each_subschema_with_pointer(
+        schema,
+        pointer,
+        &|keyword| options.is_custom_subschema_keyword(keyword),
+        &mut |subschema_pointer, subschema| {
+            if result.is_ok() {
+                result = check(subschema, &subschema_pointer, options);
+            }
+        },
+    );
+    result
+}
+
+/// Remove the unknown keywords (see [`Options::is_unknown_keyword`]) from `schema` and all its subschemas
+/// (including the ones in `definitions`).
+/// The method returns true if a schema modification occurred.
+pub(crate) fn strip(schema: &mut Value, options: &Options, edits: &mut Edits) -> bool {
+    let mut updated_schema = false;
+    if let Value::Object(schema_object) = schema {
+        let unknown_keywords: Vec<String> = schema_object
+            .keys()
+            .filter(|keyword| options.is_unknown_keyword(keyword))
+            .cloned()
+            .collect();
+        for keyword in &unknown_keywords {
+            let _ = schema_object.remove(keyword);
+            edits.remove(keyword);
+        }
+        updated_schema |= !unknown_keywords.is_empty();
+        if let Some(Value::Object(definitions)) = schema_object.get_mut("definitions") {
+            edits.within("definitions", |edits| {
+                for (name, definition) in definitions.iter_mut() {
+                    updated_schema |= edits.within(name, |edits| strip(definition, options, edits));
+                }
+            });
+        }
+    }
+    for_each_subschema_mut_with_location(
+        schema,
+        &|keyword| options.is_custom_subschema_keyword(keyword),
+        &mut |location, subschema| {
+            updated_schema |=
+                edits.within_location(location, |edits| strip(subschema, options, edits));
+        },
+    );
+    updated_schema
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{check, strip};
+    use crate::error::Error;
+    use crate::helpers::edits::Edits;
+    use crate::options::{CustomKeyword, Options};
+    use serde_json::{json, Value};
+    use test_case::test_case;
+
+    fn options() -> Options {
+        Options::new().register_keyword("x-schema", CustomKeyword::assertion().with_subschema())
+    }
+
+    #[test_case(&json!(true) => Ok(()))]
+    #[test_case(&json!({"$schema": "http://json-schema.org/draft-07/schema#", "title": "a", "type": "string"}) => Ok(()))]
+    #[test_case(&json!({"x-schema": {"type": "string"}}) => Ok(()))]
+    #[test_case(&json!({"minimun": 1}) => Err(Error::UnknownKeyword { pointer: String::new(), keyword: "minimun".to_string() }))]
+    #[test_case(&json!({"properties": {"a/b": {"x-foo": 1}}}) => Err(Error::UnknownKeyword { pointer: "/properties/a~1b".to_string(), keyword: "x-foo".to_string() }))]
+    #[test_case(&json!({"definitions": {"a": {"x-foo": 1}}}) => Err(Error::UnknownKeyword { pointer: "/definitions/a".to_string(), keyword: "x-foo".to_string() }))]
+    #[test_case(&json!({"x-schema": {"x-foo": 1}}) => Err(Error::UnknownKeyword { pointer: "/x-schema".to_string(), keyword: "x-foo".to_string() }))]
+    #[test_case(&json!({"const": {"x-foo": 1}}) => Ok(()))]
+    fn test_check(schema: &Value) -> Result<(), Error> {
+        check(schema, "", &options())
+    }
+
+    #[test_case(json!(true) => json!(true))]
+    #[test_case(json!({"x-foo": 1}) => json!({}))]
+    #[test_case(json!({"title": "a", "minimun": 1, "type": "string"}) => json!({"title": "a", "type": "string"}))]
+    #[test_case(json!({"items": [{"x-foo": 1}], "definitions": {"a": {"x-foo": 1}}}) => json!({"items": [{}], "definitions": {"a": {}}}))]
+    #[test_case(json!({"x-schema": {"x-foo": 1}}) => json!({"x-schema": {}}))]
+    fn test_strip(mut schema: Value) -> Value {
+        let original_schema = schema.clone();
+        let mut edits = Edits::recording();
+        let _ = strip(&mut schema, &options(), &mut edits);
+        crate::assert_edits_transform(&original_schema, edits, &schema);
+        schema
+    }
+}
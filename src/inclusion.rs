@@ -0,0 +1,630 @@
+//! Inclusion analysis of JSON Schemas.
+//!
+//! A schema `A` is included into (is a subschema of) a schema `B` if every instance valid
+//! against `A` is valid against `B` as well.
+//!
+//! The inclusion is proven by decomposing `B` into its conjuncts and verifying that the restrictions
+//! imposed by each conjunct are already imposed by `A` (this is done via `intersection_schema`).
+//! If the proof fails we look for a counterexample by sampling the abstract domain of `A`.
+use crate::{
+    helpers::{
+        edits::Edits,
+        intersect::{intersection_schema, IntersectStatus},
+        is,
+        types::PrimitiveTypesBitMap,
+    },
+    jsonschema_equivalent,
+    primitive_type::PrimitiveType,
+    satisfiability::{is_satisfiable, pattern_matches, validate, Domain, Satisfiability},
+};
+use serde_json::{json, Map, Value};
+use std::convert::TryFrom;
+
+/// Outcome of the inclusion analysis of two schemas.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Inclusion {
+    /// All the instances valid against the first schema are valid against the second schema
+    Yes,
+    /// There are instances that are valid against the first schema but not valid against the second schema.
+    /// If possible, one of such instances is reported as counterexample.
+    No(Option<Value>),
+    /// The analysis was not able to determine if the first schema is included into the second one
+    Unknown,
+}
+
+/// Schema used when a keyword is not defined (ie. `additionalProperties`)
+static TRUE_SCHEMA: Value = Value::Bool(true);
+
+/// Schema used for positions that can not be present (ie. after `maxItems`)
+static FALSE_SCHEMA: Value = Value::Bool(false);
+
+/// Keywords that are not handled by `intersection_schema` and so needs dedicated logic
+static APPLICATOR_KEYWORDS: &[&str] = &[
+    "additionalItems",
+    "additionalProperties",
+    "allOf",
+    "anyOf",
+    "else",
+    "if",
+    "items",
+    "not",
+    "oneOf",
+    "patternProperties",
+    "properties",
+    "then",
+];
+
+/// Keywords defining numeric and length bounds, whose inclusion is verified via the abstract domain
+static BOUND_KEYWORDS: &[&str] = &[
+    "exclusiveMaximum",
+    "exclusiveMinimum",
+    "maxItems",
+    "maxLength",
+    "maxProperties",
+    "maximum",
+    "minItems",
+    "minLength",
+    "minProperties",
+    "minimum",
+];
+
+/// Depth of the sample values generated while looking for counterexamples
+static SAMPLE_VALUES_DEPTH: usize = 3;
+
+/// Maximum number of nested inclusion proofs, needed to bound the proof of schemas that
+/// are not reduced by their decomposition
+static MAX_INCLUSION_DEPTH: usize = 32;
+
+/// Pairs of schemas whose inclusion is being proven
+type Visiting = Vec<(Value, Value)>;
+
+/// Optimised schema representing the conjunction of `schemas`
+fn conjunction(schemas: &[&Value]) -> Value {
+    jsonschema_equivalent(json!({ "allOf": schemas }))
+}
+
+/// Check if `schema` imposes all the restrictions of `other_schema_core`, which contains no applicators
+fn is_core_included(schema: &Value, mut other_schema_core: Map<String, Value>) -> bool {
+    let other_schema_bounds: Map<String, Value> = BOUND_KEYWORDS
+        .iter()
+        .filter_map(|keyword| {
+            other_schema_core
+                .remove(*keyword)
+                .map(|value| ((*keyword).to_string(), value))
+        })
+        .collect();
+    let other_schema_bounds = Value::Object(other_schema_bounds);
+    if !Domain::from_schema(schema).implies_bounds_of(&Domain::from_schema(&other_schema_bounds)) {
+        return false;
+    }
+
+    if other_schema_core.is_empty() {
+        return true;
+    }
+    let other_schema_core = Value::Object(other_schema_core);
+    let mut intersection = schema.clone();
+    if let IntersectStatus::Complete {
+        updated_schema: false,
+        ..
+    } = intersection_schema(&mut intersection, &other_schema_core, &mut Edits::default())
+    {
+        // `other_schema_core` does not add any restriction to `schema`
+        true
+    } else {
+        // Let the optimiser drop the restrictions that are not relevant for `schema` (ie. `minLength` on numbers)
+        &conjunction(&[schema, &other_schema_core]) == schema
+    }
+}
+
+/// Check the inclusion of the `properties`, `patternProperties` and `additionalProperties` restrictions
+fn are_properties_included(
+    schema_object: &Map<String, Value>,
+    other_schema_object: &Map<String, Value>,
+    visiting: &mut Visiting,
+) -> bool {
+    let other_properties = other_schema_object
+        .get("properties")
+        .and_then(Value::as_object);
+    let other_pattern_properties = other_schema_object
+        .get("patternProperties")
+        .and_then(Value::as_object);
+    let other_additional_properties = other_schema_object.get("additionalProperties");
+
+    let properties = schema_object.get("properties").and_then(Value::as_object);
+    let pattern_properties = schema_object
+        .get("patternProperties")
+        .and_then(Value::as_object)
+        .filter(|pattern_properties| !pattern_properties.is_empty());
+    let additional_properties = schema_object
+        .get("additionalProperties")
+        .unwrap_or(&TRUE_SCHEMA);
+
+    // Schema applied to the property `key` of instances valid against `schema`.
+    // If a pattern of `patternProperties` can not be matched against `key` we don't know which schemas apply.
+    let property_schema = |key: &str| {
+        let mut schemas: Vec<&Value> = properties
+            .and_then(|properties| properties.get(key))
+            .into_iter()
+            .collect();
+        for (pattern, pattern_schema) in pattern_properties.into_iter().flatten() {
+            if pattern_matches(pattern, key)? {
+                schemas.push(pattern_schema);
+            }
+        }
+        Some(match schemas.as_slice() {
+            [] => additional_properties.clone(),
+            [schema] => (*schema).clone(),
+            _ => conjunction(&schemas),
+        })
+    };
+
+    if let Some(other_properties) = other_properties {
+        for (key, other_property_schema) in other_properties {
+            if is::true_schema(other_property_schema) {
+                continue;
+            }
+            match property_schema(key) {
+                Some(property_schema)
+                    if is_included(&property_schema, other_property_schema, visiting) => {}
+                _ => return false,
+            }
+        }
+    }
+
+    if let Some(other_pattern_properties) = other_pattern_properties {
+        for (pattern, other_pattern_schema) in other_pattern_properties {
+            if is::true_schema(other_pattern_schema) {
+                continue;
+            }
+            if let Some(pattern_schema) =
+                pattern_properties.and_then(|pattern_properties| pattern_properties.get(pattern))
+            {
+                if is_included(pattern_schema, other_pattern_schema, visiting) {
+                    continue;
+                }
+            }
+            // An anchored literal pattern (ie. `^a$`) matches a single property
+            let matched_property = pattern
+                .strip_prefix('^')
+                .and_then(|pattern| pattern.strip_suffix('$'))
+                .filter(|key| pattern_matches(pattern, key) == Some(true));
+            match matched_property.and_then(property_schema) {
+                Some(property_schema)
+                    if is_included(&property_schema, other_pattern_schema, visiting) => {}
+                _ => return false,
+            }
+        }
+    }
+
+    if let Some(other_additional_properties) = other_additional_properties {
+        if !is::true_schema(other_additional_properties) {
+            // All the schemas that could be applied to a property not listed into `other_schema` properties
+            let mut candidate_schemas: Vec<&Value> = properties
+                .iter()
+                .flat_map(|properties| properties.iter())
+                .filter(|(key, _)| {
+                    !other_properties
+                        .is_some_and(|other_properties| other_properties.contains_key(key.as_str()))
+                })
+                .map(|(_, property_schema)| property_schema)
+                .collect();
+            candidate_schemas.extend(
+                pattern_properties
+                    .iter()
+                    .flat_map(|pattern_properties| pattern_properties.values()),
+            );
+            candidate_schemas.push(additional_properties);
+            if !candidate_schemas.iter().all(|candidate_schema| {
+                is_included(candidate_schema, other_additional_properties, visiting)
+            }) {
+                return false;
+            }
+        }
+    }
+    true
+}
+
+/// Check the inclusion of the `items` and `additionalItems` restrictions
+fn are_items_included(
+    schema_object: &Map<String, Value>,
+    other_schema_object: &Map<String, Value>,
+    visiting: &mut Visiting,
+) -> bool {
+    let Some(other_items) = other_schema_object.get("items") else {
+        // `additionalItems` is ignored if `items` is not defined
+        return true;
+    };
+    let items = schema_object.get("items");
+    let items_count = match items {
+        Some(Value::Array(items)) => items.len(),
+        _ => 0,
+    };
+    // Positions after `maxItems` are never present, so they are not restricted at all
+    let max_items = schema_object
+        .get("maxItems")
+        .and_then(Value::as_u64)
+        .and_then(|max_items| usize::try_from(max_items).ok())
+        .unwrap_or(usize::MAX);
+    let additional_items = match items {
+        _ if max_items <= items_count => &FALSE_SCHEMA,
+        Some(Value::Array(_)) => schema_object.get("additionalItems").unwrap_or(&TRUE_SCHEMA),
+        _ => items.unwrap_or(&TRUE_SCHEMA),
+    };
+    // Schema applied to the item in position `index` of instances valid against `schema`
+    let item_schema = |index: usize| match items {
+        _ if index >= max_items => &FALSE_SCHEMA,
+        Some(Value::Array(items)) => items.get(index).unwrap_or(additional_items),
+        _ => additional_items,
+    };
+
+    if let Value::Array(other_items) = other_items {
+        for (index, other_item_schema) in other_items.iter().enumerate() {
+            if !is_included(item_schema(index), other_item_schema, visiting) {
+                return false;
+            }
+        }
+        if let Some(other_additional_items) = other_schema_object.get("additionalItems") {
+            return (other_items.len()..items_count.max(other_items.len()))
+                .map(item_schema)
+                .chain(std::iter::once(additional_items))
+                .all(|schema| is_included(schema, other_additional_items, visiting));
+        }
+        true
+    } else {
+        (0..items_count)
+            .map(item_schema)
+            .chain(std::iter::once(additional_items))
+            .all(|schema| is_included(schema, other_items, visiting))
+    }
+}
+
+/// Check, by decomposing `other_schema` in its conjuncts, if all the instances valid against `schema`
+/// are valid against `other_schema`.
+fn is_included_in_conjuncts(
+    schema: &Value,
+    other_schema_object: &Map<String, Value>,
+    visiting: &mut Visiting,
+) -> bool {
+    let other_schema_core: Map<String, Value> = other_schema_object
+        .iter()
+        .filter(|(key, _)| !APPLICATOR_KEYWORDS.contains(&key.as_str()))
+        .map(|(key, value)| (key.clone(), value.clone()))
+        .collect();
+    if !is_core_included(schema, other_schema_core) {
+        return false;
+    }
+
+    let primitive_types = PrimitiveTypesBitMap::from_schema(schema);
+    if let Value::Object(schema_object) = schema {
+        if primitive_types.contains(PrimitiveType::Object)
+            && !are_properties_included(schema_object, other_schema_object, visiting)
+        {
+            return false;
+        }
+        if primitive_types.contains(PrimitiveType::Array)
+            && !are_items_included(schema_object, other_schema_object, visiting)
+        {
+            return false;
+        }
+    } else if other_schema_object
+        .keys()
+        .any(|key| APPLICATOR_KEYWORDS.contains(&key.as_str()))
+    {
+        // `schema` is a `true` schema
+        return false;
+    }
+
+    if let Some(Value::Array(all_of)) = other_schema_object.get("allOf") {
+        if !all_of
+            .iter()
+            .all(|subschema| is_included(schema, subschema, visiting))
+        {
+            return false;
+        }
+    }
+    if let Some(Value::Array(any_of)) = other_schema_object.get("anyOf") {
+        if !any_of
+            .iter()
+            .any(|subschema| is_included(schema, subschema, visiting))
+        {
+            return false;
+        }
+    }
+    if let Some(Value::Array(one_of)) = other_schema_object.get("oneOf") {
+        // `schema` has to be included in one branch and to not intersect all the others
+        let is_included_in_one_branch = one_of.iter().enumerate().any(|(index, subschema)| {
+            is_included(schema, subschema, visiting)
+                && one_of
+                    .iter()
+                    .enumerate()
+                    .filter(|(other_index, _)| *other_index != index)
+                    .all(|(_, other_subschema)| {
+                        is_satisfiable(&json!({ "allOf": [schema, other_subschema] }))
+                            == Satisfiability::No
+                    })
+        });
+        if !is_included_in_one_branch {
+            return false;
+        }
+    }
+    if let Some(not_schema) = other_schema_object.get("not") {
+        if is_satisfiable(&json!({ "allOf": [schema, not_schema] })) != Satisfiability::No {
+            return false;
+        }
+    }
+    if let Some(if_schema) = other_schema_object.get("if") {
+        let then_schema = other_schema_object.get("then").unwrap_or(&TRUE_SCHEMA);
+        let else_schema = other_schema_object.get("else").unwrap_or(&TRUE_SCHEMA);
+        let is_then_included = is::true_schema(then_schema)
+            || is_included(&conjunction(&[schema, if_schema]), then_schema, visiting);
+        let is_else_included = is::true_schema(else_schema)
+            || is_included(schema, else_schema, visiting)
+            || is_included(schema, if_schema, visiting);
+        if !is_then_included || !is_else_included {
+            return false;
+        }
+    }
+    true
+}
+
+/// Check, by decomposing `schema` in its disjuncts, if all the instances valid against `schema`
+/// are valid against `other_schema`.
+fn are_disjuncts_included(
+    schema_object: &Map<String, Value>,
+    other_schema: &Value,
+    visiting: &mut Visiting,
+) -> bool {
+    let schema_without = |keywords: &[&str]| {
+        Value::Object(
+            schema_object
+                .iter()
+                .filter(|(key, _)| !keywords.contains(&key.as_str()))
+                .map(|(key, value)| (key.clone(), value.clone()))
+                .collect(),
+        )
+    };
+
+    for keyword in &["anyOf", "oneOf"] {
+        if let Some(Value::Array(branches)) = schema_object.get(*keyword) {
+            let rest = schema_without(&[keyword]);
+            if branches
+                .iter()
+                .all(|branch| is_included(&conjunction(&[&rest, branch]), other_schema, visiting))
+            {
+                return true;
+            }
+        }
+    }
+    if let Some(Value::Array(all_of)) = schema_object.get("allOf") {
+        if all_of
+            .iter()
+            .any(|branch| is_included(branch, other_schema, visiting))
+        {
+            return true;
+        }
+    }
+    if let Some(if_schema) = schema_object.get("if") {
+        let rest = schema_without(&["else", "if", "then"]);
+        let then_schema = schema_object.get("then").unwrap_or(&TRUE_SCHEMA);
+        let else_schema = schema_object.get("else").unwrap_or(&TRUE_SCHEMA);
+        if is_included(
+            &conjunction(&[&rest, if_schema, then_schema]),
+            other_schema,
+            visiting,
+        ) && is_included(&conjunction(&[&rest, else_schema]), other_schema, visiting)
+        {
+            return true;
+        }
+    }
+    false
+}
+
+/// Prove that all the instances valid against `schema` are valid against `other_schema`.
+///
+/// Returning `false` does not imply that `schema` is not included into `other_schema`.
+/// The proof of a pair of schemas might require the proof of the same pair (ie. the conjunction of the
+/// `else` branch and the rest of the schema could be optimised back into the schema itself), so the proof
+/// is abandoned for pairs that are already being proven and once `MAX_INCLUSION_DEPTH` is reached.
+fn is_included(schema: &Value, other_schema: &Value, visiting: &mut Visiting) -> bool {
+    if is::true_schema(other_schema) || is::false_schema(schema) || schema == other_schema {
+        return true;
+    }
+    if is_satisfiable(schema) == Satisfiability::No {
+        return true;
+    }
+    if let Value::Object(schema_object) = schema {
+        if schema_object.contains_key("$ref") {
+            // References are not resolved and the keywords next to `$ref` are ignored, so we cannot reason about the schema
+            return false;
+        }
+    }
+    if visiting.len() >= MAX_INCLUSION_DEPTH
+        || visiting
+            .iter()
+            .any(|(visiting_schema, visiting_other_schema)| {
+                visiting_schema == schema && visiting_other_schema == other_schema
+            })
+    {
+        return false;
+    }
+
+    visiting.push((schema.clone(), other_schema.clone()));
+    let is_included = match (schema, other_schema) {
+        (_, Value::Object(other_schema_object))
+            if is_included_in_conjuncts(schema, other_schema_object, visiting) =>
+        {
+            true
+        }
+        (Value::Object(schema_object), _) => {
+            are_disjuncts_included(schema_object, other_schema, visiting)
+        }
+        _ => false,
+    };
+    let _ = visiting.pop();
+    is_included
+}
+
+/// Find an instance that is valid against `schema` and not valid against `other_schema`
+fn find_counterexample(schema: &Value, other_schema: &Value) -> Option<Value> {
+    let mut domains = vec![Domain::from_schema(schema)];
+    if let Value::Object(schema_object) = schema {
+        // The abstract domain of disjunctions is an over-approximation, so we sample each branch as well
+        for keyword in &["anyOf", "oneOf"] {
+            if let Some(Value::Array(branches)) = schema_object.get(*keyword) {
+                domains.extend(
+                    branches
+                        .iter()
+                        .map(|branch| Domain::from_schemas(&[schema, branch])),
+                );
+            }
+        }
+        if let Some(if_schema) = schema_object.get("if") {
+            if let Some(then_schema) = schema_object.get("then") {
+                domains.push(Domain::from_schemas(&[schema, if_schema, then_schema]));
+            }
+            if let Some(else_schema) = schema_object.get("else") {
+                domains.push(Domain::from_schemas(&[schema, else_schema]));
+            }
+        }
+    }
+
+    let outer_domains = Domain::from_schema(other_schema).outer_domains();
+    let boundary_domains: Vec<_> = domains
+        .iter()
+        .flat_map(|domain| {
+            outer_domains.iter().map(move |outer_domain| {
+                let mut domain = domain.clone();
+                domain.and(outer_domain);
+                domain
+            })
+        })
+        .collect();
+
+    domains
+        .iter()
+        .chain(boundary_domains.iter())
+        .flat_map(|domain| domain.sample_values(SAMPLE_VALUES_DEPTH))
+        // The samples are drawn from over-approximations, so they are reported only if `validate` (which evaluates
+        // `patternProperties` and reports `Unknown` for the patterns it can not match) proves them to be counterexamples
+        .find(|value| {
+            validate(schema, value) == Satisfiability::Yes
+                && validate(other_schema, value) == Satisfiability::No
+        })
+}
+
+/// Determine if all the instances valid against `schema` are valid against `other_schema`.
+///
+/// The analysis is sound: `Inclusion::Yes` and `Inclusion::No` are reported only if the analysis
+/// has been able to prove it, otherwise `Inclusion::Unknown` is returned.
+/// ```rust
+/// use jsonschema_equivalent::{is_subschema, Inclusion};
+/// use serde_json::json;
+///
+/// assert_eq!(is_subschema(&json!({"type": "integer", "minimum": 1}), &json!({"type": "number"})), Inclusion::Yes);
+/// assert_eq!(is_subschema(&json!({"type": "number"}), &json!({"type": "integer"})), Inclusion::No(Some(json!(0.5))));
+/// assert_eq!(is_subschema(&json!({"pattern": "^a"}), &json!({"pattern": "^b"})), Inclusion::Unknown);
+/// ```
+#[must_use]
+pub fn is_subschema(schema: &Value, other_schema: &Value) -> Inclusion {
+    let schema = jsonschema_equivalent(schema.clone());
+    let other_schema = jsonschema_equivalent(other_schema.clone());
+
+    if is_included(&schema, &other_schema, &mut Visiting::new()) {
+        Inclusion::Yes
+    } else if let Some(counterexample) = find_counterexample(&schema, &other_schema) {
+        Inclusion::No(Some(counterexample))
+    } else if is_satisfiable(&schema) == Satisfiability::Yes
+        && is_satisfiable(&other_schema) == Satisfiability::No
+    {
+        Inclusion::No(None)
+    } else {
+        Inclusion::Unknown
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{is_subschema, Inclusion};
+    use serde_json::{json, Value};
+    use test_case::test_case;
+
+    fn assert_counterexample(schema: &Value, other_schema: &Value) {
+        match is_subschema(schema, other_schema) {
+            Inclusion::No(Some(counterexample)) => {
+                assert!(
+                    jsonschema::is_valid(schema, &counterexample),
+                    "{} is not valid against {}",
+                    counterexample,
+                    schema
+                );
+                assert!(
+                    !jsonschema::is_valid(other_schema, &counterexample),
+                    "{} is valid against {}",
+                    counterexample,
+                    other_schema
+                );
+            }
+            inclusion => panic!("Expected a counterexample, found {:?}", inclusion),
+        }
+    }
+
+    #[test_case(&json!(false), &json!({"type": "string"}))]
+    #[test_case(&json!({"type": "string"}), &json!(true))]
+    #[test_case(&json!({"type": "string", "minLength": 2, "maxLength": 1}), &json!({"type": "integer"}))]
+    #[test_case(&json!({"type": "integer"}), &json!({"type": "number"}))]
+    #[test_case(&json!({"type": "integer", "minimum": 5}), &json!({"type": "number", "minimum": 0}))]
+    #[test_case(&json!({"type": "number", "minimum": 2e-17}), &json!({"type": "number", "minimum": 1e-17}))]
+    #[test_case(&json!({"type": "string", "minLength": 3}), &json!({"minLength": 2}))]
+    #[test_case(&json!({"type": "string"}), &json!({"maximum": 2}))]
+    #[test_case(&json!({"required": ["a", "b"]}), &json!({"required": ["b"]}))]
+    #[test_case(&json!({"enum": [1, 2]}), &json!({"enum": [1, 2, 3]}))]
+    #[test_case(&json!({"const": "a"}), &json!({"type": "string"}))]
+    #[test_case(&json!({"type": "string", "pattern": "^a"}), &json!({"type": "string"}))]
+    #[test_case(&json!({"properties": {"a": {"type": "integer"}}}), &json!({"properties": {"a": {"type": "number"}}}))]
+    #[test_case(&json!({"properties": {"a": true}, "additionalProperties": false}), &json!({"additionalProperties": {"type": "string"}, "properties": {"a": true}}))]
+    #[test_case(&json!({"type": "array", "items": [{"type": "integer"}], "additionalItems": false}), &json!({"items": {"type": "number"}}))]
+    #[test_case(&json!({"anyOf": [{"type": "integer"}, {"type": "string"}]}), &json!({"type": ["number", "string"]}))]
+    #[test_case(&json!({"type": "integer"}), &json!({"anyOf": [{"type": "string"}, {"type": "number"}]}))]
+    #[test_case(&json!({"type": "integer"}), &json!({"oneOf": [{"type": "string"}, {"type": "number"}]}))]
+    #[test_case(&json!({"type": "integer"}), &json!({"not": {"type": "string"}}))]
+    #[test_case(&json!({"type": "integer", "minimum": 1}), &json!({"if": {"type": "integer"}, "then": {"minimum": 0}}))]
+    #[test_case(&json!({"type": "object", "properties": {"a": true}, "patternProperties": {"^a$": {"type": "string"}}}), &json!({"properties": {"a": {"type": "string"}}}))]
+    #[test_case(&json!({"type": "object", "properties": {"a": {"type": "string"}}}), &json!({"properties": {"a": true}, "patternProperties": {"^a$": {"type": "string"}}}))]
+    fn test_is_subschema_yes(schema: &Value, other_schema: &Value) {
+        crate::init_logger();
+        assert_eq!(is_subschema(schema, other_schema), Inclusion::Yes);
+    }
+
+    #[test_case(&json!({"type": "number"}), &json!({"type": "integer"}))]
+    #[test_case(&json!({"type": "number", "minimum": 0}), &json!({"type": "number", "minimum": 1}))]
+    #[test_case(&json!({"type": "number", "maximum": 10}), &json!({"type": "number", "exclusiveMaximum": 10}))]
+    #[test_case(&json!({"type": "number", "minimum": 1e-17}), &json!({"type": "number", "minimum": 2e-17}))]
+    #[test_case(&json!({"type": "string"}), &json!({"type": "string", "maxLength": 3}))]
+    #[test_case(&json!({"type": "object"}), &json!({"type": "object", "required": ["a"]}))]
+    #[test_case(&json!({"enum": [1, 2, 3]}), &json!({"enum": [1, 2]}))]
+    #[test_case(&json!({"type": "object", "properties": {"a": {"type": ["integer", "string"]}}}), &json!({"properties": {"a": {"type": "string"}}}))]
+    #[test_case(&json!({"type": "object"}), &json!({"additionalProperties": false}))]
+    #[test_case(&json!({"type": "array", "items": {"type": "string"}}), &json!({"type": "array", "maxItems": 0}))]
+    #[test_case(&json!({"anyOf": [{"type": "integer"}, {"type": "string"}]}), &json!({"type": "integer"}))]
+    #[test_case(&json!({"type": "string"}), &json!(false))]
+    #[test_case(&json!({"type": "object", "properties": {"a": true}, "patternProperties": {"^b$": {"type": "string"}}}), &json!({"properties": {"a": {"type": "string"}}}))]
+    #[test_case(&json!({"allOf": [{"if": {"type": "string"}, "else": {"required": ["a"]}, "properties": {"b": {}}}]}), &json!({"minimum": 3}))]
+    fn test_is_subschema_no(schema: &Value, other_schema: &Value) {
+        crate::init_logger();
+        assert_counterexample(schema, other_schema);
+    }
+
+    #[test_case(&json!({"type": "string", "pattern": "^a"}), &json!({"type": "string", "pattern": "^b"}))]
+    #[test_case(&json!({"$ref": "#/definitions/a"}), &json!({"type": "string"}))]
+    #[test_case(
+        &json!({"$ref": "#/definitions/e", "definitions": {"e": {"required": ["kind"], "type": "object"}}, "type": "string"}),
+        &json!({"required": ["a"]})
+    )]
+    #[test_case(&json!({"propertyNames": {"pattern": "^a"}}), &json!({"propertyNames": {"pattern": "^b"}}))]
+    #[test_case(&json!({"type": "string"}), &json!({"type": "string", "format": "email"}))]
+    #[test_case(&json!({"type": "object", "properties": {"a": true}, "patternProperties": {"^a+$": {"type": "string"}}}), &json!({"properties": {"a": {"type": "string"}}}))]
+    fn test_is_subschema_unknown(schema: &Value, other_schema: &Value) {
+        crate::init_logger();
+        assert_eq!(is_subschema(schema, other_schema), Inclusion::Unknown);
+    }
+}
@@ -1,13 +1,13 @@
-use crate::helpers::is;
+use crate::helpers::{edits::Edits, is, replace};
 use jsonschema_equivalent_rule_processor_logger::log_processing;
-use serde_json::{map::Entry, Value};
+use serde_json::Value;
 
 /// Simplify `additionalItems` keyword by
 ///  * removing the keyword if the schema is a `true` schema
 ///  * removing the keyword if `items` is not an array (as `additionalItems` should be ignored in such case)
 ///  * removing the keyword if `items` is an array whose length is not smaller (>=) than `maxItems`
 #[log_processing(cfg(feature = "logging"))]
-pub(crate) fn simplify_additional_items(schema: &mut Value) -> bool {
+pub(crate) fn simplify_additional_items(schema: &mut Value, edits: &mut Edits) -> bool {
     let schema_object = if let Some(value) = schema.as_object_mut() {
         value
     } else {
@@ -15,39 +15,19 @@ pub(crate) fn simplify_additional_items(schema: &mut Value) -> bool {
     };
     if let Some(additional_items) = schema_object.get("additionalItems") {
         if is::true_schema(additional_items) {
-            let _ = schema_object.remove("additionalItems");
-            true
+            replace::remove_keyword(schema_object, "additionalItems", edits).is_some()
         } else {
             match schema_object.get("items") {
                 Some(Value::Object(_)) => {
-                    let _ = schema_object.remove("additionalItems");
-                    true
+                    replace::remove_keyword(schema_object, "additionalItems", edits).is_some()
                 }
                 Some(Value::Array(items)) => {
                     let max_items_len = schema_object
                         .get("maxItems")
                         .and_then(Value::as_u64)
                         .unwrap_or(u64::MAX);
-                    if is::false_schema(additional_items) {
-                        // We know that we can never have additional items, as no value
-                        // can be validated correctly. This means that it is equivalent to
-                        // have `maxItems` defined to maximum the length of items
-                        let items_len = items.len();
-                        let _ = schema_object.remove("additionalItems");
-                        match schema_object.entry("maxItems") {
-                            Entry::Vacant(entry) => {
-                                let _ = entry.insert(items_len.into());
-                            }
-                            Entry::Occupied(mut entry) => {
-                                if max_items_len > items_len as u64 {
-                                    let _ = entry.insert(items_len.into());
-                                }
-                            }
-                        }
-                        true
-                    } else if max_items_len <= items.len() as u64 {
-                        let _ = schema_object.remove("additionalItems");
-                        true
+                    if max_items_len <= items.len() as u64 {
+                        replace::remove_keyword(schema_object, "additionalItems", edits).is_some()
                     } else {
                         false
                     }
@@ -60,9 +40,44 @@ pub(crate) fn simplify_additional_items(schema: &mut Value) -> bool {
     }
 }
 
+/// Replace `additionalItems` keyword with `maxItems` keyword if `additionalItems` is a `false` schema
+/// and `items` is an array (as no additional items can ever be valid)
+#[log_processing(cfg(feature = "logging"))]
+pub(crate) fn replace_false_additional_items_with_max_items(
+    schema: &mut Value,
+    edits: &mut Edits,
+) -> bool {
+    let Some(schema_object) = schema.as_object_mut() else {
+        return false;
+    };
+    let items_len = match (
+        schema_object.get("additionalItems"),
+        schema_object.get("items"),
+    ) {
+        (Some(additional_items), Some(Value::Array(items)))
+            if is::false_schema(additional_items) =>
+        {
+            items.len()
+        }
+        _ => return false,
+    };
+    // We know that we can never have additional items, as no value
+    // can be validated correctly. This means that it is equivalent to
+    // have `maxItems` defined to maximum the length of items
+    let _ = replace::remove_keyword(schema_object, "additionalItems", edits);
+    if schema_object.get("maxItems").is_none_or(|max_items| {
+        max_items
+            .as_u64()
+            .is_none_or(|max_items| max_items > items_len as u64)
+    }) {
+        let _ = replace::keyword_with(schema_object, "maxItems", items_len.into(), edits);
+    }
+    true
+}
+
 #[cfg(test)]
 mod tests {
-    use super::simplify_additional_items;
+    use super::{replace_false_additional_items_with_max_items, simplify_additional_items};
     use serde_json::{json, Value};
     use test_case::test_case;
 
@@ -70,8 +85,7 @@ mod tests {
     #[test_case(&json!({"additionalItems": true}) => json!({}))]
     #[test_case(&json!({"additionalItems": {"type": "string"}, "items": {"type": "boolean"}}) => json!({"items": {"type": "boolean"}}))]
     #[test_case(&json!({"additionalItems": {}}) => json!({}))]
-    #[test_case(&json!({"additionalItems": false, "items": [true, true, true]}) => json!({"items": [true, true, true], "maxItems": 3}))]
-    #[test_case(&json!({"additionalItems": false, "items": [true, true, true], "maxItems": 4}) => json!({"items": [true, true, true], "maxItems": 3}))]
+    #[test_case(&json!({"additionalItems": false, "items": [true, true, true]}) => json!({"additionalItems": false, "items": [true, true, true]}))]
     #[test_case(&json!({"additionalItems": false, "items": [true, true, true], "maxItems": 2}) => json!({"items": [true, true, true], "maxItems": 2}))]
     #[test_case(&json!({"additionalItems": {"type": "boolean"}, "items": [true, true, true], "maxItems": 2}) => json!({"items": [true, true, true], "maxItems": 2}))]
     #[test_case(&json!({"additionalItems": {"type": "boolean"}, "items": [true, true, true], "maxItems": 3}) => json!({"items": [true, true, true], "maxItems": 3}))]
@@ -79,4 +93,14 @@ mod tests {
     fn test_simplify_additional_items(schema: &Value) -> Value {
         crate::base_test_keyword_processor(&simplify_additional_items, schema)
     }
+
+    #[test_case(&json!({}) => json!({}))]
+    #[test_case(&json!({"additionalItems": false, "items": {"type": "boolean"}}) => json!({"additionalItems": false, "items": {"type": "boolean"}}))]
+    #[test_case(&json!({"additionalItems": {"type": "boolean"}, "items": [true, true, true]}) => json!({"additionalItems": {"type": "boolean"}, "items": [true, true, true]}))]
+    #[test_case(&json!({"additionalItems": false, "items": [true, true, true]}) => json!({"items": [true, true, true], "maxItems": 3}))]
+    #[test_case(&json!({"additionalItems": false, "items": [true, true, true], "maxItems": 4}) => json!({"items": [true, true, true], "maxItems": 3}))]
+    #[test_case(&json!({"additionalItems": false, "items": [true, true, true], "maxItems": 2}) => json!({"items": [true, true, true], "maxItems": 2}))]
+    fn test_replace_false_additional_items_with_max_items(schema: &Value) -> Value {
+        crate::base_test_keyword_processor(&replace_false_additional_items_with_max_items, schema)
+    }
 }
@@ -1,11 +1,11 @@
-use crate::helpers::is;
+use crate::helpers::{edits::Edits, is, replace};
 use jsonschema_equivalent_rule_processor_logger::log_processing;
 use serde_json::Value;
 
 /// Simplify `additionalProperties` keyword by:
 ///  * removing the keyword if the schema is a `true` schema
 #[log_processing(cfg(feature = "logging"))]
-pub(crate) fn simplify_additional_properties(schema: &mut Value) -> bool {
+pub(crate) fn simplify_additional_properties(schema: &mut Value, edits: &mut Edits) -> bool {
     let schema_object = if let Some(value) = schema.as_object_mut() {
         value
     } else {
@@ -15,8 +15,7 @@ pub(crate) fn simplify_additional_properties(schema: &mut Value) -> bool {
         .get("additionalProperties")
         .map_or(false, is::true_schema)
     {
-        let _ = schema_object.remove("additionalProperties");
-        true
+        replace::remove_keyword(schema_object, "additionalProperties", edits).is_some()
     } else {
         false
     }
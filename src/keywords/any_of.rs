@@ -0,0 +1,681 @@
+use crate::{
+    helpers::{
+        cost::{self, Estimate},
+        edits::Edits,
+        is, replace,
+        types::PrimitiveTypesBitMap,
+    },
+    primitive_type::PrimitiveType,
+    satisfiability::same_value,
+};
+use jsonschema_equivalent_rule_processor_logger::log_processing;
+use serde_json::{json, Map, Value};
+
+/// Order the `anyOf` subschemas by validation cost, so that validators (which stop on the first
+/// valid subschema) validate first the subschemas that are cheap and likely to succeed.
+/// The order is stable: subschemas with equivalent costs preserve their relative order.
+#[log_processing(cfg(feature = "logging"))]
+pub(crate) fn order_any_of_by_cost(schema: &mut Value, edits: &mut Edits) -> bool {
+    if let Some(Value::Array(items)) = schema.get_mut("anyOf") {
+        edits.within("anyOf", |edits| {
+            cost::sort_by_rank(items, Estimate::any_of_rank, edits)
+        })
+    } else {
+        false
+    }
+}
+
+/// Keywords defining a numeric lower bound (the weakest bound being the lowest)
+static LOWER_BOUND_KEYWORDS: &[&str] = &[
+    "exclusiveMinimum",
+    "minItems",
+    "minLength",
+    "minProperties",
+    "minimum",
+];
+/// Keywords defining a numeric upper bound (the weakest bound being the highest)
+static UPPER_BOUND_KEYWORDS: &[&str] = &[
+    "exclusiveMaximum",
+    "maxItems",
+    "maxLength",
+    "maxProperties",
+    "maximum",
+];
+
+/// Run `edit` recording its edits relative to the branch in position `index` of the `combinator` keyword
+fn within_branch<R>(
+    edits: &mut Edits,
+    combinator: &str,
+    index: usize,
+    edit: impl FnOnce(&mut Edits) -> R,
+) -> R {
+    edits.within(combinator, |edits| edits.within(index, edit))
+}
+
+/// Remove the `keyword` from the `branch` in position `index` of the `combinator` keyword
+fn remove_branch_keyword(
+    branch: &mut Value,
+    combinator: &str,
+    index: usize,
+    keyword: &str,
+    edits: &mut Edits,
+) {
+    if let Some(branch_object) = branch.as_object_mut() {
+        let _ = within_branch(edits, combinator, index, |edits| {
+            replace::remove_keyword(branch_object, keyword, edits)
+        });
+    }
+}
+
+/// Factor the `type` constraints of the branches into the parent schema.
+/// The parent schema allows the union of the types of the branches, so the branches `type` allowing
+/// all the types allowed by the parent schema are redundant.
+fn factor_type(
+    schema_object: &mut Map<String, Value>,
+    combinator: &str,
+    branches: &mut [Value],
+    edits: &mut Edits,
+) -> bool {
+    let branches_types = branches.iter().map(|branch| branch.get("type")).try_fold(
+        PrimitiveTypesBitMap::default(),
+        |types, branch_type| {
+            branch_type.map(|branch_type| {
+                types | PrimitiveTypesBitMap::from_schema_value(Some(branch_type))
+            })
+        },
+    );
+    let schema_types = PrimitiveTypesBitMap::from_schema_value(schema_object.get("type"));
+    let common_types =
+        branches_types.map_or(schema_types, |branches_types| schema_types & branches_types);
+    if common_types.is_empty() {
+        // The schema is a `false` schema, which is handled by other rules
+        return false;
+    }
+
+    let mut updated_schema = false;
+    for (index, branch) in branches.iter_mut().enumerate() {
+        let branch_types = PrimitiveTypesBitMap::from_schema_value(branch.get("type"));
+        if branch.get("type").is_some() && branch_types & common_types == common_types {
+            remove_branch_keyword(branch, combinator, index, "type", edits);
+            updated_schema = true;
+        }
+    }
+    if updated_schema && common_types != schema_types {
+        if let Some(type_) = common_types.to_schema_value() {
+            let _ = replace::keyword_with(schema_object, "type", type_, edits);
+        }
+    }
+    updated_schema
+}
+
+/// Factor the `required` properties common to all the branches into the parent schema
+fn factor_required(
+    schema_object: &mut Map<String, Value>,
+    combinator: &str,
+    branches: &mut [Value],
+    edits: &mut Edits,
+) -> bool {
+    let mut common_required: Option<Vec<Value>> = None;
+    for branch in branches.iter() {
+        let Some(Value::Array(branch_required)) = branch.get("required") else {
+            common_required = Some(Vec::new());
+            break;
+        };
+        common_required = Some(match common_required {
+            None => branch_required.clone(),
+            Some(common_required) => common_required
+                .into_iter()
+                .filter(|property| branch_required.contains(property))
+                .collect(),
+        });
+    }
+    let mut required = match schema_object.get("required") {
+        Some(Value::Array(required)) => required.clone(),
+        Some(_) => return false,
+        None => Vec::new(),
+    };
+    let required_len = required.len();
+    for property in common_required.unwrap_or_default() {
+        if !required.contains(&property) {
+            required.push(property);
+        }
+    }
+
+    let mut updated_schema = false;
+    for (index, branch) in branches.iter_mut().enumerate() {
+        if let Some(Value::Array(branch_required)) = branch.get_mut("required") {
+            let indexes_to_remove: Vec<usize> = branch_required
+                .iter()
+                .enumerate()
+                .filter_map(|(property_index, property)| {
+                    if required.contains(property) {
+                        Some(property_index)
+                    } else {
+                        None
+                    }
+                })
+                .collect();
+            if indexes_to_remove.is_empty() {
+                continue;
+            }
+            updated_schema = true;
+            if indexes_to_remove.len() == branch_required.len() {
+                remove_branch_keyword(branch, combinator, index, "required", edits);
+            } else {
+                let _ = within_branch(edits, combinator, index, |edits| {
+                    edits.within("required", |edits| {
+                        replace::remove_items(branch_required, &indexes_to_remove, edits)
+                    })
+                });
+            }
+        }
+    }
+    if updated_schema && required.len() != required_len {
+        let _ = replace::keyword_with(schema_object, "required", Value::Array(required), edits);
+    }
+    updated_schema
+}
+
+/// Factor the weakest bound defined, by `keyword`, by all the branches into the parent schema.
+/// The branches bounds which are not stronger than the parent schema bound are redundant.
+/// `is_weaker(bound, other_bound)` checks if `bound` is weaker than, or as strong as, `other_bound`.
+fn factor_bound(
+    schema_object: &mut Map<String, Value>,
+    combinator: &str,
+    branches: &mut [Value],
+    keyword: &str,
+    is_weaker: fn(f64, f64) -> bool,
+    edits: &mut Edits,
+) -> bool {
+    let mut bound: Option<(f64, &Value)> = None;
+    if branches
+        .iter()
+        .all(|branch| branch.get(keyword).and_then(Value::as_f64).is_some())
+    {
+        for branch_value in branches.iter().filter_map(|branch| branch.get(keyword)) {
+            let branch_bound = branch_value.as_f64().unwrap_or_default();
+            match bound {
+                Some((weakest_bound, _)) if is_weaker(weakest_bound, branch_bound) => {}
+                _ => bound = Some((branch_bound, branch_value)),
+            }
+        }
+    }
+    if let Some(schema_value) = schema_object.get(keyword) {
+        let Some(schema_bound) = schema_value.as_f64() else {
+            return false;
+        };
+        match bound {
+            Some((branches_bound, _)) if !is_weaker(branches_bound, schema_bound) => {}
+            _ => bound = Some((schema_bound, schema_value)),
+        }
+    }
+    let Some((bound, bound_value)) = bound.map(|(bound, value)| (bound, value.clone())) else {
+        return false;
+    };
+
+    let mut updated_schema = false;
+    for (index, branch) in branches.iter_mut().enumerate() {
+        if let Some(branch_bound) = branch.get(keyword).and_then(Value::as_f64) {
+            if is_weaker(branch_bound, bound) {
+                remove_branch_keyword(branch, combinator, index, keyword, edits);
+                updated_schema = true;
+            }
+        }
+    }
+    if updated_schema {
+        let _ = replace::keyword_with(schema_object, keyword, bound_value, edits);
+    }
+    updated_schema
+}
+
+/// Checks if the schema defines Draft 4 `exclusiveMaximum`/`exclusiveMinimum` keywords, which alter the meaning of
+/// `maximum`/`minimum` keywords
+fn has_boolean_exclusive_bounds(schema_object: &Map<String, Value>) -> bool {
+    schema_object
+        .get("exclusiveMaximum")
+        .is_some_and(Value::is_boolean)
+        || schema_object
+            .get("exclusiveMinimum")
+            .is_some_and(Value::is_boolean)
+}
+
+/// Factor the constraints implied by all the `anyOf` (or `oneOf`) branches into the parent schema, removing them
+/// from the branches.
+/// As the parent schema is valid only if (at least) one branch is valid, the constraints common to all the branches
+/// (the union of the types, the common `required` properties and the weakest bounds) can be enforced by the parent
+/// schema. Then the branches constraints enforced by the parent schema are redundant.
+///
+/// ie. `{"anyOf": [{"type": "object", "required": ["id", "a"]}, {"type": "object", "required": ["id", "b"]}]}`
+/// is equivalent to `{"type": "object", "required": ["id"], "anyOf": [{"required": ["a"]}, {"required": ["b"]}]}`
+///
+/// NOTE: The keywords next to `$ref` are ignored (in Draft4, Draft6 and Draft7), so nothing is factored if the
+/// schema or any of its branches defines `$ref`.
+#[log_processing(cfg(feature = "logging"))]
+pub(crate) fn factor_common_constraints(schema: &mut Value, edits: &mut Edits) -> bool {
+    let Some(schema_object) = schema.as_object_mut() else {
+        return false;
+    };
+    if schema_object.contains_key("$ref") {
+        return false;
+    }
+
+    let mut updated_schema = false;
+    for keyword in &["anyOf", "oneOf"] {
+        let mut branches = match schema_object.get_mut(*keyword) {
+            Some(Value::Array(branches))
+                if !branches.is_empty()
+                    && branches.iter().all(|branch| {
+                        branch
+                            .as_object()
+                            .is_some_and(|branch_object| !branch_object.contains_key("$ref"))
+                    }) =>
+            {
+                std::mem::take(branches)
+            }
+            _ => continue,
+        };
+
+        updated_schema |= factor_type(schema_object, keyword, &mut branches, edits);
+        updated_schema |= factor_required(schema_object, keyword, &mut branches, edits);
+        let factor_maximum_minimum = !has_boolean_exclusive_bounds(schema_object)
+            && !branches
+                .iter()
+                .filter_map(Value::as_object)
+                .any(has_boolean_exclusive_bounds);
+        for bound_keyword in LOWER_BOUND_KEYWORDS {
+            if factor_maximum_minimum || *bound_keyword != "minimum" {
+                updated_schema |= factor_bound(
+                    schema_object,
+                    keyword,
+                    &mut branches,
+                    bound_keyword,
+                    |bound, other_bound| bound <= other_bound,
+                    edits,
+                );
+            }
+        }
+        for bound_keyword in UPPER_BOUND_KEYWORDS {
+            if factor_maximum_minimum || *bound_keyword != "maximum" {
+                updated_schema |= factor_bound(
+                    schema_object,
+                    keyword,
+                    &mut branches,
+                    bound_keyword,
+                    |bound, other_bound| bound >= other_bound,
+                    edits,
+                );
+            }
+        }
+
+        let _ = schema_object.insert((*keyword).to_string(), Value::Array(branches));
+    }
+
+    // `anyOf` with a `true` branch (ie. a branch whose constraints have all been factored) is always valid
+    if schema_object
+        .get("anyOf")
+        .and_then(Value::as_array)
+        .is_some_and(|branches| branches.iter().any(is::true_schema))
+    {
+        let _ = replace::remove_keyword(schema_object, "anyOf", edits);
+        updated_schema = true;
+    }
+    updated_schema
+}
+
+/// Checks if `schema_object` allows only JSON objects
+fn allows_only_objects(schema_object: &Map<String, Value>) -> bool {
+    schema_object.get("type").is_some_and(|type_| {
+        PrimitiveTypesBitMap::from_schema_value(Some(type_))
+            == PrimitiveTypesBitMap::from(PrimitiveType::Object)
+    })
+}
+
+/// Checks if `schema_object` requires the `property`
+fn requires_property(schema_object: &Map<String, Value>, property: &str) -> bool {
+    schema_object
+        .get("required")
+        .and_then(Value::as_array)
+        .is_some_and(|required| required.iter().any(|value| value == property))
+}
+
+/// Value of the `const` keyword of the `property` schema defined by `branch`
+fn tag_value<'b>(branch: &'b Value, property: &str) -> Option<&'b Value> {
+    branch.get("properties")?.get(property)?.get("const")
+}
+
+/// Property discriminating the `branches`: all the branches require it and define a different `const` value for it.
+/// The instances are known to be JSON objects, so an instance can be valid only against the branch whose `const`
+/// value matches the value of the property.
+///
+/// NOTE: The keywords next to `$ref` are ignored (in Draft4, Draft6 and Draft7), so there is no discriminating
+/// property if the schema or any of the branches defines `$ref`.
+fn discriminating_property<'b>(
+    schema_object: &Map<String, Value>,
+    branches: &'b [Value],
+) -> Option<&'b str> {
+    if schema_object.contains_key("$ref") {
+        return None;
+    }
+    let branches_objects = branches
+        .iter()
+        .map(Value::as_object)
+        .collect::<Option<Vec<_>>>()?;
+    if branches_objects
+        .iter()
+        .any(|branch_object| branch_object.contains_key("$ref"))
+    {
+        return None;
+    }
+    if !allows_only_objects(schema_object)
+        && !branches_objects
+            .iter()
+            .all(|branch_object| allows_only_objects(branch_object))
+    {
+        return None;
+    }
+
+    branches_objects
+        .first()?
+        .get("properties")?
+        .as_object()?
+        .keys()
+        .map(String::as_str)
+        .find(|property| {
+            let mut tag_values: Vec<&Value> = Vec::with_capacity(branches.len());
+            branches_objects
+                .iter()
+                .zip(branches)
+                .all(|(branch_object, branch)| {
+                    let Some(value) = tag_value(branch, property) else {
+                        return false;
+                    };
+                    if (requires_property(schema_object, property)
+                        || requires_property(branch_object, property))
+                        && !tag_values
+                            .iter()
+                            .any(|tag_value| same_value(tag_value, value))
+                    {
+                        tag_values.push(value);
+                        true
+                    } else {
+                        false
+                    }
+                })
+        })
+}
+
+/// Specialise the `anyOf` (or `oneOf`) keyword whose branches are discriminated by a tag property (required by all
+/// the branches, with a different `const` value in each branch) into a chain of `if`/`then`/`else` keywords.
+/// Validators check all the `anyOf`/`oneOf` branches, while the chain validates only the branch selected by the tag.
+///
+/// ie. `{"type": "object", "oneOf": [{"properties": {"kind": {"const": "a"}, "a": {"type": "string"}}, "required": ["kind"]}, ...]}`
+/// is equivalent to `{"type": "object", "if": {"properties": {"kind": {"const": "a"}}}, "then": {"properties": {"kind": {"const": "a"}, "a": {"type": "string"}}, "required": ["kind"]}, "else": ...}`
+/// where the last `else` schema is a `false` schema.
+///
+/// The instances are required to be JSON objects, otherwise all the `if` schemas would be valid.
+#[log_processing(cfg(feature = "logging"))]
+pub(crate) fn specialise_discriminated_union(schema: &mut Value, edits: &mut Edits) -> bool {
+    let Some(schema_object) = schema.as_object_mut() else {
+        return false;
+    };
+
+    let mut updated_schema = false;
+    for keyword in &["anyOf", "oneOf"] {
+        let Some(Value::Array(branches)) = schema_object.get(*keyword) else {
+            continue;
+        };
+        if branches.len() < 2 {
+            continue;
+        }
+        let Some(property) = discriminating_property(schema_object, branches).map(str::to_string)
+        else {
+            continue;
+        };
+        // The chain is added to `allOf` if the schema already defines any of `if`, `then` and `else`
+        let chain_into_all_of = ["if", "then", "else"]
+            .iter()
+            .any(|chain_keyword| schema_object.contains_key(*chain_keyword));
+        if chain_into_all_of
+            && schema_object
+                .get("allOf")
+                .is_some_and(|all_of| !all_of.is_array())
+        {
+            continue;
+        }
+        let Some(Value::Array(branches)) = replace::remove_keyword(schema_object, keyword, edits)
+        else {
+            continue;
+        };
+
+        let chain = branches
+            .into_iter()
+            .rev()
+            .fold(Value::Bool(false), |else_schema, branch| {
+                let mut if_schema = Map::new();
+                if let Some(value) = tag_value(&branch, &property) {
+                    let _ = if_schema.insert(
+                        "properties".to_string(),
+                        json!({ property.as_str(): {"const": value} }),
+                    );
+                }
+                let mut chain_object = Map::new();
+                let _ = chain_object.insert("if".to_string(), Value::Object(if_schema));
+                let _ = chain_object.insert("then".to_string(), branch);
+                let _ = chain_object.insert("else".to_string(), else_schema);
+                Value::Object(chain_object)
+            });
+        if chain_into_all_of {
+            if let Some(Value::Array(all_of_items)) = schema_object.get_mut("allOf") {
+                edits.within("allOf", |edits| edits.add(all_of_items.len(), &chain));
+                all_of_items.push(chain);
+            } else {
+                let _ =
+                    replace::keyword_with(schema_object, "allOf", Value::Array(vec![chain]), edits);
+            }
+        } else if let Value::Object(chain_object) = chain {
+            for (chain_keyword, chain_value) in chain_object {
+                let _ = replace::keyword_with(schema_object, &chain_keyword, chain_value, edits);
+            }
+        }
+        updated_schema = true;
+    }
+    updated_schema
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{factor_common_constraints, order_any_of_by_cost, specialise_discriminated_union};
+    use crate::{
+        helpers::edits::Edits,
+        options::{Options, Profile},
+    };
+    use serde_json::{json, Value};
+    use test_case::test_case;
+
+    #[test_case(&json!({"anyOf": [{"type": "string"}]}) => json!({"anyOf": [{"type": "string"}]}))]
+    #[test_case(&json!({"anyOf": [{"const": "a"}, {"type": "string"}]}) => json!({"anyOf": [{"type": "string"}, {"const": "a"}]}))]
+    #[test_case(&json!({"anyOf": [{"type": "string"}, {"type": "integer"}]}) => json!({"anyOf": [{"type": "string"}, {"type": "integer"}]}))]
+    fn test_order_any_of_by_cost(schema: &Value) -> Value {
+        crate::base_test_keyword_processor(&order_any_of_by_cost, schema)
+    }
+
+    #[test_case(&json!({"anyOf": [true, {"type": "string"}]}) => json!({}))]
+    #[test_case(&json!({"anyOf": [{"type": "string"}, {"type": "integer"}]}) => json!({"anyOf": [{"type": "string"}, {"type": "integer"}]}))]
+    #[test_case(&json!({"anyOf": [{"type": "string", "minLength": 1}, {"type": "string", "maxLength": 3}]}) => json!({"type": "string", "anyOf": [{"minLength": 1}, {"maxLength": 3}]}))]
+    #[test_case(&json!({"anyOf": [{"type": ["integer", "string"]}, {"type": "string", "minLength": 1}]}) => json!({"type": ["integer", "string"]}))]
+    #[test_case(&json!({"anyOf": [{"type": "string"}, {"minLength": 1}], "type": "string"}) => json!({"type": "string"}))]
+    #[test_case(&json!({"anyOf": [{"type": "number"}, {"minimum": 1}], "type": "integer"}) => json!({"type": "integer"}))]
+    #[test_case(&json!({"anyOf": [{"type": "integer"}, {"minimum": 1}], "type": "number"}) => json!({"anyOf": [{"type": "integer"}, {"minimum": 1}], "type": "number"}))]
+    #[test_case(
+        &json!({"oneOf": [{"type": "object", "required": ["id", "a"]}, {"type": "object", "required": ["b", "id"]}]})
+        => json!({"oneOf": [{"required": ["a"]}, {"required": ["b"]}], "required": ["id"], "type": "object"})
+    )]
+    #[test_case(&json!({"oneOf": [{"required": ["a"]}, {"minProperties": 1}], "required": ["b"]}) => json!({"oneOf": [{"required": ["a"]}, {"minProperties": 1}], "required": ["b"]}))]
+    #[test_case(&json!({"oneOf": [{"required": ["a", "b"]}, {"minProperties": 1}], "required": ["a"]}) => json!({"oneOf": [{"required": ["b"]}, {"minProperties": 1}], "required": ["a"]}))]
+    #[test_case(&json!({"anyOf": [{"minimum": 1, "maxLength": 2}, {"minimum": 3, "maxLength": 4}]}) => json!({"anyOf": [{"maxLength": 2}, {"minimum": 3}], "maxLength": 4, "minimum": 1}))]
+    #[test_case(&json!({"oneOf": [{"minimum": 1, "maxLength": 2}, {"minimum": 3, "maxLength": 4}]}) => json!({"oneOf": [{"maxLength": 2}, {"minimum": 3}], "maxLength": 4, "minimum": 1}))]
+    #[test_case(&json!({"oneOf": [{"minimum": 1}, {"minimum": 3}], "minimum": 2}) => json!({"oneOf": [{}, {"minimum": 3}], "minimum": 2}))]
+    #[test_case(&json!({"oneOf": [{"minimum": 1}, {"minimum": 3}], "minimum": 5}) => json!({"oneOf": [{}, {}], "minimum": 5}))]
+    #[test_case(&json!({"oneOf": [{"minimum": 1, "exclusiveMinimum": true}, {"minimum": 3}]}) => json!({"oneOf": [{"minimum": 1, "exclusiveMinimum": true}, {"minimum": 3}]}))]
+    #[test_case(&json!({"oneOf": [{"type": "string"}, false]}) => json!({"oneOf": [{"type": "string"}, false]}))]
+    #[test_case(
+        &json!({"anyOf": [{"$ref": "#/definitions/a", "type": "string"}, {"type": "string", "minLength": 1}], "definitions": {"a": {"type": "integer"}}})
+        => json!({"anyOf": [{"$ref": "#/definitions/a", "type": "string"}, {"type": "string", "minLength": 1}], "definitions": {"a": {"type": "integer"}}})
+    )]
+    #[test_case(&json!({"$ref": "#/definitions/a", "anyOf": [{"type": "string"}, {"type": "string", "minLength": 1}]}) => json!({"$ref": "#/definitions/a", "anyOf": [{"type": "string"}, {"type": "string", "minLength": 1}]}))]
+    #[test_case(&json!({"oneOf": [{"type": "string"}, {"type": "integer"}], "type": "boolean"}) => json!({"oneOf": [{"type": "string"}, {"type": "integer"}], "type": "boolean"}))]
+    fn test_factor_common_constraints(schema: &Value) -> Value {
+        crate::base_test_keyword_processor(&factor_common_constraints, schema)
+    }
+
+    #[test]
+    fn test_factor_common_constraints_does_not_factor_branches_with_ref() {
+        crate::init_logger();
+        let schema = json!({
+            "anyOf": [{"$ref": "#/definitions/a", "type": "string"}, {"type": "string", "minLength": 1}],
+            "definitions": {"a": {"type": "integer"}},
+        });
+        let optimised_schema = crate::jsonschema_equivalent(schema.clone());
+        assert!(jsonschema::draft7::is_valid(&schema, &json!(1)));
+        assert!(
+            jsonschema::draft7::is_valid(&optimised_schema, &json!(1)),
+            "{} does not validate 1",
+            optimised_schema
+        );
+    }
+
+    #[test_case(
+        &json!({"oneOf": [{"type": "object", "required": ["id", "a"]}, {"type": "object", "required": ["b", "id"]}]}),
+        &[json!({"id": 1, "a": 1}), json!({"id": 1, "a": 1, "b": 1}), json!({"a": 1, "b": 1}), json!(1)]
+    )]
+    #[test_case(
+        &json!({"anyOf": [{"minimum": 1, "maxLength": 2}, {"minimum": 3, "maxLength": 4}]}),
+        &[json!(0), json!(1), json!(3), json!("ab"), json!("abc"), json!("abcde")]
+    )]
+    #[test_case(
+        &json!({"oneOf": [{"minimum": 1, "maxLength": 2}, {"minimum": 3, "maxLength": 4}], "minimum": 2}),
+        &[json!(0), json!(2), json!(3), json!("ab"), json!("abc"), json!("abcde")]
+    )]
+    fn test_factor_common_constraints_preserves_validation(schema: &Value, instances: &[Value]) {
+        let mut optimised_schema = schema.clone();
+        assert!(factor_common_constraints(
+            &mut optimised_schema,
+            &mut Edits::default()
+        ));
+        for instance in instances {
+            assert_eq!(
+                jsonschema::is_valid(schema, instance),
+                jsonschema::is_valid(&optimised_schema, instance),
+                "{schema} and {optimised_schema} disagree on {instance}"
+            );
+        }
+    }
+
+    #[test_case(&json!({"oneOf": [{"type": "object", "properties": {"kind": {"const": "a"}}, "required": ["kind"]}]}) => json!({"oneOf": [{"type": "object", "properties": {"kind": {"const": "a"}}, "required": ["kind"]}]}))]
+    #[test_case(
+        &json!({"oneOf": [{"properties": {"kind": {"const": "a"}}, "required": ["kind"]}, {"properties": {"kind": {"const": "b"}}, "required": ["kind"]}]})
+        => json!({"oneOf": [{"properties": {"kind": {"const": "a"}}, "required": ["kind"]}, {"properties": {"kind": {"const": "b"}}, "required": ["kind"]}]})
+    )]
+    #[test_case(
+        &json!({"type": "object", "oneOf": [{"properties": {"kind": {"const": "a"}}, "required": ["kind"]}, {"properties": {"kind": {"const": "a"}}, "required": ["kind"]}]})
+        => json!({"type": "object", "oneOf": [{"properties": {"kind": {"const": "a"}}, "required": ["kind"]}, {"properties": {"kind": {"const": "a"}}, "required": ["kind"]}]})
+    )]
+    #[test_case(
+        &json!({"type": "object", "oneOf": [{"properties": {"kind": {"const": "a"}}, "required": ["kind"]}, {"properties": {"kind": {"const": "b"}}}]})
+        => json!({"type": "object", "oneOf": [{"properties": {"kind": {"const": "a"}}, "required": ["kind"]}, {"properties": {"kind": {"const": "b"}}}]})
+    )]
+    #[test_case(
+        &json!({"type": "object", "required": ["kind"], "oneOf": [{"properties": {"kind": {"const": "a"}, "a": {"type": "string"}}}, {"properties": {"kind": {"const": "b"}}, "required": ["b"]}]})
+        => json!({
+            "type": "object",
+            "required": ["kind"],
+            "if": {"properties": {"kind": {"const": "a"}}},
+            "then": {"properties": {"kind": {"const": "a"}, "a": {"type": "string"}}},
+            "else": {
+                "if": {"properties": {"kind": {"const": "b"}}},
+                "then": {"properties": {"kind": {"const": "b"}}, "required": ["b"]},
+                "else": false,
+            },
+        })
+    )]
+    #[test_case(
+        &json!({"anyOf": [{"type": "object", "properties": {"kind": {"const": 1}}, "required": ["kind"]}, {"type": "object", "properties": {"kind": {"const": 2}}, "required": ["kind"]}], "if": {"required": ["a"]}, "then": {"required": ["b"]}})
+        => json!({
+            "if": {"required": ["a"]},
+            "then": {"required": ["b"]},
+            "allOf": [{
+                "if": {"properties": {"kind": {"const": 1}}},
+                "then": {"type": "object", "properties": {"kind": {"const": 1}}, "required": ["kind"]},
+                "else": {
+                    "if": {"properties": {"kind": {"const": 2}}},
+                    "then": {"type": "object", "properties": {"kind": {"const": 2}}, "required": ["kind"]},
+                    "else": false,
+                },
+            }],
+        })
+    )]
+    #[test_case(
+        &json!({"definitions": {"any": {}}, "type": "object", "anyOf": [{"$ref": "#/definitions/any", "properties": {"kind": {"const": "a"}}, "required": ["kind"]}, {"properties": {"kind": {"const": "b"}}, "required": ["kind", "x"]}]})
+        => json!({"definitions": {"any": {}}, "type": "object", "anyOf": [{"$ref": "#/definitions/any", "properties": {"kind": {"const": "a"}}, "required": ["kind"]}, {"properties": {"kind": {"const": "b"}}, "required": ["kind", "x"]}]})
+    )]
+    #[test_case(
+        &json!({"$ref": "#/definitions/any", "type": "object", "oneOf": [{"properties": {"kind": {"const": "a"}}, "required": ["kind"]}, {"properties": {"kind": {"const": "b"}}, "required": ["kind"]}]})
+        => json!({"$ref": "#/definitions/any", "type": "object", "oneOf": [{"properties": {"kind": {"const": "a"}}, "required": ["kind"]}, {"properties": {"kind": {"const": "b"}}, "required": ["kind"]}]})
+    )]
+    #[test_case(
+        &json!({"type": "object", "if": {}, "allOf": {}, "oneOf": [{"properties": {"kind": {"const": "a"}}, "required": ["kind"]}, {"properties": {"kind": {"const": "b"}}, "required": ["kind"]}]})
+        => json!({"type": "object", "if": {}, "allOf": {}, "oneOf": [{"properties": {"kind": {"const": "a"}}, "required": ["kind"]}, {"properties": {"kind": {"const": "b"}}, "required": ["kind"]}]})
+    )]
+    fn test_specialise_discriminated_union(schema: &Value) -> Value {
+        crate::base_test_keyword_processor(&specialise_discriminated_union, schema)
+    }
+
+    #[test]
+    fn test_specialise_discriminated_union_does_not_specialise_branches_with_ref() {
+        crate::init_logger();
+        let schema = json!({
+            "definitions": {"any": {}},
+            "type": "object",
+            "anyOf": [
+                {"$ref": "#/definitions/any", "properties": {"kind": {"const": "a"}}, "required": ["kind"]},
+                {"properties": {"kind": {"const": "b"}}, "required": ["kind", "x"]},
+            ],
+        });
+        let optimised_schema = crate::jsonschema_equivalent_with_options(
+            schema.clone(),
+            &Options::new().with_profile(Profile::Aggressive),
+        );
+        assert!(jsonschema::draft7::is_valid(&schema, &json!({"kind": "b"})));
+        assert!(
+            jsonschema::draft7::is_valid(&optimised_schema, &json!({"kind": "b"})),
+            "{} does not validate {{\"kind\": \"b\"}}",
+            optimised_schema
+        );
+    }
+
+    #[test_case(
+        &json!({"type": "object", "required": ["kind"], "oneOf": [{"properties": {"kind": {"const": "a"}, "a": {"type": "string"}}}, {"properties": {"kind": {"const": "b"}}, "required": ["b"]}]}),
+        &[json!({}), json!({"kind": "a"}), json!({"kind": "a", "a": 1}), json!({"kind": "b"}), json!({"kind": "b", "b": 1}), json!({"kind": "c"}), json!({"a": "a"})]
+    )]
+    #[test_case(
+        &json!({"anyOf": [{"type": "object", "properties": {"kind": {"const": 1}, "a": {"minimum": 1}}, "required": ["kind"]}, {"type": "object", "properties": {"kind": {"const": 2}}, "required": ["kind", "b"]}]}),
+        &[json!(1), json!("kind"), json!({}), json!({"kind": 1.0}), json!({"kind": 1, "a": 0}), json!({"kind": 2}), json!({"kind": 2, "b": 0}), json!({"kind": 3})]
+    )]
+    fn test_specialise_discriminated_union_preserves_validation(
+        schema: &Value,
+        instances: &[Value],
+    ) {
+        let mut optimised_schema = schema.clone();
+        assert!(specialise_discriminated_union(
+            &mut optimised_schema,
+            &mut Edits::default()
+        ));
+        for instance in instances {
+            assert_eq!(
+                jsonschema::is_valid(schema, instance),
+                jsonschema::is_valid(&optimised_schema, instance),
+                "{schema} and {optimised_schema} disagree on {instance}"
+            );
+        }
+    }
+}
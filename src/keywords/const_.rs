@@ -1,6 +1,7 @@
 use crate::{
-    helpers::{replace, types::PrimitiveTypesBitMap},
+    helpers::{edits::Edits, replace, types::PrimitiveTypesBitMap},
     primitive_type::PrimitiveType,
+    satisfiability::same_value,
 };
 use jsonschema_equivalent_rule_processor_logger::log_processing;
 use serde_json::Value;
@@ -10,7 +11,7 @@ use serde_json::Value;
 /// * removing types that are not in sync with the type of the `const` value
 /// * if no types are left after previous removal, then the `schema` is a `false` schema
 #[log_processing(cfg(feature = "logging"))]
-pub(crate) fn simple_const_cleanup(schema: &mut Value) -> bool {
+pub(crate) fn simple_const_cleanup(schema: &mut Value, edits: &mut Edits) -> bool {
     let schema_object = if let Some(value) = schema.as_object_mut() {
         value
     } else {
@@ -30,6 +31,7 @@ pub(crate) fn simple_const_cleanup(schema: &mut Value) -> bool {
             replace::type_with(
                 schema_object,
                 PrimitiveTypesBitMap::from(const_primitive_type),
+                edits,
             )
         } else if const_primitive_type == PrimitiveType::Number
             && schema_primitive_types.contains(PrimitiveType::Integer)
@@ -38,9 +40,34 @@ pub(crate) fn simple_const_cleanup(schema: &mut Value) -> bool {
             replace::type_with(
                 schema_object,
                 PrimitiveTypesBitMap::from(PrimitiveType::Integer),
+                edits,
             )
         } else {
-            replace::with_false_schema(schema)
+            replace::with_false_schema(schema, edits)
+        }
+    } else {
+        false
+    }
+}
+
+/// Intersect `const` keyword with `enum` keyword.
+/// The `enum` keyword is redundant if it contains the `const` value, otherwise the `schema` is a `false` schema.
+#[log_processing(cfg(feature = "logging"))]
+pub(crate) fn intersect_const_with_enum(schema: &mut Value, edits: &mut Edits) -> bool {
+    let Some(schema_object) = schema.as_object_mut() else {
+        return false;
+    };
+
+    if let (Some(const_value), Some(Value::Array(enum_values))) =
+        (schema_object.get("const"), schema_object.get("enum"))
+    {
+        if enum_values
+            .iter()
+            .any(|enum_value| same_value(enum_value, const_value))
+        {
+            replace::remove_keyword(schema_object, "enum", edits).is_some()
+        } else {
+            replace::with_false_schema(schema, edits)
         }
     } else {
         false
@@ -49,7 +76,7 @@ pub(crate) fn simple_const_cleanup(schema: &mut Value) -> bool {
 
 #[cfg(test)]
 mod tests {
-    use super::simple_const_cleanup;
+    use super::{intersect_const_with_enum, simple_const_cleanup};
     use serde_json::{json, Value};
     use test_case::test_case;
 
@@ -66,4 +93,12 @@ mod tests {
     fn test_simple_const_cleanup(schema: &Value) -> Value {
         crate::base_test_keyword_processor(&simple_const_cleanup, schema)
     }
+
+    #[test_case(&json!({"const": 1}) => json!({"const": 1}))]
+    #[test_case(&json!({"const": 1, "enum": [2, 1.0]}) => json!({"const": 1}))]
+    #[test_case(&json!({"const": 1, "enum": [2, 3]}) => json!(false))]
+    #[test_case(&json!({"const": {"a": [1]}, "enum": [{"a": [1]}]}) => json!({"const": {"a": [1]}}))]
+    fn test_intersect_const_with_enum(schema: &Value) -> Value {
+        crate::base_test_keyword_processor(&intersect_const_with_enum, schema)
+    }
 }
@@ -0,0 +1,76 @@
+use crate::helpers::{edits::Edits, replace, types::PrimitiveTypesBitMap};
+use crate::options::Options;
+use jsonschema_equivalent_rule_processor_logger::log_processing;
+use serde_json::Value;
+
+/// Remove the registered assertion keywords that do not apply to any of the primitive types allowed by `type`.
+///
+/// This is the counterpart of `remove_extraneous_keys_keyword_type` for the keywords registered via
+/// [`Options::register_keyword`](crate::Options::register_keyword).
+#[log_processing(cfg(feature = "logging"))]
+pub(crate) fn remove_custom_keywords_of_other_types(
+    schema: &mut Value,
+    options: &Options,
+    edits: &mut Edits,
+) -> bool {
+    let Some(schema_object) = schema.as_object_mut() else {
+        return false;
+    };
+    if !schema_object.contains_key("type") {
+        return false;
+    }
+    let schema_primitive_types = PrimitiveTypesBitMap::from_schema_value(schema_object.get("type"));
+
+    let keywords_to_remove: Vec<&str> = options
+        .custom_keywords()
+        .filter(|(keyword, custom_keyword)| {
+            if !custom_keyword.is_assertion()
+                || custom_keyword.primitive_types().is_empty()
+                || !schema_object.contains_key(*keyword)
+            {
+                return false;
+            }
+            let mut keyword_primitive_types = PrimitiveTypesBitMap::default();
+            for primitive_type in custom_keyword.primitive_types() {
+                keyword_primitive_types |= *primitive_type;
+            }
+            (schema_primitive_types & keyword_primitive_types).is_empty()
+        })
+        .map(|(keyword, _)| keyword)
+        .collect();
+
+    for keyword in &keywords_to_remove {
+        let _ = replace::remove_keyword(schema_object, keyword, edits);
+    }
+    !keywords_to_remove.is_empty()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::remove_custom_keywords_of_other_types;
+    use crate::options::{CustomKeyword, Options};
+    use serde_json::{json, Value};
+    use test_case::test_case;
+
+    #[test_case(&json!({"x-digits": 1}) => json!({"x-digits": 1}))]
+    #[test_case(&json!({"type": "string", "x-digits": 1}) => json!({"type": "string"}))]
+    #[test_case(&json!({"type": "integer", "x-digits": 1}) => json!({"type": "integer", "x-digits": 1}))]
+    #[test_case(&json!({"type": "number", "x-digits": 1}) => json!({"type": "number", "x-digits": 1}))]
+    #[test_case(&json!({"type": "string", "x-any": 1, "x-doc": 1}) => json!({"type": "string", "x-any": 1, "x-doc": 1}))]
+    fn test_remove_custom_keywords_of_other_types(schema: &Value) -> Value {
+        let options = Options::new()
+            .register_keyword("x-any", CustomKeyword::assertion())
+            .register_keyword(
+                "x-digits",
+                CustomKeyword::assertion().applying_to(&["integer"]),
+            )
+            .register_keyword(
+                "x-doc",
+                CustomKeyword::annotation().applying_to(&["integer"]),
+            );
+        crate::base_test_keyword_processor(
+            &|schema, edits| remove_custom_keywords_of_other_types(schema, &options, edits),
+            schema,
+        )
+    }
+}
@@ -1,6 +1,7 @@
 use crate::{
-    helpers::{replace, types::PrimitiveTypesBitMap},
+    helpers::{cost, edits::Edits, replace, types::PrimitiveTypesBitMap},
     primitive_type::PrimitiveType,
+    satisfiability::same_value,
 };
 use serde_json::Value;
 
@@ -10,7 +11,7 @@ use serde_json::Value;
 /// * Enum of a single value are equivalent to `const` keyword (after removal stage)
 /// * Enum with no possible variants (after removal stage) are requivalent to a `false` schema
 #[jsonschema_equivalent_rule_processor_logger::log_processing(cfg(feature = "logging"))]
-pub(crate) fn simple_enum_cleanup(schema: &mut Value) -> bool {
+pub(crate) fn simple_enum_cleanup(schema: &mut Value, edits: &mut Edits) -> bool {
     let schema_object = if let Some(value) = schema.as_object_mut() {
         value
     } else {
@@ -49,12 +50,11 @@ pub(crate) fn simple_enum_cleanup(schema: &mut Value) -> bool {
             if enum_indexes_to_remove.is_empty() {
                 false
             } else if enum_indexes_to_remove.len() == enum_values.len() {
-                replace::with_false_schema(schema)
+                replace::with_false_schema(schema, edits)
             } else {
-                for index_to_remove in enum_indexes_to_remove.iter().rev() {
-                    let _ = enum_values.remove(*index_to_remove);
-                }
-                true
+                edits.within("enum", |edits| {
+                    replace::remove_items(enum_values, &enum_indexes_to_remove, edits)
+                })
             }
         }
     } else {
@@ -62,9 +62,156 @@ pub(crate) fn simple_enum_cleanup(schema: &mut Value) -> bool {
     }
 }
 
+/// Replace `enum` keyword with a single value with the equivalent, and cheaper to validate, `const` keyword
+#[jsonschema_equivalent_rule_processor_logger::log_processing(cfg(feature = "logging"))]
+pub(crate) fn replace_singleton_enum_with_const(schema: &mut Value, edits: &mut Edits) -> bool {
+    let Some(schema_object) = schema.as_object_mut() else {
+        return false;
+    };
+    if schema_object.contains_key("const") {
+        return false;
+    }
+    if !matches!(schema_object.get("enum"), Some(Value::Array(enum_values)) if enum_values.len() == 1)
+    {
+        return false;
+    }
+    if let Some(Value::Array(mut enum_values)) =
+        replace::remove_keyword(schema_object, "enum", edits)
+    {
+        let _ = replace::keyword_with(schema_object, "const", enum_values.remove(0), edits);
+    }
+    true
+}
+
+/// Checks if `value` is valid against `type` keyword allowing `primitive_types`
+fn has_primitive_type(value: &Value, primitive_types: PrimitiveTypesBitMap) -> bool {
+    let value_primitive_type = PrimitiveType::from_serde_value(value);
+    primitive_types.contains(value_primitive_type)
+        || (
+            // `1.0` is not considered an integer, as it depends on the Draft version
+            value_primitive_type == PrimitiveType::Number
+                && (value.is_i64() || value.is_u64())
+                && primitive_types.contains(PrimitiveType::Integer)
+        )
+}
+
+/// Values allowed by a subschema defining only `const` or `enum` keyword.
+/// The subschema might define `type` keyword as well, as long as all the values are of the allowed types.
+fn allowed_values(subschema: &Value) -> Option<&[Value]> {
+    let subschema_object = subschema.as_object()?;
+    let values = match (subschema_object.get("const"), subschema_object.get("enum")) {
+        (Some(value), None) => std::slice::from_ref(value),
+        (None, Some(Value::Array(values))) => values,
+        _ => return None,
+    };
+    match (subschema_object.len(), subschema_object.get("type")) {
+        (1, None) => Some(values),
+        (2, Some(type_)) => {
+            let primitive_types = PrimitiveTypesBitMap::from_schema_value(Some(type_));
+            if values
+                .iter()
+                .all(|value| has_primitive_type(value, primitive_types))
+            {
+                Some(values)
+            } else {
+                None
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Values allowed by `anyOf` (or `oneOf` if `is_one_of`) keyword whose branches define only `const` or `enum` keywords.
+/// The values are deduplicated, according to JSON equality. In case of `oneOf` the values allowed by multiple
+/// branches are not allowed.
+fn combinator_allowed_values(branches: &[Value], is_one_of: bool) -> Option<Vec<Value>> {
+    // Values, and number of branches allowing them
+    let mut values: Vec<(&Value, usize)> = Vec::new();
+    for branch in branches {
+        let mut branch_values: Vec<&Value> = Vec::new();
+        for value in allowed_values(branch)? {
+            if !branch_values
+                .iter()
+                .any(|branch_value| same_value(branch_value, value))
+            {
+                branch_values.push(value);
+            }
+        }
+        for branch_value in branch_values {
+            if let Some((_, count)) = values
+                .iter_mut()
+                .find(|(value, _)| same_value(value, branch_value))
+            {
+                *count = count.saturating_add(1);
+            } else {
+                values.push((branch_value, 1));
+            }
+        }
+    }
+    Some(
+        values
+            .into_iter()
+            .filter(|(_, count)| !is_one_of || *count == 1)
+            .map(|(value, _)| value.clone())
+            .collect(),
+    )
+}
+
+/// Replace `anyOf` (and `oneOf`) keyword, whose subschemas only define `const` or `enum` keywords, with the
+/// equivalent `enum` keyword (intersected with the already defined `enum` keyword) if it is cheaper to validate.
+/// ie. `{"anyOf": [{"const": 1}, {"const": 2}]}` is equivalent to `{"enum": [1, 2]}`
+#[jsonschema_equivalent_rule_processor_logger::log_processing(cfg(feature = "logging"))]
+pub(crate) fn replace_any_of_values_with_enum(schema: &mut Value, edits: &mut Edits) -> bool {
+    let Some(schema_object) = schema.as_object_mut() else {
+        return false;
+    };
+
+    let mut updated_schema = false;
+    for keyword in &["anyOf", "oneOf"] {
+        let Some(branches_value) = schema_object.get(*keyword) else {
+            continue;
+        };
+        let Some(mut enum_values) = branches_value
+            .as_array()
+            .filter(|branches| !branches.is_empty())
+            .and_then(|branches| combinator_allowed_values(branches, *keyword == "oneOf"))
+        else {
+            continue;
+        };
+        if let Some(Value::Array(schema_enum_values)) = schema_object.get("enum") {
+            enum_values = schema_enum_values
+                .iter()
+                .filter(|schema_enum_value| {
+                    enum_values
+                        .iter()
+                        .any(|enum_value| same_value(enum_value, schema_enum_value))
+                })
+                .cloned()
+                .collect();
+        } else if schema_object.contains_key("enum") {
+            continue;
+        }
+        if enum_values.is_empty() {
+            return replace::with_false_schema(schema, edits);
+        }
+
+        let enum_ = Value::Array(enum_values);
+        if cost::keyword_estimate("enum", &enum_).cost
+            <= cost::keyword_estimate(keyword, branches_value).cost
+        {
+            let _ = replace::remove_keyword(schema_object, keyword, edits);
+            let _ = replace::keyword_with(schema_object, "enum", enum_, edits);
+            updated_schema = true;
+        }
+    }
+    updated_schema
+}
+
 #[cfg(test)]
 mod tests {
-    use super::simple_enum_cleanup;
+    use super::{
+        replace_any_of_values_with_enum, replace_singleton_enum_with_const, simple_enum_cleanup,
+    };
     use serde_json::{json, Value};
     use test_case::test_case;
 
@@ -76,4 +223,33 @@ mod tests {
     fn test_remove_extraneous_keys_keyword_type_does_remove_keys(schema: &Value) -> Value {
         crate::base_test_keyword_processor(&simple_enum_cleanup, schema)
     }
+
+    #[test_case(&json!({"enum": [1]}) => json!({"const": 1}))]
+    #[test_case(&json!({"enum": [1, 2]}) => json!({"enum": [1, 2]}))]
+    #[test_case(&json!({"enum": []}) => json!({"enum": []}))]
+    #[test_case(&json!({"const": 1, "enum": [1]}) => json!({"const": 1, "enum": [1]}))]
+    #[test_case(&json!({"enum": [1, 2], "type": "integer"}) => json!({"enum": [1, 2], "type": "integer"}); "enum with multiple values is left in place")]
+    fn test_replace_singleton_enum_with_const(schema: &Value) -> Value {
+        crate::base_test_keyword_processor(&replace_singleton_enum_with_const, schema)
+    }
+
+    #[test_case(&json!({"anyOf": [{"const": 1}, {"const": 2}]}) => json!({"enum": [1, 2]}))]
+    #[test_case(&json!({"anyOf": [{"const": 1}, {"enum": [2, 1, 3]}]}) => json!({"enum": [1, 2, 3]}))]
+    #[test_case(&json!({"anyOf": [{"const": 1}, {"const": 2}], "type": "integer"}) => json!({"enum": [1, 2], "type": "integer"}))]
+    #[test_case(&json!({"anyOf": [{"const": 1}]}) => json!({"enum": [1]}))]
+    #[test_case(&json!({"anyOf": [{"const": 1}, {"const": 2}], "enum": [3, 1]}) => json!({"enum": [1]}))]
+    #[test_case(&json!({"anyOf": [{"const": 1}, {"const": 2}], "enum": [3]}) => json!(false))]
+    #[test_case(&json!({"anyOf": [{"const": 1}, {"const": 1.0}, {"enum": [2, 2]}]}) => json!({"enum": [1, 2]}))]
+    #[test_case(&json!({"oneOf": [{"const": 1}, {"enum": [1, 2, 3]}, {"enum": [3, 4, 4]}]}) => json!({"enum": [2, 4]}))]
+    #[test_case(&json!({"oneOf": [{"const": 1}, {"const": 1}]}) => json!(false))]
+    #[test_case(&json!({"anyOf": [{"const": "a"}, {"const": "b"}], "oneOf": [{"const": "b"}, {"const": "c"}]}) => json!({"enum": ["b"]}))]
+    #[test_case(&json!({"anyOf": [{"const": 1}, {"const": 2, "title": "b"}]}) => json!({"anyOf": [{"const": 1}, {"const": 2, "title": "b"}]}))]
+    #[test_case(&json!({"anyOf": [{"const": 1, "type": "integer"}, {"const": "a", "type": "string"}]}) => json!({"enum": [1, "a"]}))]
+    #[test_case(&json!({"anyOf": [{"const": 1, "type": "string"}, {"const": 2}]}) => json!({"anyOf": [{"const": 1, "type": "string"}, {"const": 2}]}))]
+    #[test_case(&json!({"anyOf": [{"const": 1.0, "type": "integer"}, {"const": 2}]}) => json!({"anyOf": [{"const": 1.0, "type": "integer"}, {"const": 2}]}))]
+    #[test_case(&json!({"anyOf": [{"const": 1}, {"type": "string"}]}) => json!({"anyOf": [{"const": 1}, {"type": "string"}]}))]
+    #[test_case(&json!({"anyOf": []}) => json!({"anyOf": []}))]
+    fn test_replace_any_of_values_with_enum(schema: &Value) -> Value {
+        crate::base_test_keyword_processor(&replace_any_of_values_with_enum, schema)
+    }
 }
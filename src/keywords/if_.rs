@@ -1,11 +1,28 @@
-use crate::helpers::is;
+use crate::helpers::{edits::Edits, is, replace};
 use jsonschema_equivalent_rule_processor_logger::log_processing;
-use serde_json::Value;
+use serde_json::{Map, Value};
+
+/// Append `subschema` to the `allOf` items of `schema_object`
+fn append_to_all_of(schema_object: &mut Map<String, Value>, subschema: Value, edits: &mut Edits) {
+    match schema_object.get_mut("allOf") {
+        Some(Value::Array(all_of_items)) => {
+            edits.within("allOf", |edits| edits.add(all_of_items.len(), &subschema));
+            all_of_items.push(subschema);
+        }
+        Some(_) => {
+            let _ = replace::remove_keyword(schema_object, "allOf", edits);
+        }
+        None => {
+            let _ =
+                replace::keyword_with(schema_object, "allOf", Value::Array(vec![subschema]), edits);
+        }
+    }
+}
 
 /// Simplify `if` keyword group by embedding the content of `then` or `else` schema
 /// into `allOf` in case of deterministic validation or removing it if `then` or `else` are missing.
 #[log_processing(cfg(feature = "logging"))]
-pub(crate) fn simplify_if(schema: &mut Value) -> bool {
+pub(crate) fn simplify_if(schema: &mut Value, edits: &mut Edits) -> bool {
     let schema_object = if let Some(value) = schema.as_object_mut() {
         value
     } else {
@@ -16,36 +33,23 @@ pub(crate) fn simplify_if(schema: &mut Value) -> bool {
         if is::false_schema(if_schema) {
             // In case of a `false` schema we know that the `then` schema will never be considered
             // so we can simplify the schema by ensuring that the `else` schema has to be verified
-            let _ = schema_object.remove("if");
-            let _ = schema_object.remove("then");
-            if let Some(else_schema) = schema_object.remove("else") {
-                if let Value::Array(mut all_of_items) = schema_object
-                    .remove("allOf")
-                    .unwrap_or_else(|| Value::Array(Vec::with_capacity(1)))
-                {
-                    all_of_items.push(else_schema);
-                    let _ = schema_object.insert("allOf".to_string(), Value::Array(all_of_items));
-                }
+            let _ = replace::remove_keyword(schema_object, "if", edits);
+            let _ = replace::remove_keyword(schema_object, "then", edits);
+            if let Some(else_schema) = replace::remove_keyword(schema_object, "else", edits) {
+                append_to_all_of(schema_object, else_schema, edits);
             }
             true
         } else if is::true_schema(if_schema) {
             // In case of a `true` schema we know that the `then` schema will never be considered
             // so we can simplify the schema by ensuring that the `then` schema has to be verified
-            let _ = schema_object.remove("if");
-            let _ = schema_object.remove("else");
-            if let Some(else_schema) = schema_object.remove("then") {
-                if let Value::Array(mut all_of_items) = schema_object
-                    .remove("allOf")
-                    .unwrap_or_else(|| Value::Array(Vec::with_capacity(1)))
-                {
-                    all_of_items.push(else_schema);
-                    let _ = schema_object.insert("allOf".to_string(), Value::Array(all_of_items));
-                }
+            let _ = replace::remove_keyword(schema_object, "if", edits);
+            let _ = replace::remove_keyword(schema_object, "else", edits);
+            if let Some(then_schema) = replace::remove_keyword(schema_object, "then", edits) {
+                append_to_all_of(schema_object, then_schema, edits);
             }
             true
         } else if !schema_object.contains_key("else") && !schema_object.contains_key("then") {
-            let _ = schema_object.remove("if");
-            true
+            replace::remove_keyword(schema_object, "if", edits).is_some()
         } else {
             false
         }
@@ -1,10 +1,11 @@
+use crate::helpers::{edits::Edits, replace};
 use jsonschema_equivalent_rule_processor_logger::log_processing;
 use serde_json::Value;
 
 /// Simplify `additionalImtems` keyword by
 ///  * shrinking `items` keyword if defined as array and longer than `maxItems` keyword
 #[log_processing(cfg(feature = "logging"))]
-pub(crate) fn simplify_items(schema: &mut Value) -> bool {
+pub(crate) fn simplify_items(schema: &mut Value, edits: &mut Edits) -> bool {
     let max_items_len = schema
         .get("maxItems")
         .and_then(Value::as_u64)
@@ -21,12 +22,10 @@ pub(crate) fn simplify_items(schema: &mut Value) -> bool {
         });
 
     if let Some(Value::Array(items)) = schema.get_mut("items") {
-        if items.len() > max_items_len {
-            items.truncate(max_items_len);
-            true
-        } else {
-            false
-        }
+        let indexes_to_remove: Vec<usize> = (max_items_len..items.len()).collect();
+        edits.within("items", |edits| {
+            replace::remove_items(items, &indexes_to_remove, edits)
+        })
     } else {
         false
     }
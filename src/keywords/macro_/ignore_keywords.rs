@@ -1,4 +1,4 @@
-use crate::helpers::is;
+use crate::helpers::{edits::Edits, is, replace};
 use jsonschema_equivalent_rule_processor_logger::log_processing;
 use serde_json::Value;
 use std::collections::HashMap;
@@ -8,7 +8,7 @@ use std::collections::HashMap;
 /// * `then` or `else` keywords have no meaning if `if` keyword is not defined
 /// * `additionalItems` keyword have meaning only if `items` keyword is defined
 #[log_processing(cfg(feature = "logging"))]
-pub(crate) fn remove_keywords_in_must_ignore_groups(schema: &mut Value) -> bool {
+pub(crate) fn remove_keywords_in_must_ignore_groups(schema: &mut Value, edits: &mut Edits) -> bool {
     let schema_object = if let Some(value) = schema.as_object_mut() {
         value
     } else {
@@ -20,7 +20,7 @@ pub(crate) fn remove_keywords_in_must_ignore_groups(schema: &mut Value) -> bool
     macro_rules! define_parent_child_relation {
         ($($parent: literal => $child: literal),*,) => {
             $(if schema_object.contains_key($child) && !schema_object.contains_key($parent) {
-                let _ = schema_object.remove($child);
+                let _ = replace::remove_keyword(schema_object, $child, edits);
                 #[allow(clippy::useless_let_if_seq)]  // Clippy override needed to allow the definition of a simple macro, not defining
                 {                                     // the override would require considering the first case as an exception
                     updated_schema = true;
@@ -89,7 +89,10 @@ lazy_static::lazy_static! {
 /// Examples are:
 ///  * `additionalItems`, `additionalProperties`, `then`, `else`, set to a `true` schema
 #[log_processing(cfg(feature = "logging"))]
-pub(crate) fn omit_keywords_that_do_not_alter_schema_selectivity(schema: &mut Value) -> bool {
+pub(crate) fn omit_keywords_that_do_not_alter_schema_selectivity(
+    schema: &mut Value,
+    edits: &mut Edits,
+) -> bool {
     let schema_object = if let Some(value) = schema.as_object_mut() {
         value
     } else {
@@ -112,7 +115,7 @@ pub(crate) fn omit_keywords_that_do_not_alter_schema_selectivity(schema: &mut Va
         .collect();
 
     for keyword_to_remove in &keywords_to_remove {
-        let _ = schema_object.remove(**keyword_to_remove);
+        let _ = replace::remove_keyword(schema_object, keyword_to_remove, edits);
     }
     !keywords_to_remove.is_empty()
 }
@@ -1,4 +1,4 @@
-use crate::helpers::{replace, types::PrimitiveTypesBitMap};
+use crate::helpers::{edits::Edits, replace, types::PrimitiveTypesBitMap};
 use crate::primitive_type::PrimitiveType;
 use jsonschema_equivalent_rule_processor_logger::log_processing;
 use serde_json::Value;
@@ -12,14 +12,16 @@ fn cleanup_incongruent_keywords(
     schema_primitive_types: &mut PrimitiveTypesBitMap,
     primitive_types_to_remove: PrimitiveTypesBitMap,
     keywords_to_remove: &[&str],
+    edits: &mut Edits,
 ) -> bool {
     schema_primitive_types.remove_all(primitive_types_to_remove);
     if schema_primitive_types.is_empty() {
-        replace::with_false_schema(schema)
+        replace::with_false_schema(schema, edits)
     } else if let Value::Object(schema_object) = schema {
         let mut updated_schema = false;
         for keyword_to_remove in keywords_to_remove {
-            updated_schema |= schema_object.remove(*keyword_to_remove).is_some();
+            updated_schema |=
+                replace::remove_keyword(schema_object, keyword_to_remove, edits).is_some();
         }
         updated_schema
     } else {
@@ -33,6 +35,7 @@ fn cleanup_incongruent_keywords(
 fn update_exclusive_maximum_minimum(
     schema: &mut Value,
     schema_primitive_types: &mut PrimitiveTypesBitMap,
+    edits: &mut Edits,
 ) -> bool {
     // Checking for PrimitiveType::Integer only as PrimitiveType::Number will include integer as well
     if schema_primitive_types.contains(PrimitiveType::Integer) {
@@ -45,6 +48,7 @@ fn update_exclusive_maximum_minimum(
                 schema_primitive_types,
                 PrimitiveTypesBitMap::from(&[PrimitiveType::Integer, PrimitiveType::Number]),
                 &["exclusiveMaximum", "exclusiveMinimum"],
+                edits,
             ),
             _ => false,
         }
@@ -60,6 +64,7 @@ fn update_exclusive_maximum_minimum(
 fn update_max_min_items(
     schema: &mut Value,
     schema_primitive_types: &mut PrimitiveTypesBitMap,
+    edits: &mut Edits,
 ) -> bool {
     if schema_primitive_types.contains(PrimitiveType::Array) {
         match (
@@ -71,6 +76,7 @@ fn update_max_min_items(
                 schema_primitive_types,
                 PrimitiveTypesBitMap::from(PrimitiveType::Array),
                 &["maxItems", "minItems"],
+                edits,
             ),
             _ => false,
         }
@@ -86,6 +92,7 @@ fn update_max_min_items(
 fn update_max_min_length(
     schema: &mut Value,
     schema_primitive_types: &mut PrimitiveTypesBitMap,
+    edits: &mut Edits,
 ) -> bool {
     if schema_primitive_types.contains(PrimitiveType::String) {
         match (
@@ -97,6 +104,7 @@ fn update_max_min_length(
                 schema_primitive_types,
                 PrimitiveTypesBitMap::from(PrimitiveType::String),
                 &["maxLength", "minLength"],
+                edits,
             ),
             _ => false,
         }
@@ -112,6 +120,7 @@ fn update_max_min_length(
 fn update_max_min_properties(
     schema: &mut Value,
     schema_primitive_types: &mut PrimitiveTypesBitMap,
+    edits: &mut Edits,
 ) -> bool {
     if schema_primitive_types.contains(PrimitiveType::Object) {
         match (
@@ -123,6 +132,7 @@ fn update_max_min_properties(
                 schema_primitive_types,
                 PrimitiveType::Object.into(),
                 &["maxProperties", "minProperties"],
+                edits,
             ),
             _ => false,
         }
@@ -138,6 +148,7 @@ fn update_max_min_properties(
 fn update_maximum_minimum(
     schema: &mut Value,
     schema_primitive_types: &mut PrimitiveTypesBitMap,
+    edits: &mut Edits,
 ) -> bool {
     // Checking for PrimitiveType::Integer only as PrimitiveType::Number will include integer as well
     if schema_primitive_types.contains(PrimitiveType::Integer) {
@@ -150,6 +161,7 @@ fn update_maximum_minimum(
                 schema_primitive_types,
                 PrimitiveTypesBitMap::from(&[PrimitiveType::Integer, PrimitiveType::Number]),
                 &["maximum", "minimum"],
+                edits,
             ),
             _ => false,
         }
@@ -164,7 +176,7 @@ fn update_maximum_minimum(
 /// `maxLength`, `maxProperties`, `maximum`, `minItems`, `minLength`, `minProperties`,
 /// `minimum` keywords
 #[log_processing(cfg(feature = "logging"))]
-pub(crate) fn update_max_min_related_keywords(schema: &mut Value) -> bool {
+pub(crate) fn update_max_min_related_keywords(schema: &mut Value, edits: &mut Edits) -> bool {
     let mut updated_schema = false;
     let mut schema_primitive_types = PrimitiveTypesBitMap::from_schema(schema);
 
@@ -175,12 +187,12 @@ pub(crate) fn update_max_min_related_keywords(schema: &mut Value) -> bool {
         update_exclusive_maximum_minimum,
         update_maximum_minimum,
     ] {
-        updated_schema |= method(schema, &mut schema_primitive_types);
+        updated_schema |= method(schema, &mut schema_primitive_types, edits);
     }
 
     if updated_schema {
         if let Value::Object(schema_object) = schema {
-            let _ = replace::type_with(schema_object, schema_primitive_types);
+            let _ = replace::type_with(schema_object, schema_primitive_types, edits);
         }
     }
     updated_schema
@@ -192,23 +204,23 @@ mod tests {
         update_exclusive_maximum_minimum, update_max_min_items, update_max_min_length,
         update_max_min_properties, update_max_min_related_keywords, update_maximum_minimum,
     };
-    use crate::helpers::{replace, types::PrimitiveTypesBitMap};
+    use crate::helpers::{edits::Edits, replace, types::PrimitiveTypesBitMap};
 
     use serde_json::{json, Value};
     use test_case::test_case;
 
     fn test(
-        keyword_update_logic: fn(&mut Value, &mut PrimitiveTypesBitMap) -> bool,
+        keyword_update_logic: fn(&mut Value, &mut PrimitiveTypesBitMap, &mut Edits) -> bool,
         schema: &Value,
     ) -> Value {
         crate::base_test_keyword_processor(
-            &|schema| {
+            &|schema, edits| {
                 let mut schema_primitive_types = PrimitiveTypesBitMap::from_schema(schema);
-                let r = keyword_update_logic(schema, &mut schema_primitive_types);
+                let r = keyword_update_logic(schema, &mut schema_primitive_types, edits);
                 if let Value::Object(schema_object) = schema {
                     // Do it in the test as the tested methods are only a part of the overall
                     // `update_max_min_related_keywords` and it would perform this operation.
-                    let _ = replace::type_with(schema_object, schema_primitive_types);
+                    let _ = replace::type_with(schema_object, schema_primitive_types, edits);
                 }
                 r
             },
@@ -3,7 +3,9 @@
 mod additional_items;
 mod additional_properties;
 mod all_of;
+mod any_of;
 mod const_;
+mod custom;
 mod enum_;
 mod if_;
 mod items;
@@ -13,43 +15,275 @@ mod required;
 mod type_;
 
 use crate::{
-    constants::{KEYWORDS_WITH_DIRECT_SUBSCHEMAS, KEYWORDS_WITH_SUBSCHEMAS},
-    helpers::{is, replace},
+    helpers::{
+        annotations,
+        edits::Edits,
+        is, replace,
+        subschemas::{self, Location},
+    },
+    options::{AnnotationPolicy, Options, Profile},
+    patch::PatchOperation,
 };
 use serde_json::Value;
+use std::{
+    collections::BTreeSet,
+    time::{Duration, Instant},
+};
+
+/// Optimisation rule
+#[derive(Debug)]
+pub(crate) struct Rule {
+    /// Stable name of the rule (the name of the method, as reported by `log_processing`)
+    pub(crate) name: &'static str,
+    /// Less aggressive profile including the rule
+    pub(crate) profile: Profile,
+    /// Keywords whose values are read by the rule (`None` if the rule reads the values of any keyword).
+    /// The presence of the other keywords could be checked by the rule as well.
+    pub(crate) keywords: Option<&'static [&'static str]>,
+    /// Method applying the rule, recording its edits into the provided [`Edits`]
+    pub(crate) method: fn(&mut Value, &Options, &mut Edits) -> bool,
+}
+
+/// Build a [`Rule`] named as the method.
+/// The methods depending on the [`Options`] (ie. on the registered keywords) are marked `with options`.
+/// The keywords whose values are read by the method are listed via `reading [...]` (any keyword if omitted).
+macro_rules! rule {
+    (@keywords) => {
+        None
+    };
+    (@keywords [$($keyword:literal),*]) => {
+        Some(&[$($keyword),*])
+    };
+    ($profile:ident, $($module:ident)::+ => $method:ident $(reading [$($keyword:literal),* $(,)?])?) => {
+        Rule {
+            name: stringify!($method),
+            profile: Profile::$profile,
+            keywords: rule!(@keywords $([$($keyword),*])?),
+            method: |schema, _, edits| $($module::)+$method(schema, edits),
+        }
+    };
+    ($profile:ident, $($module:ident)::+ => $method:ident with options $(reading [$($keyword:literal),* $(,)?])?) => {
+        Rule {
+            name: stringify!($method),
+            profile: Profile::$profile,
+            keywords: rule!(@keywords $([$($keyword),*])?),
+            method: $($module::)+$method,
+        }
+    };
+}
+
+impl Rule {
+    /// Check if the rule has to be applied to a schema, given the keywords holding the subschemas modified since the
+    /// rules have been applied to the schema without modifying it (`None` if such application did not happen).
+    fn is_affected_by(&self, modified_keywords: Option<&BTreeSet<String>>) -> bool {
+        match (modified_keywords, self.keywords) {
+            (None, _) => true,
+            (Some(modified_keywords), None) => !modified_keywords.is_empty(),
+            (Some(modified_keywords), Some(keywords)) => keywords
+                .iter()
+                .any(|keyword| modified_keywords.contains(*keyword)),
+        }
+    }
+}
 
 /// Order of the methods used to update the schema
 ///
 /// NOTE: The order might be important for the capability/quality of the
 /// library so please be mindfull before modifying the order (and if you
 /// do so please motivate it in the pull request description)
-static UPDATE_SCHEMA_METHODS: &[fn(&mut Value) -> bool] = &[
-    // `remove_extraneous_keys_keyword_type` and `remove_keywords_in_must_ignore_groups`
-    // is added first as it quickly reduces the amount of keywords to process
-    type_::remove_extraneous_keys_keyword_type,
-    macro_::ignore_keywords::remove_keywords_in_must_ignore_groups,
-    macro_::ignore_keywords::omit_keywords_that_do_not_alter_schema_selectivity,
+pub(crate) static UPDATE_SCHEMA_METHODS: &[Rule] = &[
+    // `remove_custom_keywords_of_other_types`, `remove_extraneous_keys_keyword_type` and
+    // `remove_keywords_in_must_ignore_groups` are added first as they quickly reduce the amount of keywords to process
+    rule!(Safe, custom => remove_custom_keywords_of_other_types with options reading ["type"]),
+    rule!(Safe, type_ => remove_extraneous_keys_keyword_type reading ["type"]),
+    rule!(Safe, macro_::ignore_keywords => remove_keywords_in_must_ignore_groups reading []),
+    rule!(Safe, macro_::ignore_keywords => omit_keywords_that_do_not_alter_schema_selectivity reading [
+        "additionalItems", "additionalProperties", "dependencies", "else", "items", "minItems", "minLength",
+        "minProperties", "patternProperties", "properties", "propertyNames", "required", "then", "uniqueItems"
+    ]),
     // All others, currently no special ordering is defined
-    additional_items::simplify_additional_items,
-    additional_properties::simplify_additional_properties,
-    const_::simple_const_cleanup,
-    enum_::simple_enum_cleanup,
-    if_::simplify_if,
-    items::simplify_items,
-    macro_::maximum_minimum_related_keywords::update_max_min_related_keywords,
-    property_names::optimise_property_names,
-    required::remove_empty_required,
-    type_::optimise_keyword_type,
+    rule!(Default, additional_items => simplify_additional_items reading ["additionalItems", "items", "maxItems"]),
+    rule!(Aggressive, additional_items => replace_false_additional_items_with_max_items reading ["additionalItems", "items", "maxItems"]),
+    rule!(Default, any_of => factor_common_constraints reading [
+        "anyOf", "oneOf", "type", "required", "exclusiveMaximum", "exclusiveMinimum", "maxItems", "maxLength",
+        "maxProperties", "maximum", "minItems", "minLength", "minProperties", "minimum",
+    ]),
+    rule!(Default, additional_properties => simplify_additional_properties reading ["additionalProperties"]),
+    rule!(Default, const_ => simple_const_cleanup reading ["const", "type"]),
+    rule!(Default, const_ => intersect_const_with_enum reading ["const", "enum"]),
+    rule!(Default, enum_ => replace_any_of_values_with_enum reading ["anyOf", "oneOf", "enum"]),
+    rule!(Default, enum_ => simple_enum_cleanup reading ["enum", "type"]),
+    rule!(Default, enum_ => replace_singleton_enum_with_const reading ["enum"]),
+    rule!(Default, if_ => simplify_if reading ["if"]),
+    rule!(Default, items => simplify_items reading ["items", "maxItems"]),
+    rule!(Default, macro_::maximum_minimum_related_keywords => update_max_min_related_keywords reading [
+        "type", "maxItems", "minItems", "maxLength", "minLength", "maxProperties", "minProperties",
+        "exclusiveMaximum", "exclusiveMinimum", "maximum", "minimum"
+    ]),
+    rule!(Default, property_names => optimise_property_names reading ["minProperties", "propertyNames", "type"]),
+    rule!(Safe, required => remove_empty_required reading ["required"]),
+    rule!(Default, type_ => optimise_keyword_type reading ["type"]),
     // Mutli schema handling/merges needs to be done at the end
-    all_of::flatten_all_of,
-    all_of::simplify_all_of,
+    rule!(Default, all_of => flatten_all_of),
+    rule!(Default, all_of => simplify_all_of reading ["allOf", "type"]),
+    // Specialisation of the discriminated unions is done once the common constraints are factored
+    rule!(Aggressive, any_of => specialise_discriminated_union),
+    // Ordering by validation cost is done once the subschemas are in their final shape
+    rule!(Aggressive, all_of => order_all_of_by_cost reading ["allOf"]),
+    rule!(Aggressive, any_of => order_any_of_by_cost reading ["anyOf"]),
 ];
 
-/// Perform the schema optimisaton without descending the schema
-fn update_schema_no_recursive(schema: &mut Value) -> bool {
+/// Observer of the optimisation, notified about the rules applied to the schema and its subschemas.
+///
+/// The observers mirror the structure of the schema: every subschema is observed by a [`Observer::child`]
+/// observer which is then [`Observer::merge`]d, following the order of the subschemas, into the parent observer.
+pub(crate) trait Observer: Send + Sized {
+    /// `true` if the observer is notified about the schema modifications (see [`Observer::modified`]).
+    /// Providing the schema before the modifications requires to clone it.
+    const OBSERVES_MODIFICATIONS: bool = false;
+    /// `true` if the observer is notified about the edits performed by the rules (see [`Observer::edited`])
+    const OBSERVES_EDITS: bool = false;
+    /// `true` if the observer is notified about the rules applications (see [`Observer::rule_applied`])
+    const OBSERVES_RULES: bool = false;
+
+    /// Observer of the subschema at `location`
+    fn child(&self, location: Location<'_>) -> Self;
+
+    /// Merge the observations of `child` into the observations of `self`
+    fn merge(&mut self, child: Self);
+
+    /// The observed schema has been modified from `schema` to `updated_schema`
+    fn modified(&mut self, _schema: &Value, _updated_schema: &Value) {}
+
+    /// The observed schema has been edited by `operations` (relative to the observed schema)
+    fn edited(&mut self, _operations: Vec<PatchOperation>) {}
+
+    /// The rule named `rule` has been applied to the observed schema, in `elapsed` time
+    fn rule_applied(&mut self, _rule: &'static str, _is_schema_updated: bool, _elapsed: Duration) {}
+}
+
+/// No observation of the optimisation
+impl Observer for () {
+    fn child(&self, _location: Location<'_>) -> Self {}
+
+    fn merge(&mut self, _child: Self) {}
+}
+
+/// Journal of the edits performed on a schema observed by `O`
+fn edits_for<O: Observer>() -> Edits {
+    if O::OBSERVES_EDITS {
+        Edits::recording()
+    } else {
+        Edits::default()
+    }
+}
+
+/// Perform the schema optimisaton without descending the schema.
+/// The rules not affected by `modified_keywords` are skipped (see [`Rule::is_affected_by`]).
+fn update_schema_no_recursive<O: Observer>(
+    schema: &mut Value,
+    modified_keywords: Option<&BTreeSet<String>>,
+    options: &Options,
+    observer: &mut O,
+) -> bool {
+    let mut edits = edits_for::<O>();
+    if options.annotation_policy() == AnnotationPolicy::Preserve {
+        let original_schema = O::OBSERVES_MODIFICATIONS.then(|| schema.clone());
+        if let Some(schema_object) = schema.as_object_mut() {
+            let schema_annotations = annotations::detach(schema_object, options);
+            if !schema_annotations.is_empty() {
+                // The rules are not applied to annotated boolean schemas as they would be replaced
+                // by boolean schemas, losing the annotations
+                let updated_schema = !annotations::is_annotated_boolean_schema(schema)
+                    && apply_rules(
+                        schema,
+                        modified_keywords,
+                        options,
+                        observer,
+                        false,
+                        &mut edits,
+                    );
+                annotations::attach(schema, schema_annotations, &mut edits);
+                // The modifications are notified at once as the rules are applied to the schema without annotations
+                if let (true, Some(original_schema)) = (updated_schema, original_schema) {
+                    observer.modified(&original_schema, schema);
+                }
+                if updated_schema {
+                    if edits.replaces_value() {
+                        // The detached annotations are not part of the replaced schema
+                        edits = edits_for::<O>();
+                        edits.replace_value(schema);
+                    }
+                    observer.edited(edits.into_operations());
+                }
+                return updated_schema;
+            }
+        }
+    }
+    let updated_schema = apply_rules(
+        schema,
+        modified_keywords,
+        options,
+        observer,
+        true,
+        &mut edits,
+    );
+    if updated_schema {
+        observer.edited(edits.into_operations());
+    }
+    updated_schema
+}
+
+/// Apply the rule `name` (implemented by `method`) to the schema, notifying `observer` and recording the edits into `edits`.
+/// The modifications are notified only if `observe_modifications` is `true`.
+fn apply_rule<O: Observer>(
+    schema: &mut Value,
+    name: &'static str,
+    method: impl FnOnce(&mut Value, &mut Edits) -> bool,
+    observer: &mut O,
+    observe_modifications: bool,
+    edits: &mut Edits,
+) -> bool {
+    let original_schema =
+        (O::OBSERVES_MODIFICATIONS && observe_modifications).then(|| schema.clone());
+    let start = O::OBSERVES_RULES.then(Instant::now);
+    let updated_schema = method(schema, edits);
+    if let Some(start) = start {
+        observer.rule_applied(name, updated_schema, start.elapsed());
+    }
+    if let (true, Some(original_schema)) = (updated_schema, original_schema) {
+        observer.modified(&original_schema, schema);
+    }
+    updated_schema
+}
+
+/// Apply the enabled rules to the schema, without descending the schema, recording the edits into `edits`.
+///
+/// The rules not affected by `modified_keywords` are skipped as long as the schema is not modified, as they would
+/// not modify the schema. Once a rule modifies the schema all the following rules are applied.
+fn apply_rules<O: Observer>(
+    schema: &mut Value,
+    modified_keywords: Option<&BTreeSet<String>>,
+    options: &Options,
+    observer: &mut O,
+    observe_modifications: bool,
+    edits: &mut Edits,
+) -> bool {
     let mut updated_schema = false;
-    for method in UPDATE_SCHEMA_METHODS {
-        if method(schema) {
+    for rule in UPDATE_SCHEMA_METHODS {
+        if !options.is_rule_enabled(rule.name)
+            || (!updated_schema && !rule.is_affected_by(modified_keywords))
+        {
+            continue;
+        }
+        if apply_rule(
+            schema,
+            rule.name,
+            |schema, edits| (rule.method)(schema, options, edits),
+            observer,
+            observe_modifications,
+            edits,
+        ) {
             updated_schema = true;
         }
         if &Value::Bool(true) == schema {
@@ -61,52 +295,241 @@ fn update_schema_no_recursive(schema: &mut Value) -> bool {
     updated_schema
 }
 
-/// Discend the schema and optimise it.
+/// Optimisation state of a schema, mirroring the structure of its subschemas.
+///
+/// The state allows to skip, on the following optimisation rounds, the subschemas that are already optimised.
+/// A subschema is optimised if processing it did not perform any modification; considering that the rules are
+/// deterministic and depend only on the subschema they are applied to, processing it again would not perform
+/// any modification as long as the subschema is not modified by the rules applied to its parent schemas.
+///
+/// The schemas that are not known to be optimised (the modified subschemas and their ancestors) are processed again,
+/// but the rules already applied to them without modifications are applied again only if the keywords they read
+/// (see [`Rule::keywords`]) hold modified subschemas.
+#[derive(Debug, Default)]
+pub(crate) struct SchemaState {
+    /// `true` if the schema is known to be already optimised
+    optimised: bool,
+    /// `true` if the enabled rules have been applied to the schema without modifying it. Since then, the schema
+    /// could have been modified only by the optimisation of its subschemas.
+    rules_applied: bool,
+    /// State of the subschemas, in the order they are visited by [`subschemas::for_each_subschema_mut_with_location`]
+    subschemas: Vec<SchemaState>,
+}
+
+/// Minimum size, in JSON values, of the subschemas of a schema to process for optimising them concurrently.
+/// Below the threshold the overhead of the parallelisation is not worth.
+#[cfg(feature = "parallel")]
+static PARALLEL_MIN_SIZE: usize = 1024;
+
+/// Number of JSON values contained by `value` (including `value` itself), counted up to `limit`
+#[cfg(feature = "parallel")]
+fn size_up_to(value: &Value, limit: usize) -> usize {
+    match value {
+        Value::Array(items) => size_of_children_up_to(items.iter(), limit),
+        Value::Object(object) => size_of_children_up_to(object.values(), limit),
+        _ => 1,
+    }
+}
+
+/// Number of JSON values contained by `children` (including their parent), counted up to `limit`
+#[cfg(feature = "parallel")]
+fn size_of_children_up_to<'v>(children: impl Iterator<Item = &'v Value>, limit: usize) -> usize {
+    let mut size: usize = 1;
+    for child in children {
+        if size >= limit {
+            break;
+        }
+        size = size.saturating_add(size_up_to(child, limit.saturating_sub(size)));
+    }
+    size
+}
+
+/// Optimise the subschemas of `schema`, and update their state.
+/// Return the keywords holding the modified subschemas.
+#[cfg(not(feature = "parallel"))]
+fn update_subschemas_with_state<O: Observer>(
+    schema: &mut Value,
+    subschemas_state: &mut Vec<SchemaState>,
+    options: &Options,
+    observer: &mut O,
+) -> BTreeSet<String> {
+    let is_custom_subschema_keyword = |keyword: &str| options.is_custom_subschema_keyword(keyword);
+    let mut modified_keywords = BTreeSet::new();
+    let mut index = 0;
+    subschemas::for_each_subschema_mut_with_location(
+        schema,
+        &is_custom_subschema_keyword,
+        &mut |location, subschema| {
+            if subschemas_state.len() <= index {
+                subschemas_state.push(SchemaState::default());
+            }
+            let mut subschema_observer = observer.child(location);
+            if update_schema_with_state(
+                subschema,
+                &mut subschemas_state[index],
+                options,
+                &mut subschema_observer,
+            ) {
+                let _ = modified_keywords.insert(location.keyword().to_string());
+            }
+            observer.merge(subschema_observer);
+            index = index.saturating_add(1);
+        },
+    );
+    modified_keywords
+}
+
+/// Optimise the subschemas of `schema`, and update their state.
+///
+/// The subschemas are independent, so they are optimised concurrently if the subschemas to process (the ones not
+/// already optimised) are at least two and their size is at least [`PARALLEL_MIN_SIZE`].
+/// The output, as well as the observations, is identical to the sequential optimisation.
+/// Return the keywords holding the modified subschemas.
+#[cfg(feature = "parallel")]
+fn update_subschemas_with_state<O: Observer>(
+    schema: &mut Value,
+    subschemas_state: &mut Vec<SchemaState>,
+    options: &Options,
+    observer: &mut O,
+) -> BTreeSet<String> {
+    use rayon::iter::{IndexedParallelIterator, IntoParallelIterator, ParallelIterator};
+
+    let is_custom_subschema_keyword = |keyword: &str| options.is_custom_subschema_keyword(keyword);
+    let mut subschemas = Vec::new();
+    subschemas::for_each_subschema_mut_with_location(
+        schema,
+        &is_custom_subschema_keyword,
+        &mut |location, subschema| {
+            subschemas.push((
+                subschema,
+                location.keyword().to_string(),
+                observer.child(location),
+            ));
+        },
+    );
+    if subschemas_state.len() < subschemas.len() {
+        subschemas_state.resize_with(subschemas.len(), SchemaState::default);
+    }
+
+    let update_subschema = |((subschema, keyword, mut subschema_observer), subschema_state): (
+        (&mut Value, String, O),
+        &mut SchemaState,
+    )| {
+        let updated_schema =
+            update_schema_with_state(subschema, subschema_state, options, &mut subschema_observer);
+        (updated_schema.then_some(keyword), subschema_observer)
+    };
+    let mut subschemas_to_process: usize = 0;
+    let mut remaining_size = PARALLEL_MIN_SIZE;
+    for ((subschema, _, _), subschema_state) in subschemas.iter().zip(subschemas_state.iter()) {
+        if !subschema_state.optimised {
+            subschemas_to_process = subschemas_to_process.saturating_add(1);
+            remaining_size = remaining_size.saturating_sub(size_up_to(subschema, remaining_size));
+        }
+    }
+
+    let results: Vec<(Option<String>, O)> = if subschemas_to_process < 2 || remaining_size > 0 {
+        subschemas
+            .into_iter()
+            .zip(subschemas_state.iter_mut())
+            .map(update_subschema)
+            .collect()
+    } else {
+        subschemas
+            .into_par_iter()
+            .zip(subschemas_state.into_par_iter())
+            .map(update_subschema)
+            .collect()
+    };
+
+    let mut modified_keywords = BTreeSet::new();
+    for (modified_keyword, subschema_observer) in results {
+        modified_keywords.extend(modified_keyword);
+        observer.merge(subschema_observer);
+    }
+    modified_keywords
+}
+
+/// Discend the schema and optimise it, skipping the subschemas that `state` reports as already optimised.
+/// The optimisation is notified to `observer`.
 /// Return true if schema modifications have been performed
-pub(crate) fn update_schema(schema: &mut Value) -> bool {
+pub(crate) fn update_schema_with_state<O: Observer>(
+    schema: &mut Value,
+    state: &mut SchemaState,
+    options: &Options,
+    observer: &mut O,
+) -> bool {
+    if state.optimised {
+        return false;
+    }
+
     let mut updated_schema = false;
-    if is::true_schema(schema) {
-        return replace::with_true_schema(schema);
-    } else if let Value::Object(schema_object) = schema {
-        for (key, subschema) in schema_object {
-            if KEYWORDS_WITH_SUBSCHEMAS.contains(&key.as_ref()) {
-                match subschema {
-                    Value::Object(subschema_object) => {
-                        if KEYWORDS_WITH_DIRECT_SUBSCHEMAS.contains(&key.as_ref()) {
-                            // In case of schemas where the keyword value MUST be a valid JSON Schema
-                            // ie. `{"additionalProperties": {"type": "string"}}`
-                            updated_schema |= update_schema(subschema);
-                        } else {
-                            // In case of schemas where the keyword holds a JSON Object and its
-                            // values MUST be a valid JSON Schema
-                            // ie. `{"properties": {"property" {"type": "string"}}}`
-                            for subschema_value in subschema_object.values_mut() {
-                                updated_schema |= update_schema(subschema_value);
-                            }
-                        }
-                    }
-                    Value::Array(subschema_array) => {
-                        // In case of schemas where the keyword holds a JSON Array and its
-                        // values MUST be a valid JSON Schema
-                        // ie. `{"allOf": [{"type": "string"}]}`
-                        for subschema_value in subschema_array {
-                            updated_schema |= update_schema(subschema_value);
-                        }
-                    }
-                    _ => {}
-                }
-                updated_schema |= update_schema(subschema);
-            }
+    // The registered annotations are lost only if they are not preserved
+    if is::true_schema(schema)
+        || (options.annotation_policy() != AnnotationPolicy::Preserve
+            && annotations::has_only_registered_annotations(schema, options))
+    {
+        let original_schema = O::OBSERVES_MODIFICATIONS.then(|| schema.clone());
+        let mut edits = edits_for::<O>();
+        updated_schema = replace::with_true_schema(schema, &mut edits);
+        if let (true, Some(original_schema)) = (updated_schema, original_schema) {
+            observer.modified(&original_schema, schema);
+        }
+        if updated_schema {
+            observer.edited(edits.into_operations());
         }
+    } else if schema.is_object() {
+        let modified_keywords =
+            update_subschemas_with_state(schema, &mut state.subschemas, options, observer);
+        updated_schema |= !modified_keywords.is_empty();
 
-        updated_schema |= update_schema_no_recursive(schema);
+        let rules_applied = state.rules_applied;
+        state.rules_applied = true;
+        if update_schema_no_recursive(
+            schema,
+            rules_applied.then_some(&modified_keywords),
+            options,
+            observer,
+        ) {
+            // The rules could have modified any subschema, so their state is not reliable anymore
+            state.subschemas.clear();
+            state.rules_applied = false;
+            updated_schema = true;
+        }
     }
+
+    state.optimised = !updated_schema;
+    if state.optimised {
+        // The state of the subschemas is not needed as the whole schema is skipped
+        state.subschemas.clear();
+    }
+    updated_schema
+}
+
+/// Discend the schema and optimise it, recording the edits into `edits`.
+/// Return true if schema modifications have been performed
+#[cfg(test)]
+pub(crate) fn update_schema(schema: &mut Value, edits: &mut Edits) -> bool {
+    let mut recorder = crate::patch::Recorder::default();
+    let updated_schema = update_schema_with_state(
+        schema,
+        &mut SchemaState::default(),
+        &Options::default(),
+        &mut recorder,
+    );
+    edits.extend(recorder.into_operations());
     updated_schema
 }
 
 #[cfg(test)]
 mod tests {
-    use super::update_schema;
+    #[cfg(feature = "parallel")]
+    use super::size_up_to;
+    use super::{update_schema, update_schema_with_state, SchemaState, UPDATE_SCHEMA_METHODS};
+    use crate::helpers::edits::Edits;
+    use crate::{
+        jsonschema_equivalent_with_options, jsonschema_equivalent_with_stats_ref, options::Options,
+    };
     use serde_json::{json, Value};
 
     use test_case::test_case;
@@ -114,6 +537,8 @@ mod tests {
     #[test_case(&json!({}) => json!(true))]
     #[test_case(&json!({"properties": {"prop": {"type": "string", "minimum": 1}}}) => json!({"properties": {"prop": {"type": "string"}}}))]
     #[test_case(&json!({"allOf": [{"type": "string", "minimum": 1}]}) => json!({"type": "string"}))]
+    #[test_case(&json!({"items": {"type": "string", "minimum": 1}}) => json!({"items": {"type": "string"}}))]
+    #[test_case(&json!({"properties": {"minimum": {"type": "string", "minimum": 1}}}) => json!({"properties": {"minimum": {"type": "string"}}}))]
     #[test_case(
         &json!({"allOf": [{"properties": {"bar": {"type": "integer"}}, "required": ["bar"]}, {"properties": {"foo": {"type": "string"}}, "required": ["foo"]}]})
         => json!({"allOf": [{"properties": {"bar": {"type":"integer"}}, "required": ["bar"]}, {"properties": {"foo": {"type": "string"}}, "required": ["foo"]}], "required": ["bar", "foo"]})
@@ -121,4 +546,123 @@ mod tests {
     fn test_update_schema_descend_schema(schema: &Value) -> Value {
         crate::base_test_keyword_processor(&update_schema, schema)
     }
+
+    // Only the subschemas are optimised, and not the values of the keywords holding them (ie. the `properties` object)
+    // nor the values of the other keywords. Visiting every value of the keywords used to alter the schemas as follows:
+    // * `{"properties": {"maxLength": {"type": "string"}}}`, removing the property named as a keyword
+    // * `{"enum": [{"type": "string"}], "type": "object"}`, rewriting an `enum` value and removing `{}` (seen as `true`)
+    // * `{"const": {"type": "string"}, "type": "object"}`, rewriting the `const` value
+    #[test_case(&json!({"properties": {"type": {"type": "string"}, "maxLength": {"type": "string"}}}) => json!({"properties": {"maxLength": {"type": "string"}, "type": {"type": "string"}}}))]
+    #[test_case(&json!({"type": "object", "enum": [{"allOf": [{"type": "string"}]}, {}]}) => json!({"type": "object", "enum": [{"allOf": [{"type": "string"}]}, {}]}))]
+    #[test_case(&json!({"type": "object", "const": {"type": "string", "minimum": 1}}) => json!({"type": "object", "const": {"type": "string", "minimum": 1}}))]
+    fn test_update_schema_optimises_only_subschemas(schema: &Value) -> Value {
+        crate::base_test_keyword_processor(&update_schema, schema)
+    }
+
+    #[test_case(&json!({"properties": {"a": {"allOf": [{"type": "string"}, {"minLength": 1}]}, "b": {"type": "string", "minimum": 1}}}))]
+    #[test_case(&json!({"allOf": [{"allOf": [{"type": ["integer", "string"]}, {"type": "number"}]}, {"items": {"not": {"not": {"type": "string"}}}}]}))]
+    #[test_case(&json!({"anyOf": [{"if": {"type": "string"}, "then": {"maxLength": 2}}, {"additionalItems": false, "items": [{}, {}]}]}))]
+    #[test_case(&json!({"properties": (0..32).map(|index| (format!("p{index}"), json!({"type": "string", "minimum": index}))).collect::<serde_json::Map<_, _>>()}))]
+    #[test_case(&json!({"type": "object", "propertyNames": {"allOf": [{"type": ["integer", "string"]}, {"maxLength": 2}]}, "anyOf": [{"allOf": [{"required": ["a"]}]}, {"not": {"type": "string", "minimum": 1}}]}))]
+    #[test_case(&json!({"if": {"allOf": [{"allOf": [true]}]}, "then": {"minimum": 1}, "additionalItems": {"allOf": [{}]}, "items": [{"enum": [1, "a"], "type": "string"}]}))]
+    fn test_update_schema_with_state_is_equivalent_to_full_rounds(schema: &Value) {
+        crate::init_logger();
+        let mut expected_schema = schema.clone();
+        let mut expected_rounds: usize = 0;
+        while update_schema(&mut expected_schema, &mut Edits::default()) {
+            expected_rounds = expected_rounds.saturating_add(1);
+        }
+
+        let mut processed_schema = schema.clone();
+        let mut state = SchemaState::default();
+        let mut rounds: usize = 0;
+        while update_schema_with_state(
+            &mut processed_schema,
+            &mut state,
+            &Options::default(),
+            &mut (),
+        ) {
+            rounds = rounds.saturating_add(1);
+        }
+        assert_eq!(processed_schema, expected_schema);
+        assert_eq!(rounds, expected_rounds);
+    }
+
+    // Outputs of the optimisation rounds processing the whole schema, before the optimisation state was introduced
+    // (the `all_rules.md` examples are pinned by `tests/all_rules.rs`). The rules added since then are disabled.
+    #[test_case(json!({"type": "string", "minimum": 1, "properties": {"a": {"type": "integer", "minLength": 1}}}) => json!({"type": "string"}))]
+    #[test_case(json!({"properties": {"a": {"allOf": [{"type": "string"}], "minimum": 1}}}) => json!({"properties": {"a": {"type": "string"}}}))]
+    #[test_case(
+        json!({"properties": {"a": {"properties": {"b": {"allOf": [{"type": "integer"}, {"minimum": 1, "minLength": 1}]}}}}})
+        => json!({"properties": {"a": {"properties": {"b": {"minimum": 1, "type": "integer"}}}}})
+    )]
+    #[test_case(
+        json!({"items": [{"anyOf": [{"type": "number", "maxLength": 1}]}, {"not": {"type": "string", "minimum": 1}}], "additionalItems": {"required": []}})
+        => json!({"items": [{"anyOf": [{"type": "number"}]}, {"not": {"type": "string"}}]})
+    )]
+    #[test_case(
+        json!({"allOf": [{"properties": {"bar": {"type": "integer"}}, "required": ["bar"]}, {"properties": {"foo": {"type": "string"}}, "required": ["foo"]}]})
+        => json!({"allOf": [{"properties": {"bar": {"type": "integer"}}, "required": ["bar"]}, {"properties": {"foo": {"type": "string"}}, "required": ["foo"]}], "required": ["bar", "foo"]})
+    )]
+    #[test_case(
+        json!({"if": {"type": "string"}, "then": {"minLength": 1, "minimum": 2}, "else": {"type": "integer", "minLength": 2}})
+        => json!({"else": {"type": "integer"}, "if": {"type": "string"}, "then": {"minLength": 1, "minimum": 2}})
+    )]
+    #[test_case(
+        json!({"definitions": {"a": {"type": "boolean", "minItems": 1}}, "properties": {"x": {"$ref": "#/definitions/a"}, "y": {"allOf": [{"type": ["string", "null"]}, {"type": "string"}]}}})
+        => json!({"definitions": {"a": {"minItems": 1, "type": "boolean"}}, "properties": {"x": {"$ref": "#/definitions/a"}, "y": {"type": "string"}}})
+    )]
+    #[test_case(
+        json!({"type": "object", "properties": {"a": {"type": "string", "minLength": 2, "maxLength": 1}}, "required": ["a"]})
+        => json!({"properties": {"a": false}, "required": ["a"], "type": "object"})
+    )]
+    #[test_case(
+        json!({"propertyNames": {"allOf": [{"minLength": 1}], "minimum": 1}, "type": "object", "minProperties": 0})
+        => json!({"propertyNames": {"minLength": 1, "type": "string"}, "type": "object"})
+    )]
+    #[test_case(
+        json!({"dependencies": {"a": {"type": "object", "allOf": [{"minProperties": 1}]}}, "patternProperties": {"^a": {"type": "null", "enum": [null, 1]}}})
+        => json!({"dependencies": {"a": {"minProperties": 1, "type": "object"}}, "patternProperties": {"^a": {"enum": [null], "type": "null"}}})
+    )]
+    fn test_update_schema_with_state_preserves_full_rounds_outputs(schema: Value) -> Value {
+        crate::init_logger();
+        let options = Options::new()
+            .disable_rule("factor_common_constraints")
+            .disable_rule("intersect_const_with_enum")
+            .disable_rule("replace_any_of_values_with_enum")
+            .disable_rule("replace_singleton_enum_with_const");
+        jsonschema_equivalent_with_options(schema, &options)
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test_case(&json!(1), 10 => 1)]
+    #[test_case(&json!({"type": "string", "enum": ["a", "b"]}), 10 => 5)]
+    #[test_case(&json!({"properties": {"a": {"type": "string"}, "b": {"type": "string"}}}), 3 => 3)]
+    fn test_size_up_to(value: &Value, limit: usize) -> usize {
+        size_up_to(value, limit)
+    }
+
+    #[test]
+    fn test_update_schema_with_state_skips_rules_not_reading_modified_keywords() {
+        let mut schema =
+            json!({"properties": {"a": {"allOf": [{"type": "string"}], "minimum": 1}}});
+        let (_, stats) = jsonschema_equivalent_with_stats_ref(&mut schema, &Options::default());
+        assert_eq!(schema, json!({"properties": {"a": {"type": "string"}}}));
+        assert_eq!(stats.rounds, 3);
+        // The first round processes `/properties/a/allOf/0`, `/properties/a` (flattening `allOf`) and the root schema.
+        // The second round processes `/properties/a` (removing `minimum`) and the root schema, only by the rules reading
+        // `properties`. The third round processes only `/properties/a`, as no subschema of the root schema is modified.
+        assert_eq!(stats.rules["remove_empty_required"].applications, 5);
+        assert_eq!(
+            stats.rules["omit_keywords_that_do_not_alter_schema_selectivity"].applications,
+            6
+        );
+    }
+
+    #[test]
+    fn test_update_schema_methods_names_are_unique() {
+        let names: std::collections::HashSet<_> =
+            UPDATE_SCHEMA_METHODS.iter().map(|rule| rule.name).collect();
+        assert_eq!(names.len(), UPDATE_SCHEMA_METHODS.len());
+    }
 }
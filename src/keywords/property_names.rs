@@ -1,4 +1,4 @@
-use crate::helpers::{is, replace, types::PrimitiveTypesBitMap};
+use crate::helpers::{edits::Edits, is, replace, types::PrimitiveTypesBitMap};
 use crate::primitive_type::PrimitiveType;
 use jsonschema_equivalent_rule_processor_logger::log_processing;
 use serde_json::Value;
@@ -13,7 +13,7 @@ use serde_json::Value;
 ///  * if a JSON Object would be a valid instance, then we know that the attributes MUST be strings so we
 ///     can simplify the `propertyNames` schema allowing only `type` string instances
 #[log_processing(cfg(feature = "logging"))]
-pub(crate) fn optimise_property_names(schema: &mut Value) -> bool {
+pub(crate) fn optimise_property_names(schema: &mut Value, edits: &mut Edits) -> bool {
     let schema_object = if let Some(value) = schema.as_object_mut() {
         value
     } else {
@@ -31,8 +31,7 @@ pub(crate) fn optimise_property_names(schema: &mut Value) -> bool {
     };
 
     if is::true_schema(property_names_schema) {
-        let _ = schema_object.remove("propertyNames");
-        return true;
+        return replace::remove_keyword(schema_object, "propertyNames", edits).is_some();
     }
 
     let mut updated_schema = false;
@@ -43,10 +42,13 @@ pub(crate) fn optimise_property_names(schema: &mut Value) -> bool {
         // We know that `propertyNames` is an object as we have types in the bitmap.
         // A `false` schema does not have types in the bitmap
         if let Value::Object(property_names_schema_object) = property_names_schema {
-            updated_schema |= replace::type_with(
-                property_names_schema_object,
-                PrimitiveTypesBitMap::from(PrimitiveType::String),
-            );
+            updated_schema |= edits.within("propertyNames", |edits| {
+                replace::type_with(
+                    property_names_schema_object,
+                    PrimitiveTypesBitMap::from(PrimitiveType::String),
+                    edits,
+                )
+            });
         }
     }
 
@@ -59,10 +61,10 @@ pub(crate) fn optimise_property_names(schema: &mut Value) -> bool {
 
             if schema_min_properties > 0.0 {
                 schema_primitive_types.remove(PrimitiveType::Object);
-                if replace::type_with(schema_object, schema_primitive_types) {
+                if replace::type_with(schema_object, schema_primitive_types, edits) {
                     if schema_object.get("type") == None {
                         // If the only supported type was object then the schema is just a `false` schema
-                        let _ = replace::with_false_schema(schema);
+                        let _ = replace::with_false_schema(schema, edits);
                     }
                     // We were able to modify the schema on `replace::type_with`
                     true
@@ -70,8 +72,9 @@ pub(crate) fn optimise_property_names(schema: &mut Value) -> bool {
                     false
                 }
             } else {
-                let _ = schema_object.insert("maxProperties".to_string(), Value::from(0));
-                let _ = schema_object.remove("propertyNames");
+                let _ =
+                    replace::keyword_with(schema_object, "maxProperties", Value::from(0), edits);
+                let _ = replace::remove_keyword(schema_object, "propertyNames", edits);
                 true
             }
         } else if let Value::Object(property_names_schema_object) = property_names_schema {
@@ -82,13 +85,15 @@ pub(crate) fn optimise_property_names(schema: &mut Value) -> bool {
                 // to have only one primitive type (if not defined all the primitive types would be present) and the schema has only 1 property
                 // In this case `propertyNames` is only saying that the type of a JSON property is a string, and this is guaranteed by JSON already
                 // so the `keyword` is not adding any restriction
-                let _ = schema_object.remove("propertyNames");
-                true
+                replace::remove_keyword(schema_object, "propertyNames", edits).is_some()
             } else {
-                replace::type_with(
-                    property_names_schema_object,
-                    PrimitiveTypesBitMap::from(PrimitiveType::String),
-                )
+                edits.within("propertyNames", |edits| {
+                    replace::type_with(
+                        property_names_schema_object,
+                        PrimitiveTypesBitMap::from(PrimitiveType::String),
+                        edits,
+                    )
+                })
             }
         } else {
             // This is impossible because we know that type string is included, so (1) it cannot be a false schema,
@@ -96,8 +101,7 @@ pub(crate) fn optimise_property_names(schema: &mut Value) -> bool {
             false
         }
     } else {
-        let _ = schema_object.remove("propertyNames");
-        true
+        replace::remove_keyword(schema_object, "propertyNames", edits).is_some()
     };
 
     updated_schema
@@ -1,17 +1,19 @@
+use crate::helpers::{edits::Edits, replace};
 use jsonschema_equivalent_rule_processor_logger::log_processing;
 use serde_json::Value;
 
 /// Removes empty `required` schemas.
 #[log_processing(cfg(feature = "logging"))]
-pub(crate) fn remove_empty_required(schema: &mut Value) -> bool {
+pub(crate) fn remove_empty_required(schema: &mut Value, edits: &mut Edits) -> bool {
     match schema.get("required") {
-        Some(Value::Array(array)) if array.is_empty() => {
-            let _ = schema
+        Some(Value::Array(array)) if array.is_empty() => replace::remove_keyword(
+            schema
                 .as_object_mut()
-                .expect("As a property exist we're sure that we're dealing with an object")
-                .remove("required");
-            true
-        }
+                .expect("As a property exist we're sure that we're dealing with an object"),
+            "required",
+            edits,
+        )
+        .is_some(),
         _ => false,
     }
 }
@@ -1,5 +1,5 @@
 use crate::{
-    helpers::{preserve_keys, replace, types::PrimitiveTypesBitMap},
+    helpers::{edits::Edits, preserve_keys, replace, types::PrimitiveTypesBitMap},
     primitive_type::PrimitiveType,
 };
 use jsonschema_equivalent_rule_processor_logger::log_processing;
@@ -99,7 +99,7 @@ lazy_static::lazy_static! {
 
 /// Removes duplicated types, avoid not need of list and remove the keyword if all the types are included
 #[log_processing(cfg(feature = "logging"))]
-pub(crate) fn optimise_keyword_type(schema: &mut Value) -> bool {
+pub(crate) fn optimise_keyword_type(schema: &mut Value, edits: &mut Edits) -> bool {
     let schema_object = if let Some(value) = schema.as_object_mut() {
         value
     } else {
@@ -109,13 +109,14 @@ pub(crate) fn optimise_keyword_type(schema: &mut Value) -> bool {
     replace::type_with(
         schema_object,
         PrimitiveTypesBitMap::from_schema_value(schema_object.get("type")),
+        edits,
     )
 }
 
 /// Removes all the schema keywords that are irrelevant/incongruent with the presence
 /// of a specific `type` keyword
 #[log_processing(cfg(feature = "logging"))]
-pub(crate) fn remove_extraneous_keys_keyword_type(schema: &mut Value) -> bool {
+pub(crate) fn remove_extraneous_keys_keyword_type(schema: &mut Value, edits: &mut Edits) -> bool {
     let schema_object = if let Some(value) = schema.as_object_mut() {
         value
     } else {
@@ -149,9 +150,9 @@ pub(crate) fn remove_extraneous_keys_keyword_type(schema: &mut Value) -> bool {
             keys_to_reserve.extend(KEYWORDS_TYPE_STRING.iter());
         }
 
-        let removed_keys = preserve_keys(schema_object, &keys_to_reserve);
+        let removed_keys = preserve_keys(schema_object, &keys_to_reserve, edits);
 
-        replace::type_with(schema_object, primitive_types) || removed_keys
+        replace::type_with(schema_object, primitive_types, edits) || removed_keys
     }
 }
 
@@ -62,12 +62,49 @@
     variant_size_differences
 )]
 
+mod bundle;
+mod canonical;
+pub mod compat;
 pub(crate) mod constants;
+mod equivalence;
+mod error;
+mod extract;
+mod fingerprint;
 pub(crate) mod helpers;
+mod inclusion;
 mod keywords;
+mod lint;
+mod options;
+mod patch;
 pub(crate) mod primitive_type;
+mod satisfiability;
+mod sharing;
+mod source_map;
+mod specialise;
+mod stats;
+use helpers::edits::Edits;
 use serde_json::Value;
 
+pub use bundle::{
+    bundle, bundle_with_options, try_jsonschema_equivalent_with_resolver, FileSystemResolver,
+    InMemoryResolver, Resolver,
+};
+pub use canonical::canonicalise;
+pub use equivalence::{are_equivalent, Equivalence};
+pub use error::Error;
+pub use extract::{extract, extract_with_options};
+pub use fingerprint::{fingerprint, FINGERPRINT_FORMAT_VERSION};
+pub use inclusion::{is_subschema, Inclusion};
+pub use lint::{lint, Diagnostic, Severity};
+pub use options::{AnnotationPolicy, CustomKeyword, Options, Profile, UnknownKeywordPolicy};
+pub use patch::{jsonschema_equivalent_with_patch, PatchOperation};
+pub use primitive_type::PrimitiveType;
+pub use satisfiability::{is_satisfiable, Satisfiability};
+pub use sharing::{share_subschemas, DEFAULT_SHARE_SUBSCHEMAS_MIN_SIZE};
+pub use source_map::{jsonschema_equivalent_with_source_map, SourceMap};
+pub use specialise::{specialise, specialise_with_options, Fact};
+pub use stats::{jsonschema_equivalent_with_stats_ref, RuleStats, SchemaStats, Stats};
+
 /// Maximum number of allowed rounds to update the schema. This is needed to prevent, unlikely but possible, infinite loop
 static MAX_UPDATE_SCHEMA_ITERATIONS: usize = 100;
 
@@ -76,9 +113,111 @@ static MAX_UPDATE_SCHEMA_ITERATIONS: usize = 100;
 #[must_use]
 #[inline]
 pub fn jsonschema_equivalent_ref(schema: &mut Value) -> &mut Value {
-    for _ in 0..MAX_UPDATE_SCHEMA_ITERATIONS {
-        if !keywords::update_schema(schema) {
-            return schema;
+    jsonschema_equivalent_with_options_ref(schema, &Options::default())
+}
+
+/// Optimise input schema, as [`jsonschema_equivalent_ref`], applying only the rules enabled by `options`.
+///
+/// If the schema cannot be optimised (see [`try_jsonschema_equivalent_with_options_ref`]) it is not modified.
+#[must_use]
+pub fn jsonschema_equivalent_with_options_ref<'s>(
+    schema: &'s mut Value,
+    options: &Options,
+) -> &'s mut Value {
+    match prepare_schema(schema, options, &mut Edits::default()) {
+        Ok(()) => {
+            let _ = optimise_schema(schema, options, &mut ());
+            schema
+        }
+        Err(error) => {
+            log::warn!("Schema not optimised: {error}");
+            schema
+        }
+    }
+}
+
+/// Optimise input schema, as [`jsonschema_equivalent_with_options_ref`], reporting the errors instead of ignoring them.
+///
+/// The schema is not modified in case of errors.
+///
+/// # Errors
+///
+/// * [`Error::UnknownKeyword`] if the schema contains unknown keywords and [`UnknownKeywordPolicy::Error`] is used.
+/// * [`Error::UnknownPrimitiveType`] if `type` contains a name that is not a primitive type (ie. `{"type": "strnig"}`).
+/// * [`Error::InvalidSchema`], [`Error::InvalidKeywordValue`] or [`Error::DuplicatedItem`]
+///   if the schema does not satisfy the rules of the meta-schemas and [`Options::with_strict_mode`] is used.
+pub fn try_jsonschema_equivalent_with_options_ref<'s>(
+    schema: &'s mut Value,
+    options: &Options,
+) -> Result<&'s mut Value, Error> {
+    prepare_schema(schema, options, &mut Edits::default())?;
+    let _ = optimise_schema(schema, options, &mut ());
+    Ok(schema)
+}
+
+/// Generate an equivalent schema to the schema provided as input, applying only the rules enabled by `options`
+/// and reporting the errors instead of ignoring them.
+/// ```rust
+/// use jsonschema_equivalent::{try_jsonschema_equivalent_with_options, Error, Options, UnknownKeywordPolicy};
+/// use serde_json::json;
+///
+/// let options = Options::new().with_unknown_keyword_policy(UnknownKeywordPolicy::Error);
+/// assert_eq!(
+///     try_jsonschema_equivalent_with_options(json!({"properties": {"a": {"minimun": 1}}}), &options),
+///     Err(Error::UnknownKeyword { pointer: "/properties/a".to_string(), keyword: "minimun".to_string() }),
+/// );
+///
+/// let options = Options::new().with_strict_mode(true);
+/// assert_eq!(
+///     try_jsonschema_equivalent_with_options(json!({"type": "object", "required": ["a", "a"]}), &options),
+///     Err(Error::DuplicatedItem { pointer: "".to_string(), keyword: "required".to_string(), item: "a".to_string() }),
+/// );
+/// ```
+///
+/// # Errors
+///
+/// See [`try_jsonschema_equivalent_with_options_ref`].
+pub fn try_jsonschema_equivalent_with_options(
+    mut schema: Value,
+    options: &Options,
+) -> Result<Value, Error> {
+    let _ = try_jsonschema_equivalent_with_options_ref(&mut schema, options)?;
+    Ok(schema)
+}
+
+/// Apply the policies of `options` that act once on the whole schema, before its optimisation,
+/// recording the edits into `edits`
+fn prepare_schema(schema: &mut Value, options: &Options, edits: &mut Edits) -> Result<(), Error> {
+    if options.strict_mode() {
+        helpers::meta_schema::check(schema, "", options)?;
+    } else {
+        helpers::meta_schema::check_primitive_types(schema, "", options)?;
+    }
+    match options.unknown_keyword_policy() {
+        UnknownKeywordPolicy::Keep => {}
+        UnknownKeywordPolicy::Strip => {
+            let _ = helpers::unknown_keywords::strip(schema, options, edits);
+        }
+        UnknownKeywordPolicy::Error => helpers::unknown_keywords::check(schema, "", options)?,
+    }
+    if options.annotation_policy() == AnnotationPolicy::Strip {
+        let _ = helpers::annotations::strip(schema, options, edits);
+    }
+    Ok(())
+}
+
+/// Optimise the schema applying the rules enabled by `options`, notifying the optimisation to `observer`.
+/// Return the number of performed optimisation rounds.
+fn optimise_schema<O: keywords::Observer>(
+    schema: &mut Value,
+    options: &Options,
+    observer: &mut O,
+) -> usize {
+    // The state allows every round to process only the subschemas modified by the previous round
+    let mut state = keywords::SchemaState::default();
+    for round in 1..=MAX_UPDATE_SCHEMA_ITERATIONS {
+        if !keywords::update_schema_with_state(schema, &mut state, options, observer) {
+            return round;
         }
     }
     log::info!(
@@ -86,7 +225,7 @@ pub fn jsonschema_equivalent_ref(schema: &mut Value) -> &mut Value {
         MAX_UPDATE_SCHEMA_ITERATIONS,
         schema
     );
-    schema
+    MAX_UPDATE_SCHEMA_ITERATIONS
 }
 
 /// Generate an equivalent schema to the schema provided as input
@@ -106,6 +245,46 @@ pub fn jsonschema_equivalent(mut schema: Value) -> Value {
     schema
 }
 
+/// Optimise input schema, as [`jsonschema_equivalent_ref`], and transform it into its canonical form (see [`canonicalise`]).
+///
+/// Equivalent inputs differing only on the order of keys, `required`/`type`/`enum` items or `allOf`/`anyOf` branches
+/// produce byte-identical outputs.
+/// The input is canonicalised before the optimisation as well, as the outcome of the rules might depend on the order
+/// of the items (ie. the order in which `allOf` branches are merged).
+#[must_use]
+#[inline]
+pub fn jsonschema_equivalent_canonical_ref(schema: &mut Value) -> &mut Value {
+    canonicalise(schema);
+    let schema = jsonschema_equivalent_ref(schema);
+    canonicalise(schema);
+    schema
+}
+
+/// Generate an equivalent schema, in its canonical form, to the schema provided as input
+/// ```rust
+/// use jsonschema_equivalent::jsonschema_equivalent_canonical;
+/// use serde_json::json;
+///
+/// assert_eq!(
+///     jsonschema_equivalent_canonical(json!({"type": "object", "required": ["b", "a"]})).to_string(),
+///     jsonschema_equivalent_canonical(json!({"required": ["a", "b"], "type": "object"})).to_string(),
+/// );
+/// ```
+#[must_use]
+#[inline]
+pub fn jsonschema_equivalent_canonical(mut schema: Value) -> Value {
+    let _ = jsonschema_equivalent_canonical_ref(&mut schema);
+    schema
+}
+
+/// Generate an equivalent schema to the schema provided as input, applying only the rules enabled by `options`
+#[must_use]
+#[inline]
+pub fn jsonschema_equivalent_with_options(mut schema: Value, options: &Options) -> Value {
+    let _ = jsonschema_equivalent_with_options_ref(&mut schema, options);
+    schema
+}
+
 #[cfg(test)]
 pub(crate) fn init_logger() {
     use std::io::Write;
@@ -115,14 +294,34 @@ pub(crate) fn init_logger() {
         .try_init();
 }
 
+/// Assert that the operations recorded by `edits` transform `schema` into `updated_schema`
+#[cfg(test)]
+pub(crate) fn assert_edits_transform(schema: &Value, edits: Edits, updated_schema: &Value) {
+    let mut patched_schema = schema.clone();
+    for operation in edits.into_operations() {
+        assert!(
+            operation.apply(&mut patched_schema),
+            "{:?} cannot be applied to {}",
+            operation,
+            patched_schema
+        );
+    }
+    assert_eq!(
+        &patched_schema, updated_schema,
+        "Edits of {schema} are not complete"
+    );
+}
+
 #[cfg(test)]
 pub(crate) fn base_test_keyword_processor(
-    keyword_processing_method: &dyn Fn(&mut Value) -> bool,
+    keyword_processing_method: &dyn Fn(&mut Value, &mut Edits) -> bool,
     schema: &Value,
 ) -> Value {
     init_logger();
     let mut processed_schema: Value = schema.clone();
-    let is_schema_updated = keyword_processing_method(&mut processed_schema);
+    let mut edits = Edits::recording();
+    let is_schema_updated = keyword_processing_method(&mut processed_schema, &mut edits);
+    assert_edits_transform(schema, edits, &processed_schema);
     assert_eq!(
         is_schema_updated,
         schema != &processed_schema,
@@ -136,7 +335,9 @@ pub(crate) fn base_test_keyword_processor(
 
 #[cfg(test)]
 mod tests {
-    use super::{jsonschema_equivalent, jsonschema_equivalent_ref};
+    use super::{
+        jsonschema_equivalent, jsonschema_equivalent_canonical, jsonschema_equivalent_ref,
+    };
     use serde_json::{json, Value};
     use test_case::test_case;
 
@@ -152,4 +353,40 @@ mod tests {
         crate::init_logger();
         jsonschema_equivalent(schema)
     }
+
+    #[test_case(
+        json!({"allOf": [{"required": ["b", "a"]}, {"type": ["string", "object"]}]}),
+        json!({"allOf": [{"type": ["object", "string"]}, {"required": ["a", "b"]}]})
+    )]
+    #[test_case(
+        json!({"anyOf": [{"type": "string", "minLength": 1}, {"type": "integer"}]}),
+        json!({"anyOf": [{"type": "integer"}, {"minLength": 1, "type": "string"}]})
+    )]
+    #[test_case(
+        json!({"allOf": [{"type": "integer"}, {"minimum": 1, "maximum": 5}, {"type": ["integer", "string"], "maximum": 3}]}),
+        json!({"allOf": [{"type": ["integer", "string"], "maximum": 3}, {"minimum": 1, "maximum": 5}, {"type": "integer"}]})
+    )]
+    #[test_case(
+        json!({"allOf": [{"properties": {"a": {"type": "string"}}}, {"required": ["a"]}, {"properties": {"a": {"minLength": 1}}}]}),
+        json!({"allOf": [{"properties": {"a": {"minLength": 1}}}, {"properties": {"a": {"type": "string"}}}, {"required": ["a"]}]})
+    )]
+    #[test_case(
+        json!({"anyOf": [{"type": "string", "minLength": 1}, {"type": "integer", "minimum": 1}, {"type": "string", "maxLength": 3}]}),
+        json!({"anyOf": [{"type": "string", "maxLength": 3}, {"type": "string", "minLength": 1}, {"type": "integer", "minimum": 1}]})
+    )]
+    #[test_case(
+        json!({"allOf": [{"anyOf": [{"type": "string"}, {"type": "integer"}]}, {"anyOf": [{"type": "integer"}, {"type": "string"}]}, {"anyOf": [{"type": "string", "minLength": 1}, {"type": "integer"}]}]}),
+        json!({"allOf": [{"anyOf": [{"type": "string", "minLength": 1}, {"type": "integer"}]}, {"anyOf": [{"type": "integer"}, {"type": "string"}]}, {"anyOf": [{"type": "string"}, {"type": "integer"}]}]})
+    )]
+    #[test_case(
+        json!({"anyOf": [{"const": "a"}, {"allOf": [{"type": "string"}, {"maxLength": 2}]}, {"const": 1}]}),
+        json!({"anyOf": [{"const": 1}, {"allOf": [{"maxLength": 2}, {"type": "string"}]}, {"const": "a"}]})
+    )]
+    fn test_jsonschema_equivalent_canonical(schema: Value, other_schema: Value) {
+        crate::init_logger();
+        assert_eq!(
+            jsonschema_equivalent_canonical(schema).to_string(),
+            jsonschema_equivalent_canonical(other_schema).to_string()
+        );
+    }
 }
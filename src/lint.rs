@@ -0,0 +1,195 @@
+//! Report dead, redundant and contradictory parts of a schema, without rewriting it.
+//!
+//! The diagnostics are produced by applying every optimisation rule, once, to a copy of every
+//! subschema and by describing the modifications that the rule would perform.
+use crate::helpers::{edits::Edits, subschemas::for_each_subschema_with_pointer};
+use crate::keywords::{Rule, UPDATE_SCHEMA_METHODS};
+use crate::options::{Options, Profile};
+use serde_json::Value;
+
+/// Severity of a [`Diagnostic`]
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Severity {
+    /// Part of the schema that does not alter the validation outcome (ie. `minimum` on a string schema)
+    Warning,
+    /// Part of the schema that can never be valid (ie. `maxItems` lower than `minItems`)
+    Error,
+}
+
+/// Issue found in a schema by [`lint`]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Diagnostic {
+    /// Severity of the issue
+    pub severity: Severity,
+    /// JSON Pointer of the schema with the issue
+    pub pointer: String,
+    /// Name of the optimisation rule detecting the issue (see [`Options::rule_names`](crate::Options::rule_names))
+    pub rule: &'static str,
+    /// Human readable description of the issue
+    pub message: String,
+}
+
+/// Human readable representation of the value of `type` (ie. `integer, string`)
+fn describe_type(type_value: Option<&Value>) -> String {
+    match type_value {
+        Some(Value::String(type_name)) => type_name.clone(),
+        Some(Value::Array(type_names)) => type_names
+            .iter()
+            .map(|type_name| {
+                type_name
+                    .as_str()
+                    .map_or_else(|| type_name.to_string(), str::to_string)
+            })
+            .collect::<Vec<_>>()
+            .join(", "),
+        Some(value) => value.to_string(),
+        None => String::new(),
+    }
+}
+
+/// Describe the modifications performed by `rule` on `schema` (resulting in `updated_schema`)
+fn describe_modifications(
+    rule: &Rule,
+    schema: &Value,
+    updated_schema: &Value,
+) -> Vec<(Severity, String)> {
+    match (schema, updated_schema) {
+        (_, Value::Bool(false)) => vec![(
+            Severity::Error,
+            "this schema can never match any value".to_string(),
+        )],
+        (_, Value::Bool(true)) => vec![(
+            Severity::Warning,
+            "this schema matches any value, its keywords have no effect".to_string(),
+        )],
+        // Only the `Safe` rules are limited to remove keywords, the others might move them (ie. `flatten_all_of`)
+        (Value::Object(schema_object), Value::Object(updated_schema_object))
+            if rule.profile == Profile::Safe =>
+        {
+            schema_object
+                .keys()
+                .filter(|keyword| !updated_schema_object.contains_key(*keyword))
+                .map(|keyword| {
+                    let message = if rule.name == "remove_extraneous_keys_keyword_type" {
+                        format!(
+                            "`{keyword}` has no effect because `type` is {}",
+                            describe_type(schema_object.get("type"))
+                        )
+                    } else {
+                        format!("`{keyword}` has no effect")
+                    };
+                    (Severity::Warning, message)
+                })
+                .collect()
+        }
+        _ => Vec::new(),
+    }
+}
+
+/// Lint `schema`, referenced by `pointer`, and all its subschemas
+fn lint_schema(schema: &Value, pointer: &str, diagnostics: &mut Vec<Diagnostic>) {
+    if schema
+        .as_object()
+        .is_some_and(|schema_object| !schema_object.is_empty())
+    {
+        for rule in UPDATE_SCHEMA_METHODS {
+            let mut updated_schema = schema.clone();
+            if !(rule.method)(
+                &mut updated_schema,
+                &Options::default(),
+                &mut Edits::default(),
+            ) || &updated_schema == schema
+            {
+                continue;
+            }
+            for (severity, message) in describe_modifications(rule, schema, &updated_schema) {
+                if !diagnostics.iter().any(|diagnostic| {
+                    diagnostic.pointer == pointer && diagnostic.message == message
+                }) {
+                    diagnostics.push(Diagnostic {
+                        severity,
+                        pointer: pointer.to_string(),
+                        rule: rule.name,
+                        message,
+                    });
+                }
+            }
+        }
+    }
+    for_each_subschema_with_pointer(
+        schema,
+        pointer,
+        &|_| false,
+        &mut |subschema_pointer, subschema| {
+            lint_schema(subschema, &subschema_pointer, diagnostics);
+        },
+    );
+}
+
+/// Report the dead, redundant and contradictory parts of `schema` (including the ones in `definitions`),
+/// without rewriting it.
+/// ```rust
+/// use jsonschema_equivalent::{lint, Diagnostic, Severity};
+/// use serde_json::json;
+///
+/// assert_eq!(
+///     lint(&json!({"anyOf": [{"type": "string", "minimum": 1}, {"type": "array", "minItems": 2, "maxItems": 1}]})),
+///     vec![
+///         Diagnostic {
+///             severity: Severity::Warning,
+///             pointer: "/anyOf/0".to_string(),
+///             rule: "remove_extraneous_keys_keyword_type",
+///             message: "`minimum` has no effect because `type` is string".to_string(),
+///         },
+///         Diagnostic {
+///             severity: Severity::Error,
+///             pointer: "/anyOf/1".to_string(),
+///             rule: "update_max_min_related_keywords",
+///             message: "this schema can never match any value".to_string(),
+///         },
+///     ],
+/// );
+/// ```
+#[must_use]
+pub fn lint(schema: &Value) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    lint_schema(schema, "", &mut diagnostics);
+    diagnostics
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{lint, Severity};
+    use serde_json::{json, Value};
+    use test_case::test_case;
+
+    #[test_case(&json!(true) => Vec::<(Severity, String, &str, String)>::new())]
+    #[test_case(&json!({"type": "string", "minLength": 1}) => Vec::<(Severity, String, &str, String)>::new())]
+    #[test_case(
+        &json!({"type": ["integer", "string"], "minItems": 1})
+        => vec![(Severity::Warning, String::new(), "remove_extraneous_keys_keyword_type", "`minItems` has no effect because `type` is integer, string".to_string())]
+    )]
+    #[test_case(
+        &json!({"properties": {"a": {"type": "string", "enum": [1]}}})
+        => vec![(Severity::Error, "/properties/a".to_string(), "simple_enum_cleanup", "this schema can never match any value".to_string())]
+    )]
+    #[test_case(
+        &json!({"definitions": {"a": {"type": "object", "required": []}}})
+        => vec![(Severity::Warning, "/definitions/a".to_string(), "omit_keywords_that_do_not_alter_schema_selectivity", "`required` has no effect".to_string())]
+    )]
+    #[test_case(&json!({"allOf": [{"type": "string"}]}) => Vec::<(Severity, String, &str, String)>::new() ; "rewrites are not reported")]
+    fn test_lint(schema: &Value) -> Vec<(Severity, String, &'static str, String)> {
+        crate::init_logger();
+        lint(schema)
+            .into_iter()
+            .map(|diagnostic| {
+                (
+                    diagnostic.severity,
+                    diagnostic.pointer,
+                    diagnostic.rule,
+                    diagnostic.message,
+                )
+            })
+            .collect()
+    }
+}
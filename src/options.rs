@@ -0,0 +1,481 @@
+//! Configuration of the optimisation process.
+use crate::constants::{ANNOTATION_KEYWORDS, CORE_KEYWORDS, KEYWORDS};
+use crate::keywords::UPDATE_SCHEMA_METHODS;
+use crate::primitive_type::PrimitiveType;
+use std::collections::{BTreeMap, BTreeSet};
+use std::convert::TryFrom;
+
+/// Preset of optimisation rules.
+///
+/// Profiles are ordered by aggressiveness and every profile includes the rules of the less aggressive ones.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Profile {
+    /// Rules that only remove keywords (or replace schemas with `false` schemas), without rewriting the schema.
+    /// ie. `{"type": "string", "minimum": 1}` is optimised as `{"type": "string"}`
+    Safe,
+    /// All the rules that are generally beneficial, including rewrites (used by [`jsonschema_equivalent`](crate::jsonschema_equivalent)).
+    /// ie. `{"type": ["integer", "string"], "const": "a"}` is optimised as `{"type": "string", "const": "a"}`
+    #[default]
+    Default,
+    /// All the rules, including the opt-in rewrites that might significantly alter the shape of the schema.
+    /// ie. `{"additionalItems": false, "items": [{}]}` is optimised as `{"items": [true], "maxItems": 1}`,
+    /// `allOf` and `anyOf` subschemas are ordered by their estimated validation cost, `anyOf` and `oneOf`
+    /// discriminated by a tag property are specialised into an `if`/`then`/`else` chain
+    Aggressive,
+}
+
+/// Handling of the annotation keywords (`$comment`, `default`, `description`, `examples`, `readOnly`, `title` and `writeOnly`).
+///
+/// Annotations do not alter the validation outcome, so the optimisation process might lose them
+/// (ie. when a schema is replaced by a `false` schema) or keep the ones of a single `allOf` item.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum AnnotationPolicy {
+    /// Annotations are treated as any other unknown keyword
+    #[default]
+    Keep,
+    /// Annotations are removed from the schema and all its subschemas (ie. for runtime validation)
+    Strip,
+    /// Annotations are preserved (ie. for documentation builds).
+    /// The annotations of the `allOf` items are merged into the schema, with the annotations of the
+    /// schema taking precedence and `examples` being concatenated.
+    /// Boolean schemas with annotations are represented as `{...}` (`true`) and `{"not": true, ...}` (`false`).
+    Preserve,
+}
+
+/// Handling of the unknown keywords, keywords neither defined by the specifications nor registered
+/// via [`Options::register_keyword`] (ie. vendor extensions like `x-*` or typos like `minimun`).
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum UnknownKeywordPolicy {
+    /// Unknown keywords are preserved, preventing the schema from being recognised as a `true` schema
+    #[default]
+    Keep,
+    /// Unknown keywords are removed from the schema and all its subschemas
+    Strip,
+    /// Unknown keywords are reported as [`Error::UnknownKeyword`](crate::Error::UnknownKeyword) by the fallible APIs
+    /// (ie. [`try_jsonschema_equivalent_with_options`](crate::try_jsonschema_equivalent_with_options))
+    Error,
+}
+
+/// Description of a keyword not defined by the specifications, registered via [`Options::register_keyword`].
+/// ```rust
+/// use jsonschema_equivalent::{jsonschema_equivalent_with_options, CustomKeyword, Options};
+/// use serde_json::json;
+///
+/// let options = Options::new().register_keyword("x-maxDigits", CustomKeyword::assertion().applying_to(&["integer"]));
+/// assert_eq!(
+///     jsonschema_equivalent_with_options(json!({"type": "string", "x-maxDigits": 3}), &options),
+///     json!({"type": "string"}),
+/// );
+/// ```
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct CustomKeyword {
+    primitive_types: BTreeSet<PrimitiveType>,
+    subschema: bool,
+    assertion: bool,
+}
+
+impl CustomKeyword {
+    /// Keyword that does not alter the validation outcome, handled as the annotations (see [`AnnotationPolicy`]).
+    /// Unless the annotations are preserved, schemas holding only registered annotations are recognised as `true` schemas.
+    #[must_use]
+    pub fn annotation() -> Self {
+        Self::default()
+    }
+
+    /// Keyword that alters the validation outcome of the instances of all the primitive types
+    #[must_use]
+    pub fn assertion() -> Self {
+        Self {
+            assertion: true,
+            ..Self::default()
+        }
+    }
+
+    /// Restrict the assertion to the instances of the primitive types named in `primitive_types` (ie. `["string"]`).
+    /// The keyword is removed from the schemas not allowing any of those primitive types.
+    #[must_use]
+    pub fn applying_to(mut self, primitive_types: &[&str]) -> Self {
+        for primitive_type in primitive_types {
+            match PrimitiveType::try_from(*primitive_type) {
+                Ok(primitive_type) => {
+                    let _ = self.primitive_types.insert(primitive_type);
+                }
+                Err(error) => log::warn!("Ignored primitive type of custom keyword: {error}"),
+            }
+        }
+        self
+    }
+
+    /// The value of the keyword is a schema, so it is optimised as any other subschema
+    #[must_use]
+    pub fn with_subschema(mut self) -> Self {
+        self.subschema = true;
+        self
+    }
+
+    /// Primitive types the assertion applies to (all the primitive types if empty)
+    pub(crate) fn primitive_types(&self) -> &BTreeSet<PrimitiveType> {
+        &self.primitive_types
+    }
+
+    /// Checks if the keyword alters the validation outcome
+    pub(crate) fn is_assertion(&self) -> bool {
+        self.assertion
+    }
+}
+
+/// Options of the optimisation process.
+///
+/// The rules to apply are defined by a [`Profile`], eventually enabling or disabling
+/// rules by name (the list of the names is available via [`Options::rule_names`]).
+/// ```rust
+/// use jsonschema_equivalent::{jsonschema_equivalent_with_options, Options, Profile};
+/// use serde_json::json;
+///
+/// let options = Options::new().with_profile(Profile::Safe);
+/// assert_eq!(
+///     jsonschema_equivalent_with_options(json!({"type": "string", "minimum": 1, "const": "a"}), &options),
+///     json!({"type": "string", "const": "a"}),
+/// );
+///
+/// let options = Options::new().disable_rule("simple_const_cleanup");
+/// assert_eq!(
+///     jsonschema_equivalent_with_options(json!({"type": ["integer", "string"], "const": "a"}), &options),
+///     json!({"type": ["integer", "string"], "const": "a"}),
+/// );
+/// ```
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Options {
+    profile: Profile,
+    enabled_rules: BTreeSet<String>,
+    disabled_rules: BTreeSet<String>,
+    annotation_policy: AnnotationPolicy,
+    unknown_keyword_policy: UnknownKeywordPolicy,
+    custom_keywords: BTreeMap<String, CustomKeyword>,
+    strict_mode: bool,
+}
+
+impl Options {
+    /// Options applying the rules of the [`Profile::Default`] profile
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Names of all the optimisation rules, in the order they are applied
+    #[must_use]
+    pub fn rule_names() -> Vec<&'static str> {
+        UPDATE_SCHEMA_METHODS.iter().map(|rule| rule.name).collect()
+    }
+
+    /// Apply the rules of `profile`
+    #[must_use]
+    pub fn with_profile(mut self, profile: Profile) -> Self {
+        self.profile = profile;
+        self
+    }
+
+    /// Apply the rule named `rule_name`, regardless of the profile
+    #[must_use]
+    pub fn enable_rule(mut self, rule_name: &str) -> Self {
+        warn_if_unknown_rule(rule_name);
+        let _ = self.disabled_rules.remove(rule_name);
+        let _ = self.enabled_rules.insert(rule_name.to_string());
+        self
+    }
+
+    /// Do not apply the rule named `rule_name`, regardless of the profile
+    #[must_use]
+    pub fn disable_rule(mut self, rule_name: &str) -> Self {
+        warn_if_unknown_rule(rule_name);
+        let _ = self.enabled_rules.remove(rule_name);
+        let _ = self.disabled_rules.insert(rule_name.to_string());
+        self
+    }
+
+    /// Handle the annotations according to `annotation_policy`
+    #[must_use]
+    pub fn with_annotation_policy(mut self, annotation_policy: AnnotationPolicy) -> Self {
+        self.annotation_policy = annotation_policy;
+        self
+    }
+
+    /// Policy used to handle the annotations
+    #[must_use]
+    pub fn annotation_policy(&self) -> AnnotationPolicy {
+        self.annotation_policy
+    }
+
+    /// Handle the unknown keywords according to `unknown_keyword_policy`
+    #[must_use]
+    pub fn with_unknown_keyword_policy(
+        mut self,
+        unknown_keyword_policy: UnknownKeywordPolicy,
+    ) -> Self {
+        self.unknown_keyword_policy = unknown_keyword_policy;
+        self
+    }
+
+    /// Policy used to handle the unknown keywords
+    #[must_use]
+    pub fn unknown_keyword_policy(&self) -> UnknownKeywordPolicy {
+        self.unknown_keyword_policy
+    }
+
+    /// Check, before any rewriting, that the schema satisfies the rules of the meta-schemas
+    /// (ie. `{"type": "strnig"}` is reported as [`Error::UnknownPrimitiveType`](crate::Error::UnknownPrimitiveType)
+    /// instead of being silently optimised)
+    #[must_use]
+    pub fn with_strict_mode(mut self, strict_mode: bool) -> Self {
+        self.strict_mode = strict_mode;
+        self
+    }
+
+    /// Checks if the schema has to satisfy the rules of the meta-schemas
+    #[must_use]
+    pub fn strict_mode(&self) -> bool {
+        self.strict_mode
+    }
+
+    /// Register `keyword`, not defined by the specifications, as described by `custom_keyword`.
+    /// Keywords defined by the specifications cannot be registered.
+    #[must_use]
+    pub fn register_keyword(mut self, keyword: &str, custom_keyword: CustomKeyword) -> Self {
+        if KEYWORDS.contains(keyword)
+            || ANNOTATION_KEYWORDS.contains(keyword)
+            || CORE_KEYWORDS.contains(keyword)
+        {
+            log::warn!("Keyword defined by the specifications cannot be registered: {keyword}");
+        } else {
+            let _ = self
+                .custom_keywords
+                .insert(keyword.to_string(), custom_keyword);
+        }
+        self
+    }
+
+    /// Registered keywords
+    pub(crate) fn custom_keywords(&self) -> impl Iterator<Item = (&str, &CustomKeyword)> {
+        self.custom_keywords
+            .iter()
+            .map(|(keyword, custom_keyword)| (keyword.as_str(), custom_keyword))
+    }
+
+    /// Checks if `keyword` is an annotation (defined by the specifications or registered)
+    pub(crate) fn is_annotation_keyword(&self, keyword: &str) -> bool {
+        ANNOTATION_KEYWORDS.contains(keyword)
+            || self
+                .custom_keywords
+                .get(keyword)
+                .is_some_and(|custom_keyword| !custom_keyword.is_assertion())
+    }
+
+    /// Checks if `keyword` is registered and its value is a schema
+    pub(crate) fn is_custom_subschema_keyword(&self, keyword: &str) -> bool {
+        self.custom_keywords
+            .get(keyword)
+            .is_some_and(|custom_keyword| custom_keyword.subschema)
+    }
+
+    /// Checks if `keyword` is neither defined by the specifications nor registered
+    pub(crate) fn is_unknown_keyword(&self, keyword: &str) -> bool {
+        !KEYWORDS.contains(keyword)
+            && !ANNOTATION_KEYWORDS.contains(keyword)
+            && !CORE_KEYWORDS.contains(keyword)
+            && !self.custom_keywords.contains_key(keyword)
+    }
+
+    /// Profile whose rules are applied
+    #[must_use]
+    pub fn profile(&self) -> Profile {
+        self.profile
+    }
+
+    /// Checks if the rule named `rule_name` has to be applied
+    #[must_use]
+    pub fn is_rule_enabled(&self, rule_name: &str) -> bool {
+        if self.disabled_rules.contains(rule_name) {
+            false
+        } else if self.enabled_rules.contains(rule_name) {
+            true
+        } else {
+            UPDATE_SCHEMA_METHODS
+                .iter()
+                .any(|rule| rule.name == rule_name && rule.profile <= self.profile)
+        }
+    }
+}
+
+/// Log a warning if there is no rule named `rule_name`, most likely a typo
+fn warn_if_unknown_rule(rule_name: &str) {
+    if !UPDATE_SCHEMA_METHODS
+        .iter()
+        .any(|rule| rule.name == rule_name)
+    {
+        log::warn!("Unknown optimisation rule: {rule_name}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AnnotationPolicy, CustomKeyword, Options, Profile, UnknownKeywordPolicy};
+    use crate::{
+        helpers::edits::Edits, jsonschema_equivalent_with_options, keywords::UPDATE_SCHEMA_METHODS,
+    };
+    use serde_json::{json, Value};
+    use test_case::test_case;
+
+    #[test_case(&Options::new(), "simple_const_cleanup" => true)]
+    #[test_case(&Options::new(), "unknown_rule" => false)]
+    #[test_case(&Options::new().with_profile(Profile::Safe), "simple_const_cleanup" => false)]
+    #[test_case(&Options::new().with_profile(Profile::Safe), "remove_extraneous_keys_keyword_type" => true)]
+    #[test_case(&Options::new(), "simplify_additional_items" => true)]
+    #[test_case(&Options::new().with_profile(Profile::Safe), "remove_custom_keywords_of_other_types" => true)]
+    #[test_case(&Options::new().with_profile(Profile::Safe), "simplify_items" => false)]
+    #[test_case(&Options::new().with_profile(Profile::Safe), "simplify_additional_properties" => false)]
+    #[test_case(&Options::new().with_profile(Profile::Safe), "update_max_min_related_keywords" => false)]
+    #[test_case(&Options::new(), "replace_false_additional_items_with_max_items" => false)]
+    #[test_case(&Options::new().with_profile(Profile::Aggressive), "replace_false_additional_items_with_max_items" => true)]
+    #[test_case(&Options::new(), "order_any_of_by_cost" => false)]
+    #[test_case(&Options::new().with_profile(Profile::Aggressive), "order_any_of_by_cost" => true)]
+    #[test_case(&Options::new(), "replace_any_of_values_with_enum" => true)]
+    #[test_case(&Options::new().with_profile(Profile::Safe), "replace_any_of_values_with_enum" => false)]
+    #[test_case(&Options::new().disable_rule("simple_const_cleanup"), "simple_const_cleanup" => false)]
+    #[test_case(&Options::new().with_profile(Profile::Safe).enable_rule("simple_const_cleanup"), "simple_const_cleanup" => true)]
+    #[test_case(&Options::new().enable_rule("simple_const_cleanup").disable_rule("simple_const_cleanup"), "simple_const_cleanup" => false)]
+    fn test_is_rule_enabled(options: &Options, rule_name: &str) -> bool {
+        options.is_rule_enabled(rule_name)
+    }
+
+    // The rules of the `Safe` profile only remove keywords or replace the schema with a `false` schema
+    #[test_case(&json!({"type": "string", "minLength": 2, "maxLength": 1}))]
+    #[test_case(&json!({"minLength": 2, "maxLength": 1}))]
+    #[test_case(&json!({"type": ["integer", "null"], "minimum": 2, "maximum": 1}))]
+    #[test_case(&json!({"type": "string", "minimum": 1, "items": {}, "required": []}))]
+    #[test_case(&json!({"type": "object", "required": [], "properties": {"a": {}}}))]
+    #[test_case(&json!({"type": ["array", "object"], "additionalItems": false, "items": [{}]}))]
+    #[test_case(&json!({"if": {"type": "string"}, "minItems": 1}))]
+    #[test_case(&json!({"enum": [1], "const": 1, "type": "integer"}))]
+    #[test_case(&json!({"allOf": [{"type": "string"}, {"minLength": 1}], "anyOf": [{"type": "string"}, {"type": "string", "minLength": 1}]}))]
+    fn test_safe_rules_do_not_add_keywords(schema: &Value) {
+        crate::init_logger();
+        let options = Options::new().with_profile(Profile::Safe);
+        for rule in UPDATE_SCHEMA_METHODS
+            .iter()
+            .filter(|rule| rule.profile == Profile::Safe)
+        {
+            let mut optimised_schema = schema.clone();
+            let _ = (rule.method)(&mut optimised_schema, &options, &mut Edits::default());
+            match (&optimised_schema, schema) {
+                (Value::Bool(false), _) => {}
+                (Value::Object(optimised_schema_object), Value::Object(schema_object)) => assert!(
+                    optimised_schema_object
+                        .iter()
+                        .all(|(keyword, value)| schema_object.get(keyword) == Some(value)),
+                    "{} rewrote {schema} as {optimised_schema}",
+                    rule.name,
+                ),
+                _ => assert_eq!(&optimised_schema, schema, "{} rewrote {schema}", rule.name),
+            }
+        }
+    }
+
+    #[test_case(&Options::new(), "x-foo" => true)]
+    #[test_case(&Options::new(), "minimun" => true)]
+    #[test_case(&Options::new(), "$ref" => false)]
+    #[test_case(&Options::new(), "title" => false)]
+    #[test_case(&Options::new(), "minimum" => false)]
+    #[test_case(&Options::new().register_keyword("x-foo", CustomKeyword::annotation()), "x-foo" => false)]
+    #[test_case(&Options::new().register_keyword("minimum", CustomKeyword::annotation()), "minimum" => false)]
+    fn test_is_unknown_keyword(options: &Options, keyword: &str) -> bool {
+        options.is_unknown_keyword(keyword)
+    }
+
+    #[test]
+    fn test_register_keyword_ignores_keywords_of_the_specifications() {
+        assert_eq!(
+            Options::new().register_keyword("minimum", CustomKeyword::annotation()),
+            Options::new()
+        );
+    }
+
+    #[test_case(json!({"additionalItems": false, "items": [{}]}), &Options::new() => json!({"additionalItems": false, "items": [true]}))]
+    #[test_case(json!({"additionalItems": false, "items": [{}]}), &Options::new().with_profile(Profile::Aggressive) => json!({"items": [true], "maxItems": 1}))]
+    #[test_case(json!({"additionalItems": false, "items": [{}]}), &Options::new().with_profile(Profile::Safe) => json!({"additionalItems": false, "items": [true]}))]
+    #[test_case(json!({"allOf": [{"type": "string"}, {"minLength": 1}]}), &Options::new().disable_rule("flatten_all_of") => json!({"allOf": [{"type": "string"}, {"minLength": 1, "type": "string"}], "type": "string"}))]
+    #[test_case(json!({"title": "a", "type": "string", "minimum": 1}), &Options::new() => json!({"title": "a", "type": "string"}))]
+    #[test_case(json!({"title": "a", "type": "string", "minimum": 1}), &Options::new().with_annotation_policy(AnnotationPolicy::Strip) => json!({"type": "string"}))]
+    #[test_case(json!({"title": "a", "properties": {"b": {"description": "b"}}}), &Options::new().with_annotation_policy(AnnotationPolicy::Strip) => json!({"properties": {"b": true}}))]
+    #[test_case(json!({"title": "a", "type": "string", "minimum": 1}), &Options::new().with_annotation_policy(AnnotationPolicy::Preserve) => json!({"title": "a", "type": "string"}))]
+    #[test_case(json!({"title": "a", "type": "string", "enum": [1]}), &Options::new() => json!(false))]
+    #[test_case(json!({"title": "a", "type": "string", "enum": [1]}), &Options::new().with_annotation_policy(AnnotationPolicy::Preserve) => json!({"not": true, "title": "a"}))]
+    #[test_case(json!({"title": "a", "properties": {"b": {"description": "b"}}}), &Options::new().with_annotation_policy(AnnotationPolicy::Preserve) => json!({"title": "a", "properties": {"b": {"description": "b"}}}))]
+    #[test_case(json!({"title": "a", "allOf": [{"title": "b"}, {"type": "string"}]}), &Options::new() => json!({"title": "a", "allOf": [{"title": "b"}], "type": "string"}))]
+    #[test_case(
+        json!({"title": "a", "examples": ["a"], "allOf": [{"title": "b", "description": "b", "examples": ["b"]}, {"type": "string"}]}),
+        &Options::new().with_annotation_policy(AnnotationPolicy::Preserve)
+        => json!({"title": "a", "description": "b", "examples": ["a", "b"], "type": "string"})
+    )]
+    #[test_case(json!({"type": "strnig"}), &Options::new() => json!({"type": "strnig"}))]
+    #[test_case(json!({"properties": {"a": {"type": "objcet", "minLength": 1}}, "minimum": 1, "type": "object"}), &Options::new() => json!({"properties": {"a": {"type": "objcet", "minLength": 1}}, "minimum": 1, "type": "object"}))]
+    #[test_case(
+        json!({"anyOf": [{"type": "string", "pattern": "^a"}, {"type": "integer"}], "not": {"const": 1}}),
+        &Options::new()
+        => json!({"anyOf": [{"type": "string", "pattern": "^a"}, {"type": "integer"}], "not": {"const": 1, "type": "number"}})
+    )]
+    #[test_case(
+        json!({"anyOf": [{"type": "string", "pattern": "^a"}, {"type": "integer"}], "not": {"const": 1}}),
+        &Options::new().with_profile(Profile::Aggressive)
+        => json!({"anyOf": [{"type": "integer"}, {"type": "string", "pattern": "^a"}], "not": {"const": 1, "type": "number"}})
+    )]
+    #[test_case(json!({"anyOf": [{"const": "a"}, {"const": "b"}]}), &Options::new() => json!({"enum": ["a", "b"], "type": "string"}))]
+    #[test_case(json!({"anyOf": [{"const": "a"}, {"const": 1}]}), &Options::new().with_profile(Profile::Safe) => json!({"anyOf": [{"const": "a"}, {"const": 1}]}))]
+    #[test_case(
+        json!({"type": "object", "oneOf": [{"properties": {"kind": {"const": "a"}}, "required": ["kind", "a"]}, {"properties": {"kind": {"const": "b"}}, "required": ["kind"]}]}),
+        &Options::new().with_profile(Profile::Aggressive)
+        => json!({
+            "type": "object",
+            "required": ["kind"],
+            "if": {"properties": {"kind": {"const": "a", "type": "string"}}},
+            "then": {"properties": {"kind": {"const": "a", "type": "string"}}, "required": ["a"]},
+            "else": {
+                "if": {"properties": {"kind": {"const": "b", "type": "string"}}},
+                "then": {"properties": {"kind": {"const": "b", "type": "string"}}},
+                "else": false,
+            },
+        })
+    )]
+    #[test_case(json!({"type": "strnig"}), &Options::new().with_strict_mode(true) => json!({"type": "strnig"}))]
+    #[test_case(json!({"x-foo": 1, "type": "string", "minimum": 1}), &Options::new() => json!({"x-foo": 1, "type": "string"}))]
+    #[test_case(json!({"x-foo": 1, "properties": {"a": {"x-foo": 1}}}), &Options::new().with_unknown_keyword_policy(UnknownKeywordPolicy::Strip) => json!({"properties": {"a": true}}))]
+    #[test_case(json!({"x-foo": 1}), &Options::new().with_unknown_keyword_policy(UnknownKeywordPolicy::Error) => json!({"x-foo": 1}))]
+    #[test_case(
+        json!({"x-schema": {"type": "string", "minimum": 1}, "x-foo": {"type": "string", "minimum": 1}}),
+        &Options::new().register_keyword("x-schema", CustomKeyword::assertion().with_subschema())
+        => json!({"x-schema": {"type": "string"}, "x-foo": {"type": "string", "minimum": 1}})
+    )]
+    #[test_case(
+        json!({"x-doc": 1, "x-foo": 1}),
+        &Options::new()
+            .register_keyword("x-doc", CustomKeyword::annotation())
+            .with_unknown_keyword_policy(UnknownKeywordPolicy::Strip)
+            .with_annotation_policy(AnnotationPolicy::Strip)
+        => json!(true)
+    )]
+    #[test_case(json!({"x-doc": "a"}), &Options::new().register_keyword("x-doc", CustomKeyword::annotation()) => json!(true))]
+    #[test_case(
+        json!({"type": "object", "additionalProperties": {"x-doc": "a"}}),
+        &Options::new().register_keyword("x-doc", CustomKeyword::annotation())
+        => json!({"type": "object"})
+    )]
+    #[test_case(
+        json!({"x-doc": "a"}),
+        &Options::new().register_keyword("x-doc", CustomKeyword::annotation()).with_annotation_policy(AnnotationPolicy::Preserve)
+        => json!({"x-doc": "a"})
+    )]
+    #[test_case(json!({"x-doc": "a"}), &Options::new() => json!({"x-doc": "a"}) ; "unregistered annotation")]
+    fn test_jsonschema_equivalent_with_options(schema: Value, options: &Options) -> Value {
+        crate::init_logger();
+        jsonschema_equivalent_with_options(schema, options)
+    }
+}
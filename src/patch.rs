@@ -0,0 +1,424 @@
+//! Representation of the optimisation as a [RFC 6902](https://tools.ietf.org/html/rfc6902) JSON Patch.
+//!
+//! The operations are recorded by the helpers used by the rules to edit the schema (see [`Edits`]), so
+//! every operation covers only the modified location. The operations are composed while being recorded
+//! (ie. a keyword added and later removed does not produce any operation).
+use crate::helpers::{edits::Edits, pointer, subschemas::Location};
+use crate::keywords::Observer;
+use crate::Options;
+use serde_json::{json, Value};
+
+/// Operation of a JSON Patch
+#[derive(Clone, Debug, PartialEq)]
+pub enum PatchOperation {
+    /// Add `value` at `path`
+    Add {
+        /// JSON Pointer of the location to add
+        path: String,
+        /// Added value
+        value: Value,
+    },
+    /// Remove the value at `path`
+    Remove {
+        /// JSON Pointer of the location to remove
+        path: String,
+    },
+    /// Replace the value at `path` with `value`
+    Replace {
+        /// JSON Pointer of the location to replace
+        path: String,
+        /// New value
+        value: Value,
+    },
+}
+
+impl PatchOperation {
+    /// JSON Pointer of the location modified by the operation
+    #[must_use]
+    pub fn path(&self) -> &str {
+        match self {
+            Self::Add { path, .. } | Self::Remove { path } | Self::Replace { path, .. } => path,
+        }
+    }
+
+    /// JSON representation of the operation (ie. `{"op": "remove", "path": "/minimum"}`)
+    #[must_use]
+    pub fn to_value(&self) -> Value {
+        match self {
+            Self::Add { path, value } => json!({"op": "add", "path": path, "value": value}),
+            Self::Remove { path } => json!({"op": "remove", "path": path}),
+            Self::Replace { path, value } => {
+                json!({"op": "replace", "path": path, "value": value})
+            }
+        }
+    }
+
+    /// Apply the operation to `document`.
+    /// Return false if the operation cannot be applied (ie. the parent of `path` does not exist).
+    pub(crate) fn apply(&self, document: &mut Value) -> bool {
+        let path = self.path();
+        if path.is_empty() {
+            return match self {
+                Self::Add { value, .. } | Self::Replace { value, .. } => {
+                    *document = value.clone();
+                    true
+                }
+                Self::Remove { .. } => false,
+            };
+        }
+        let parent_path = &path[..path.rfind('/').unwrap_or(0)];
+        let Some(token) = pointer::tokens(path).last() else {
+            return false;
+        };
+        match (document.pointer_mut(parent_path), self) {
+            (Some(Value::Object(parent)), Self::Add { value, .. }) => {
+                let _ = parent.insert(token, value.clone());
+                true
+            }
+            (Some(Value::Object(parent)), Self::Replace { value, .. }) => parent
+                .get_mut(&token)
+                .map(|current_value| *current_value = value.clone())
+                .is_some(),
+            (Some(Value::Object(parent)), Self::Remove { .. }) => parent.remove(&token).is_some(),
+            (Some(Value::Array(parent)), Self::Add { value, .. }) => {
+                let index = if token == "-" {
+                    Some(parent.len())
+                } else {
+                    token.parse().ok()
+                };
+                match index {
+                    Some(index) if index <= parent.len() => {
+                        parent.insert(index, value.clone());
+                        true
+                    }
+                    _ => false,
+                }
+            }
+            (Some(Value::Array(parent)), Self::Replace { value, .. }) => token
+                .parse::<usize>()
+                .ok()
+                .and_then(|index| parent.get_mut(index))
+                .map(|current_value| *current_value = value.clone())
+                .is_some(),
+            (Some(Value::Array(parent)), Self::Remove { .. }) => match token.parse::<usize>() {
+                Ok(index) if index < parent.len() => {
+                    let _ = parent.remove(index);
+                    true
+                }
+                _ => false,
+            },
+            _ => false,
+        }
+    }
+
+    /// Operation relative to the document of the value referenced by `pointer` (`self` being relative to the value)
+    pub(crate) fn within(self, pointer: &str) -> Self {
+        match self {
+            Self::Add { path, value } => Self::Add {
+                path: format!("{pointer}{path}"),
+                value,
+            },
+            Self::Remove { path } => Self::Remove {
+                path: format!("{pointer}{path}"),
+            },
+            Self::Replace { path, value } => Self::Replace {
+                path: format!("{pointer}{path}"),
+                value,
+            },
+        }
+    }
+
+    /// Operation relative to `pointer` (which is an ancestor of the path of the operation)
+    fn relative_to(&self, pointer: &str) -> Self {
+        let relative_path = |path: &str| path[pointer.len()..].to_string();
+        match self {
+            Self::Add { path, value } => Self::Add {
+                path: relative_path(path),
+                value: value.clone(),
+            },
+            Self::Remove { path } => Self::Remove {
+                path: relative_path(path),
+            },
+            Self::Replace { path, value } => Self::Replace {
+                path: relative_path(path),
+                value: value.clone(),
+            },
+        }
+    }
+}
+
+/// Recorder of the edits performed on a schema, referenced by `pointer`, and its subschemas
+#[derive(Debug, Default)]
+pub(crate) struct Recorder {
+    pointer: String,
+    operations: Vec<PatchOperation>,
+}
+
+impl Observer for Recorder {
+    const OBSERVES_EDITS: bool = true;
+
+    fn child(&self, location: Location<'_>) -> Self {
+        Self {
+            pointer: location.pointer(&self.pointer),
+            operations: Vec::new(),
+        }
+    }
+
+    fn merge(&mut self, child: Self) {
+        for operation in child.operations {
+            self.push(operation);
+        }
+    }
+
+    fn edited(&mut self, operations: Vec<PatchOperation>) {
+        for operation in operations {
+            let operation = operation.within(&self.pointer);
+            self.push(operation);
+        }
+    }
+}
+
+/// Checks if `operation` might shift the items of an array (ie. the addition or removal of an item).
+/// As the paths do not tell arrays and objects apart, object keys looking like indexes are considered as well.
+fn shifts_items(operation: &PatchOperation) -> bool {
+    match operation {
+        PatchOperation::Add { path, .. } | PatchOperation::Remove { path } => path
+            .rsplit('/')
+            .next()
+            .is_some_and(|token| token == "-" || token.parse::<usize>().is_ok()),
+        PatchOperation::Replace { .. } => false,
+    }
+}
+
+/// JSON Pointer of the parent of the location modified by `operation`
+fn parent_path(operation: &PatchOperation) -> &str {
+    let path = operation.path();
+    &path[..path.rfind('/').unwrap_or(0)]
+}
+
+impl Recorder {
+    /// Recorded operations, in the order they have to be applied
+    #[cfg(test)]
+    pub(crate) fn into_operations(self) -> Vec<PatchOperation> {
+        self.operations
+    }
+
+    /// Record `operation`, composing it with the previously recorded operations
+    fn push(&mut self, operation: PatchOperation) {
+        let path = operation.path().to_string();
+
+        // The operations recorded before a shift of the items of an array containing `path` refer to a different
+        // location than `path`, so they cannot be composed with `operation`
+        let composable_from = self
+            .operations
+            .iter()
+            .rposition(|recorded_operation| {
+                shifts_items(recorded_operation)
+                    && pointer::is_ancestor(parent_path(recorded_operation), &path)
+            })
+            .map_or(0, |index| index.saturating_add(1));
+
+        // Operations within a previously added or replaced value are applied to the value itself
+        if let Some(
+            PatchOperation::Add {
+                path: ancestor_path,
+                value,
+            }
+            | PatchOperation::Replace {
+                path: ancestor_path,
+                value,
+            },
+        ) = self.operations[composable_from..]
+            .iter_mut()
+            .rev()
+            .find(|recorded_operation| pointer::is_ancestor(recorded_operation.path(), &path))
+        {
+            if operation.relative_to(ancestor_path).apply(value) {
+                return;
+            }
+        }
+
+        // Adding, or removing, an array item does not override the operations recorded on the same location
+        if shifts_items(&operation) {
+            self.operations.push(operation);
+            return;
+        }
+
+        let mut was_added = false;
+        let mut was_removed = false;
+        let mut index = 0;
+        self.operations.retain(|recorded_operation| {
+            let is_composable = index >= composable_from;
+            index = index.saturating_add(1);
+            let recorded_path = recorded_operation.path();
+            if !is_composable {
+                true
+            } else if recorded_path == path {
+                was_added = matches!(recorded_operation, PatchOperation::Add { .. });
+                was_removed = matches!(recorded_operation, PatchOperation::Remove { .. });
+                false
+            } else {
+                !pointer::is_ancestor(&path, recorded_path)
+            }
+        });
+        let operation = match operation {
+            PatchOperation::Remove { .. } if was_added => return,
+            PatchOperation::Replace { path, value } if was_added => {
+                PatchOperation::Add { path, value }
+            }
+            PatchOperation::Add { path, value } if was_removed => {
+                PatchOperation::Replace { path, value }
+            }
+            operation => operation,
+        };
+        self.operations.push(operation);
+    }
+}
+
+/// Generate an equivalent schema to the schema provided as input, applying only the rules enabled by `options`,
+/// together with the JSON Patch transforming the input schema into the generated schema.
+///
+/// The operations are recorded by the rules while editing the schema, so the patch modifies only the edited locations.
+/// If the schema cannot be optimised (see [`try_jsonschema_equivalent_with_options_ref`](crate::try_jsonschema_equivalent_with_options_ref))
+/// it is not modified and the patch is empty.
+/// ```rust
+/// use jsonschema_equivalent::{jsonschema_equivalent_with_patch, Options, PatchOperation};
+/// use serde_json::json;
+///
+/// let (schema, patch) = jsonschema_equivalent_with_patch(
+///     json!({"type": "string", "minimum": 1, "allOf": [{"minLength": 1}]}),
+///     &Options::default(),
+/// );
+/// assert_eq!(schema, json!({"type": "string", "minLength": 1}));
+/// assert_eq!(
+///     patch,
+///     vec![
+///         PatchOperation::Remove { path: "/minimum".to_string() },
+///         PatchOperation::Add { path: "/minLength".to_string(), value: json!(1) },
+///         PatchOperation::Remove { path: "/allOf".to_string() },
+///     ],
+/// );
+/// ```
+#[must_use]
+pub fn jsonschema_equivalent_with_patch(
+    mut schema: Value,
+    options: &Options,
+) -> (Value, Vec<PatchOperation>) {
+    let mut recorder = Recorder::default();
+    let prepared_schema = schema.clone();
+    let mut edits = Edits::recording();
+    if let Err(error) = crate::prepare_schema(&mut schema, options, &mut edits) {
+        log::warn!("Schema not optimised: {error}");
+        return (prepared_schema, Vec::new());
+    }
+    recorder.edited(edits.into_operations());
+
+    let _ = crate::optimise_schema(&mut schema, options, &mut recorder);
+    (schema, recorder.operations)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{jsonschema_equivalent_with_patch, PatchOperation, Recorder};
+    use crate::{AnnotationPolicy, Options, UnknownKeywordPolicy};
+    use serde_json::{json, Value};
+    use test_case::test_case;
+
+    #[test_case(&[PatchOperation::Add { path: "/a".to_string(), value: json!(1) }, PatchOperation::Remove { path: "/a".to_string() }] => Vec::<Value>::new())]
+    #[test_case(
+        &[PatchOperation::Add { path: "/a".to_string(), value: json!(1) }, PatchOperation::Replace { path: "/a".to_string(), value: json!(2) }]
+        => vec![json!({"op": "add", "path": "/a", "value": 2})]
+    )]
+    #[test_case(
+        &[PatchOperation::Remove { path: "/a".to_string() }, PatchOperation::Add { path: "/a".to_string(), value: json!(2) }]
+        => vec![json!({"op": "replace", "path": "/a", "value": 2})]
+    )]
+    #[test_case(
+        &[PatchOperation::Remove { path: "/a/b".to_string() }, PatchOperation::Replace { path: "/a".to_string(), value: json!(2) }]
+        => vec![json!({"op": "replace", "path": "/a", "value": 2})]
+    )]
+    #[test_case(
+        &[PatchOperation::Replace { path: "/a".to_string(), value: json!({"b": 1}) }, PatchOperation::Add { path: "/a/c".to_string(), value: json!(2) }]
+        => vec![json!({"op": "replace", "path": "/a", "value": {"b": 1, "c": 2}})]
+    )]
+    #[test_case(
+        &[PatchOperation::Remove { path: "/ab".to_string() }, PatchOperation::Replace { path: "/a".to_string(), value: json!(2) }]
+        => vec![json!({"op": "remove", "path": "/ab"}), json!({"op": "replace", "path": "/a", "value": 2})]
+    )]
+    #[test_case(
+        &[PatchOperation::Remove { path: "/a/0".to_string() }, PatchOperation::Remove { path: "/a/0".to_string() }]
+        => vec![json!({"op": "remove", "path": "/a/0"}), json!({"op": "remove", "path": "/a/0"})]
+    )]
+    #[test_case(
+        &[PatchOperation::Replace { path: "/a/1".to_string(), value: json!(1) }, PatchOperation::Remove { path: "/a/0".to_string() }, PatchOperation::Replace { path: "/a/1".to_string(), value: json!(2) }]
+        => vec![json!({"op": "replace", "path": "/a/1", "value": 1}), json!({"op": "remove", "path": "/a/0"}), json!({"op": "replace", "path": "/a/1", "value": 2})]
+    )]
+    #[test_case(
+        &[PatchOperation::Add { path: "/a/1".to_string(), value: json!({}) }, PatchOperation::Remove { path: "/a/0".to_string() }, PatchOperation::Add { path: "/a/0/b".to_string(), value: json!(2) }]
+        => vec![json!({"op": "add", "path": "/a/1", "value": {}}), json!({"op": "remove", "path": "/a/0"}), json!({"op": "add", "path": "/a/0/b", "value": 2})]
+    )]
+    #[test_case(
+        &[PatchOperation::Add { path: "/a".to_string(), value: json!([1, 2]) }, PatchOperation::Remove { path: "/a/0".to_string() }]
+        => vec![json!({"op": "add", "path": "/a", "value": [2]})]
+    )]
+    fn test_recorder_composes_operations(operations: &[PatchOperation]) -> Vec<Value> {
+        let mut recorder = Recorder::default();
+        for operation in operations {
+            recorder.push(operation.clone());
+        }
+        recorder
+            .operations
+            .iter()
+            .map(PatchOperation::to_value)
+            .collect()
+    }
+
+    #[test_case(json!({"a": 1}), &PatchOperation::Add { path: "/b".to_string(), value: json!(2) } => Some(json!({"a": 1, "b": 2})))]
+    #[test_case(json!({"a": [1, 3]}), &PatchOperation::Add { path: "/a/1".to_string(), value: json!(2) } => Some(json!({"a": [1, 2, 3]})))]
+    #[test_case(json!({"a": [1]}), &PatchOperation::Add { path: "/a/-".to_string(), value: json!(2) } => Some(json!({"a": [1, 2]})))]
+    #[test_case(json!({"a/b": 1}), &PatchOperation::Remove { path: "/a~1b".to_string() } => Some(json!({})))]
+    #[test_case(json!({"a": [1, 2]}), &PatchOperation::Remove { path: "/a/0".to_string() } => Some(json!({"a": [2]})))]
+    #[test_case(json!({"a": 1}), &PatchOperation::Replace { path: String::new(), value: json!(false) } => Some(json!(false)))]
+    #[test_case(json!({"a": 1}), &PatchOperation::Replace { path: "/b".to_string(), value: json!(1) } => None)]
+    #[test_case(json!({"a": 1}), &PatchOperation::Remove { path: "/b/c".to_string() } => None)]
+    fn test_apply(mut document: Value, operation: &PatchOperation) -> Option<Value> {
+        if operation.apply(&mut document) {
+            Some(document)
+        } else {
+            None
+        }
+    }
+
+    #[test_case(&json!({"type": "string", "enum": [1, "a", 2, "b"]}) => vec![json!({"op": "remove", "path": "/enum/2"}), json!({"op": "remove", "path": "/enum/0"})])]
+    #[test_case(&json!({"properties": {"a": {"type": "string", "minimum": 1}}}) => vec![json!({"op": "remove", "path": "/properties/a/minimum"})])]
+    #[test_case(&json!({"allOf": [{"type": "string"}, {"properties": {"a": {"type": "integer"}}}]}) => vec![json!({"op": "add", "path": "/type", "value": "string"}), json!({"op": "remove", "path": "/allOf/0"})])]
+    fn test_patch_covers_only_the_edited_locations(schema: &Value) -> Vec<Value> {
+        crate::init_logger();
+        let (_, patch) = jsonschema_equivalent_with_patch(schema.clone(), &Options::new());
+        patch.iter().map(PatchOperation::to_value).collect()
+    }
+
+    #[test_case(&json!(true), &Options::new())]
+    #[test_case(&json!({}), &Options::new())]
+    #[test_case(&json!({"type": "string", "enum": [1]}), &Options::new())]
+    #[test_case(&json!({"properties": {"a": {"type": "string", "minimum": 1}, "b": {}}}), &Options::new())]
+    #[test_case(&json!({"allOf": [{"type": ["integer", "string"]}, {"allOf": [{"type": "number", "minLength": 1}]}]}), &Options::new())]
+    #[test_case(&json!({"anyOf": [{"if": true, "then": {"minProperties": 1}}, {"items": [{}, {"not": {}}], "additionalItems": false}]}), &Options::new())]
+    #[test_case(&json!({"properties": (0..16).map(|index| (format!("p{index}"), json!({"type": "string", "minimum": index}))).collect::<serde_json::Map<_, _>>()}), &Options::new())]
+    #[test_case(&json!({"title": "a", "x-foo": 1, "items": {"description": "b", "x-foo": 1}}), &Options::new().with_annotation_policy(AnnotationPolicy::Strip).with_unknown_keyword_policy(UnknownKeywordPolicy::Strip))]
+    #[test_case(&json!({"title": "a", "type": "string", "enum": [1], "allOf": [{"title": "b", "examples": [1]}]}), &Options::new().with_annotation_policy(AnnotationPolicy::Preserve))]
+    fn test_patch_transforms_the_input_into_the_output(schema: &Value, options: &Options) {
+        crate::init_logger();
+        let (optimised_schema, patch) = jsonschema_equivalent_with_patch(schema.clone(), options);
+        assert_eq!(
+            optimised_schema,
+            crate::jsonschema_equivalent_with_options(schema.clone(), options)
+        );
+
+        let mut patched_schema = schema.clone();
+        for operation in &patch {
+            assert!(operation.apply(&mut patched_schema), "{:?}", operation);
+        }
+        assert_eq!(patched_schema, optimised_schema);
+    }
+}
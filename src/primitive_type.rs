@@ -6,13 +6,20 @@ use std::convert::TryFrom;
 /// The usage of the enum allows to have a faster processing (less string comparisons)
 /// as well as smaller memory footprint as the enum instance uses 2 bytes.
 #[derive(Clone, Copy, Debug, Hash, Eq, Ord, PartialEq, PartialOrd)]
-pub(crate) enum PrimitiveType {
+pub enum PrimitiveType {
+    /// `"array"` primitive type
     Array,
+    /// `"boolean"` primitive type
     Boolean,
+    /// `"integer"` primitive type
     Integer,
+    /// `"null"` primitive type
     Null,
+    /// `"number"` primitive type
     Number,
+    /// `"object"` primitive type
     Object,
+    /// `"string"` primitive type
     String,
 }
 impl TryFrom<&str> for PrimitiveType {
@@ -0,0 +1,1793 @@
+//! Satisfiability (emptiness) analysis of JSON Schemas.
+//!
+//! The analysis abstracts a schema into a per-type [`Domain`]: the set of allowed primitive types
+//! and, for each of them, the constraints that we are able to reason about (numeric intervals,
+//! string lengths, array lengths and items, object keys and properties count).
+//!
+//! Whenever a keyword cannot be modelled precisely (ie. `pattern` or `multipleOf` with multiple values)
+//! the affected primitive types are marked as _inexact_. Inexact types can still be proven empty, as the
+//! domain is always an over-approximation of the valid instances, but can never be reported as satisfiable.
+use crate::{
+    helpers::{is, types::PrimitiveTypesBitMap},
+    primitive_type::PrimitiveType,
+};
+use serde_json::{Map, Value};
+use std::cmp::Ordering;
+use std::collections::BTreeSet;
+use std::convert::TryFrom;
+
+/// Outcome of the satisfiability analysis of a schema.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Satisfiability {
+    /// There is at least one instance that is valid against the schema
+    Yes,
+    /// No instance can be valid against the schema, so the schema is equivalent to a `false` schema
+    No,
+    /// The analysis was not able to determine if the schema can be satisfied
+    Unknown,
+}
+
+impl Satisfiability {
+    /// Satisfiability of a set of alternatives (at least one of them has to be satisfied)
+    pub(crate) fn or(self, other: Self) -> Self {
+        match (self, other) {
+            (Self::Yes, _) | (_, Self::Yes) => Self::Yes,
+            (Self::No, Self::No) => Self::No,
+            _ => Self::Unknown,
+        }
+    }
+
+    /// Satisfiability of a set of independent requirements (all of them have to be satisfied)
+    pub(crate) fn and(self, other: Self) -> Self {
+        match (self, other) {
+            (Self::No, _) | (_, Self::No) => Self::No,
+            (Self::Yes, Self::Yes) => Self::Yes,
+            _ => Self::Unknown,
+        }
+    }
+
+    /// Degrade a `Yes` into `Unknown`, used when the constraints are over-approximated
+    fn inexact(self) -> Self {
+        if self == Self::No {
+            Self::No
+        } else {
+            Self::Unknown
+        }
+    }
+}
+
+/// Primitive types, as represented by `PrimitiveTypesBitMap`, that are considered by the analysis.
+///
+/// NOTE: `PrimitiveType::Number` represents the numbers that are not integers, as the integers
+/// are already covered by `PrimitiveType::Integer`
+static KINDS: &[PrimitiveType] = &[
+    PrimitiveType::Array,
+    PrimitiveType::Boolean,
+    PrimitiveType::Integer,
+    PrimitiveType::Null,
+    PrimitiveType::Number,
+    PrimitiveType::Object,
+    PrimitiveType::String,
+];
+
+/// Extract a non negative integer (as required by `minLength`, `maxItems`, etc.) from a keyword value
+fn as_length(value: &Value) -> Option<u64> {
+    value.as_u64().or_else(|| {
+        value.as_f64().and_then(|number| {
+            if number >= 0.0 && number.fract() == 0.0 && number < 2_f64.powi(64) {
+                // This is safe because we know that number is a non negative integer smaller than u64::MAX
+                #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+                Some(number as u64)
+            } else {
+                None
+            }
+        })
+    })
+}
+
+/// Primitive type of a JSON value, distinguishing integers from the other numbers
+fn kind_of(value: &Value) -> PrimitiveType {
+    match value {
+        Value::Number(number) => {
+            if number.is_i64()
+                || number.is_u64()
+                || number.as_f64().is_some_and(|n| n.fract() == 0.0)
+            {
+                PrimitiveType::Integer
+            } else {
+                PrimitiveType::Number
+            }
+        }
+        _ => PrimitiveType::from_serde_value(value),
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Bound {
+    value: f64,
+    exclusive: bool,
+}
+
+impl Bound {
+    /// Check if `bound` (used as lower bound) is implied by `self`
+    fn implies_lower(self, bound: Self) -> bool {
+        match self.value.partial_cmp(&bound.value) {
+            Some(Ordering::Greater) => true,
+            Some(Ordering::Equal) => self.exclusive || !bound.exclusive,
+            _ => false,
+        }
+    }
+
+    /// Check if `bound` (used as upper bound) is implied by `self`
+    fn implies_upper(self, bound: Self) -> bool {
+        match self.value.partial_cmp(&bound.value) {
+            Some(Ordering::Less) => true,
+            Some(Ordering::Equal) => self.exclusive || !bound.exclusive,
+            _ => false,
+        }
+    }
+
+    /// Check if `number` is not below the bound (used as lower bound)
+    fn admits_from_above(self, number: f64) -> bool {
+        match number.partial_cmp(&self.value) {
+            Some(Ordering::Greater) => true,
+            Some(Ordering::Equal) => !self.exclusive,
+            _ => false,
+        }
+    }
+
+    /// Check if `number` is not above the bound (used as upper bound)
+    fn admits_from_below(self, number: f64) -> bool {
+        match number.partial_cmp(&self.value) {
+            Some(Ordering::Less) => true,
+            Some(Ordering::Equal) => !self.exclusive,
+            _ => false,
+        }
+    }
+}
+
+/// Numeric interval, eventually restricted by `multipleOf`
+#[derive(Clone, Debug, Default)]
+struct NumberDomain {
+    lower: Option<Bound>,
+    upper: Option<Bound>,
+    multiple_of: Vec<f64>,
+}
+
+impl NumberDomain {
+    fn restrict_lower(&mut self, bound: Bound) {
+        self.lower = match self.lower {
+            Some(current) if current.value > bound.value => Some(current),
+            Some(current) if current.value.partial_cmp(&bound.value) == Some(Ordering::Equal) => {
+                Some(Bound {
+                    value: current.value,
+                    exclusive: current.exclusive || bound.exclusive,
+                })
+            }
+            _ => Some(bound),
+        };
+    }
+
+    fn restrict_upper(&mut self, bound: Bound) {
+        self.upper = match self.upper {
+            Some(current) if current.value < bound.value => Some(current),
+            Some(current) if current.value.partial_cmp(&bound.value) == Some(Ordering::Equal) => {
+                Some(Bound {
+                    value: current.value,
+                    exclusive: current.exclusive || bound.exclusive,
+                })
+            }
+            _ => Some(bound),
+        };
+    }
+
+    fn and(&mut self, other: &Self) {
+        if let Some(lower) = other.lower {
+            self.restrict_lower(lower);
+        }
+        if let Some(upper) = other.upper {
+            self.restrict_upper(upper);
+        }
+        self.multiple_of.extend(other.multiple_of.iter().copied());
+    }
+
+    /// Smallest interval including both `self` and `other`
+    fn hull(&self, other: &Self) -> Self {
+        let lower = match (self.lower, other.lower) {
+            (Some(a), Some(b)) if a.value < b.value => Some(a),
+            (Some(a), Some(b)) if b.value < a.value => Some(b),
+            (Some(a), Some(b)) => Some(Bound {
+                value: a.value,
+                exclusive: a.exclusive && b.exclusive,
+            }),
+            _ => None,
+        };
+        let upper = match (self.upper, other.upper) {
+            (Some(a), Some(b)) if a.value > b.value => Some(a),
+            (Some(a), Some(b)) if b.value > a.value => Some(b),
+            (Some(a), Some(b)) => Some(Bound {
+                value: a.value,
+                exclusive: a.exclusive && b.exclusive,
+            }),
+            _ => None,
+        };
+        Self {
+            lower,
+            upper,
+            multiple_of: Vec::new(),
+        }
+    }
+
+    fn contains(&self, number: f64) -> bool {
+        self.lower
+            .is_none_or(|lower| lower.admits_from_above(number))
+            && self
+                .upper
+                .is_none_or(|upper| upper.admits_from_below(number))
+    }
+
+    /// Smallest multiple of `multiple_of` included in the interval (the closest to zero if the interval is unbounded)
+    fn smallest_multiple(&self, multiple_of: f64) -> Option<f64> {
+        if multiple_of <= 0.0 {
+            return None;
+        }
+        let candidate = if self.contains(0.0) {
+            0.0
+        } else if let Some(lower) = self.lower {
+            let candidate = (lower.value / multiple_of).ceil() * multiple_of;
+            if lower.admits_from_above(candidate) {
+                candidate
+            } else {
+                candidate + multiple_of
+            }
+        } else if let Some(upper) = self.upper {
+            let candidate = (upper.value / multiple_of).floor() * multiple_of;
+            if upper.admits_from_below(candidate) {
+                candidate
+            } else {
+                candidate - multiple_of
+            }
+        } else {
+            0.0
+        };
+        if self.contains(candidate) {
+            Some(candidate)
+        } else {
+            None
+        }
+    }
+
+    /// Satisfiability of the interval considering only integer numbers
+    fn integer_satisfiability(&self) -> Satisfiability {
+        match self.multiple_of.as_slice() {
+            [] => {}
+            [multiple_of] => {
+                return match self.smallest_multiple(*multiple_of) {
+                    None => Satisfiability::No,
+                    Some(candidate) if candidate.fract() == 0.0 => Satisfiability::Yes,
+                    Some(_) => Satisfiability::Unknown,
+                };
+            }
+            _ => return Satisfiability::Unknown,
+        }
+        let candidate = if self.contains(0.0) {
+            0.0
+        } else if let Some(lower) = self.lower {
+            let candidate = lower.value.ceil();
+            if lower.admits_from_above(candidate) {
+                candidate
+            } else {
+                candidate + 1.0
+            }
+        } else if let Some(upper) = self.upper {
+            let candidate = upper.value.floor();
+            if upper.admits_from_below(candidate) {
+                candidate
+            } else {
+                candidate - 1.0
+            }
+        } else {
+            0.0
+        };
+        if self.contains(candidate) {
+            Satisfiability::Yes
+        } else {
+            Satisfiability::No
+        }
+    }
+
+    /// Satisfiability of the interval considering only numbers with a fractional part
+    fn fractional_satisfiability(&self) -> Satisfiability {
+        match self.multiple_of.as_slice() {
+            [] => {}
+            [multiple_of] => {
+                return match self.smallest_multiple(*multiple_of) {
+                    None => Satisfiability::No,
+                    Some(candidate) if candidate.fract() != 0.0 => Satisfiability::Yes,
+                    Some(_) if multiple_of.fract() == 0.0 => Satisfiability::No,
+                    Some(_) => Satisfiability::Unknown,
+                };
+            }
+            _ => return Satisfiability::Unknown,
+        }
+        match (self.lower, self.upper) {
+            (Some(lower), Some(upper)) => {
+                if lower.value < upper.value {
+                    // A non degenerated interval always contains a fractional number
+                    Satisfiability::Yes
+                } else if lower.value.partial_cmp(&upper.value) == Some(Ordering::Equal)
+                    && !lower.exclusive
+                    && !upper.exclusive
+                    && lower.value.fract() != 0.0
+                {
+                    Satisfiability::Yes
+                } else {
+                    Satisfiability::No
+                }
+            }
+            _ => Satisfiability::Yes,
+        }
+    }
+}
+
+/// Range of allowed lengths (characters for strings, items for arrays and properties for objects)
+#[derive(Clone, Debug, Default)]
+struct LengthDomain {
+    min: u64,
+    max: Option<u64>,
+}
+
+impl LengthDomain {
+    /// Check if all the lengths allowed by `self` are allowed by `other`
+    fn implies(&self, other: &Self) -> bool {
+        self.min >= other.min
+            && other
+                .max
+                .is_none_or(|other_max| self.max.is_some_and(|max| max <= other_max))
+    }
+
+    fn and(&mut self, other: &Self) {
+        self.min = self.min.max(other.min);
+        self.max = match (self.max, other.max) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        };
+    }
+
+    fn hull(&self, other: &Self) -> Self {
+        Self {
+            min: self.min.min(other.min),
+            max: match (self.max, other.max) {
+                (Some(a), Some(b)) => Some(a.max(b)),
+                _ => None,
+            },
+        }
+    }
+
+    fn contains(&self, length: u64) -> bool {
+        length >= self.min && self.max.is_none_or(|max| length <= max)
+    }
+
+    fn is_empty(&self) -> bool {
+        self.max.is_some_and(|max| max < self.min)
+    }
+}
+
+/// `items` keyword defined as array of schemas, with the associated `additionalItems`
+#[derive(Clone, Copy, Debug)]
+struct Tuple<'s> {
+    items: &'s [Value],
+    additional_items: Option<&'s Value>,
+}
+
+#[derive(Clone, Debug, Default)]
+struct ArrayDomain<'s> {
+    length: LengthDomain,
+    /// Schemas applied to all the items of the array
+    items: Vec<&'s Value>,
+    tuples: Vec<Tuple<'s>>,
+    contains: Vec<&'s Value>,
+    unique_items: bool,
+}
+
+impl<'s> ArrayDomain<'s> {
+    fn and(&mut self, other: &Self) {
+        self.length.and(&other.length);
+        self.items.extend(other.items.iter().copied());
+        self.tuples.extend(other.tuples.iter().copied());
+        self.contains.extend(other.contains.iter().copied());
+        self.unique_items |= other.unique_items;
+    }
+
+    fn hull(&self, other: &Self) -> Self {
+        Self {
+            length: self.length.hull(&other.length),
+            ..Self::default()
+        }
+    }
+
+    fn longest_tuple(&self) -> usize {
+        self.tuples
+            .iter()
+            .map(|tuple| tuple.items.len())
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Schemas that the item in position `index` has to be valid against
+    fn item_schemas(&self, index: usize) -> Vec<&'s Value> {
+        let mut schemas = self.items.clone();
+        for tuple in &self.tuples {
+            if let Some(item) = tuple.items.get(index) {
+                schemas.push(item);
+            } else if let Some(additional_items) = tuple.additional_items {
+                schemas.push(additional_items);
+            }
+        }
+        schemas
+    }
+
+    fn satisfiability(&self) -> Satisfiability {
+        if self.length.is_empty() {
+            return Satisfiability::No;
+        }
+        // Items after the longest tuple share the same schemas, so we can limit the
+        // amount of positions (and of array lengths) to analyse
+        let longest_tuple = self.longest_tuple();
+        let position_satisfiability: Vec<Satisfiability> = (0..=longest_tuple)
+            .map(|index| satisfiability_of_all(&self.item_schemas(index)))
+            .collect();
+        let contains_satisfiability: Vec<Satisfiability> = if self.contains.is_empty() {
+            Vec::new()
+        } else {
+            (0..=longest_tuple)
+                .map(|index| {
+                    let mut schemas = self.item_schemas(index);
+                    schemas.extend(self.contains.iter().copied());
+                    satisfiability_of_all(&schemas)
+                })
+                .collect()
+        };
+
+        let shortest = if self.contains.is_empty() {
+            self.length.min
+        } else {
+            self.length.min.max(1)
+        };
+        let longest = self
+            .length
+            .max
+            .unwrap_or(u64::MAX)
+            .min(shortest.max((longest_tuple as u64).saturating_add(1)));
+
+        let mut result = Satisfiability::No;
+        let mut length = shortest;
+        while length <= longest {
+            let analysed_positions = if length > longest_tuple as u64 {
+                longest_tuple.saturating_add(1)
+            } else {
+                // This is safe because length is not bigger than longest_tuple
+                #[allow(clippy::cast_possible_truncation)]
+                {
+                    length as usize
+                }
+            };
+            let mut length_satisfiability = position_satisfiability[..analysed_positions]
+                .iter()
+                .fold(Satisfiability::Yes, |acc, item| acc.and(*item));
+            if !self.contains.is_empty() {
+                length_satisfiability = length_satisfiability.and(
+                    contains_satisfiability[..analysed_positions]
+                        .iter()
+                        .fold(Satisfiability::No, |acc, item| acc.or(*item)),
+                );
+            }
+            if self.unique_items
+                && length > 1
+                && (!self.items.is_empty() || !self.tuples.is_empty() || !self.contains.is_empty())
+            {
+                // Items might be forced to be equal, we don't know
+                length_satisfiability = length_satisfiability.inexact();
+            }
+            result = result.or(length_satisfiability);
+            if result == Satisfiability::Yes {
+                break;
+            }
+            length = length.saturating_add(1);
+            if length == u64::MAX {
+                break;
+            }
+        }
+        result
+    }
+}
+
+/// Determine if the regular expression `pattern` matches `key`.
+///
+/// Only the patterns made of literal characters, eventually anchored (ie. `^a$`), are evaluated as regular
+/// expressions are not modelled, `None` is returned for the other patterns.
+pub(crate) fn pattern_matches(pattern: &str, key: &str) -> Option<bool> {
+    let (starts_anchored, pattern) = pattern
+        .strip_prefix('^')
+        .map_or((false, pattern), |pattern| (true, pattern));
+    let (ends_anchored, literal) = pattern
+        .strip_suffix('$')
+        .map_or((false, pattern), |pattern| (true, pattern));
+    if literal.contains(|character: char| "\\.^$|?*+()[]{}".contains(character)) {
+        return None;
+    }
+    Some(match (starts_anchored, ends_anchored) {
+        (true, true) => key == literal,
+        (true, false) => key.starts_with(literal),
+        (false, true) => key.ends_with(literal),
+        (false, false) => key.contains(literal),
+    })
+}
+
+/// Group of `properties`, `patternProperties` and `additionalProperties` keywords defined on the same schema.
+/// The keywords are grouped together as `additionalProperties` depends on the other two.
+#[derive(Clone, Copy, Debug)]
+struct PropertiesGroup<'s> {
+    properties: Option<&'s Map<String, Value>>,
+    pattern_properties: Option<&'s Map<String, Value>>,
+    additional_properties: Option<&'s Value>,
+}
+
+#[derive(Clone, Debug, Default)]
+struct ObjectDomain<'s> {
+    length: LengthDomain,
+    required: BTreeSet<&'s str>,
+    groups: Vec<PropertiesGroup<'s>>,
+    property_names: Vec<&'s Value>,
+    has_dependencies: bool,
+}
+
+impl<'s> ObjectDomain<'s> {
+    fn and(&mut self, other: &Self) {
+        self.length.and(&other.length);
+        self.required.extend(other.required.iter().copied());
+        self.groups.extend(other.groups.iter().copied());
+        self.property_names
+            .extend(other.property_names.iter().copied());
+        self.has_dependencies |= other.has_dependencies;
+    }
+
+    fn hull(&self, other: &Self) -> Self {
+        Self {
+            length: self.length.hull(&other.length),
+            required: self
+                .required
+                .intersection(&other.required)
+                .copied()
+                .collect(),
+            ..Self::default()
+        }
+    }
+
+    /// Schemas that the value of the property `key` has to be valid against.
+    /// The returned boolean reports if the schemas are exactly determined (`patternProperties`
+    /// whose match can not be decided might apply further schemas, or prevent `additionalProperties`
+    /// from being applied)
+    fn property_schemas(&self, key: Option<&str>) -> (Vec<&'s Value>, bool) {
+        let mut schemas = Vec::new();
+        let mut exact = true;
+        for group in &self.groups {
+            let property_schema = key.and_then(|key| group.properties.and_then(|p| p.get(key)));
+            let mut is_additional = property_schema.is_none();
+            if let Some(schema) = property_schema {
+                schemas.push(schema);
+            }
+            for (pattern, schema) in group.pattern_properties.into_iter().flatten() {
+                match key.and_then(|key| pattern_matches(pattern, key)) {
+                    Some(true) => {
+                        schemas.push(schema);
+                        is_additional = false;
+                    }
+                    Some(false) => {}
+                    None => {
+                        exact = false;
+                        is_additional = false;
+                    }
+                }
+            }
+            if is_additional {
+                if let Some(additional_properties) = group.additional_properties {
+                    schemas.push(additional_properties);
+                }
+            }
+        }
+        (schemas, exact)
+    }
+
+    fn property_satisfiability(&self, key: Option<&str>) -> Satisfiability {
+        let (schemas, exact) = self.property_schemas(key);
+        let satisfiability = satisfiability_of_all(&schemas);
+        if exact {
+            satisfiability
+        } else {
+            // The schemas are a subset of the applicable ones
+            match satisfiability {
+                Satisfiability::No => Satisfiability::No,
+                _ => Satisfiability::Unknown,
+            }
+        }
+    }
+
+    /// All the properties explicitly mentioned by `properties` keywords
+    fn listed_properties(&self) -> BTreeSet<&'s str> {
+        self.groups
+            .iter()
+            .filter_map(|group| group.properties)
+            .flat_map(Map::keys)
+            .map(String::as_str)
+            .collect()
+    }
+
+    fn satisfiability(&self) -> Satisfiability {
+        if self.length.is_empty() {
+            return Satisfiability::No;
+        }
+        let mut result = Satisfiability::Yes;
+
+        let property_names_satisfiability = satisfiability_of_all(&self.property_names);
+        let mut length = self.length.clone();
+        if property_names_satisfiability == Satisfiability::No {
+            // No property name is acceptable, so only empty objects are valid
+            length.and(&LengthDomain {
+                min: 0,
+                max: Some(0),
+            });
+        } else if !self
+            .property_names
+            .iter()
+            .all(|schema| is::true_schema(schema))
+        {
+            result = result.inexact();
+        }
+        if self.has_dependencies {
+            result = result.inexact();
+        }
+
+        let required_count = self.required.len() as u64;
+        let properties_count = length.min.max(required_count);
+        if !length.contains(properties_count) {
+            return Satisfiability::No;
+        }
+
+        for key in &self.required {
+            result = result.and(self.property_satisfiability(Some(key)));
+        }
+
+        let extra_properties = properties_count.saturating_sub(required_count);
+        if extra_properties > 0 {
+            let fresh_property = self.property_satisfiability(None);
+            let extras_satisfiability = if fresh_property == Satisfiability::Yes {
+                // There are infinitely many property names not mentioned in the schema
+                Satisfiability::Yes
+            } else {
+                let listed: Vec<Satisfiability> = self
+                    .listed_properties()
+                    .iter()
+                    .filter(|key| !self.required.contains(*key))
+                    .map(|key| self.property_satisfiability(Some(key)))
+                    .collect();
+                let satisfiable = listed
+                    .iter()
+                    .filter(|item| **item == Satisfiability::Yes)
+                    .count() as u64;
+                let maybe_satisfiable = listed
+                    .iter()
+                    .filter(|item| **item != Satisfiability::No)
+                    .count() as u64;
+                if satisfiable >= extra_properties {
+                    Satisfiability::Yes
+                } else if fresh_property == Satisfiability::Unknown
+                    || maybe_satisfiable >= extra_properties
+                {
+                    Satisfiability::Unknown
+                } else {
+                    Satisfiability::No
+                }
+            };
+            result = result.and(extras_satisfiability);
+        }
+        result
+    }
+}
+
+/// Abstract representation of the instances that can be valid against a schema.
+///
+/// The domain is always an over-approximation of the valid instances, the primitive types
+/// included in `inexact` are the ones for which the approximation is not precise.
+#[derive(Clone, Debug)]
+pub(crate) struct Domain<'s> {
+    types: PrimitiveTypesBitMap,
+    inexact: PrimitiveTypesBitMap,
+    number: NumberDomain,
+    string: LengthDomain,
+    array: ArrayDomain<'s>,
+    object: ObjectDomain<'s>,
+    /// Allowed values, as defined by `const` and `enum` keywords
+    values: Option<Vec<&'s Value>>,
+}
+
+impl<'s> Domain<'s> {
+    /// Domain of a `true` schema
+    fn any() -> Self {
+        Self {
+            types: PrimitiveTypesBitMap::from_schema_value(None),
+            inexact: PrimitiveTypesBitMap::default(),
+            number: NumberDomain::default(),
+            string: LengthDomain::default(),
+            array: ArrayDomain::default(),
+            object: ObjectDomain::default(),
+            values: None,
+        }
+    }
+
+    /// Domain of a `false` schema
+    fn none() -> Self {
+        Self {
+            types: PrimitiveTypesBitMap::default(),
+            ..Self::any()
+        }
+    }
+
+    /// Domain of a schema that we are not able to reason about
+    fn unknown() -> Self {
+        Self {
+            inexact: PrimitiveTypesBitMap::from_schema_value(None),
+            ..Self::any()
+        }
+    }
+
+    /// Build the domain of the conjunction of `schemas`
+    pub(crate) fn from_schemas(schemas: &[&'s Value]) -> Self {
+        let mut domain = Self::any();
+        for schema in schemas {
+            domain.and(&Self::from_schema(schema));
+        }
+        domain
+    }
+
+    /// Build the domain of `schema`
+    pub(crate) fn from_schema(schema: &'s Value) -> Self {
+        match schema {
+            Value::Bool(true) => Self::any(),
+            Value::Bool(false) => Self::none(),
+            Value::Object(schema_object) => Self::from_schema_object(schema_object),
+            // Not a valid schema, nothing can be assumed
+            _ => Self::unknown(),
+        }
+    }
+
+    /// Build the domain of `schema_object` ignoring the applicators (`allOf`, `anyOf`, `if`, etc.)
+    fn from_schema_keywords(schema_object: &'s Map<String, Value>) -> Self {
+        if schema_object.contains_key("$ref") {
+            // References are not resolved, so we cannot reason about the schema
+            return Self::unknown();
+        }
+
+        let mut domain = Self::any();
+        for (keyword, value) in schema_object {
+            domain.restrict(schema_object, keyword, value);
+        }
+        domain
+    }
+
+    fn from_schema_object(schema_object: &'s Map<String, Value>) -> Self {
+        let mut domain = Self::from_schema_keywords(schema_object);
+        if schema_object.contains_key("$ref") {
+            return domain;
+        }
+
+        if let Some(Value::Array(all_of)) = schema_object.get("allOf") {
+            for subschema in all_of {
+                domain.and(&Self::from_schema(subschema));
+            }
+        }
+        if let Some(Value::Array(any_of)) = schema_object.get("anyOf") {
+            domain = domain.and_one_of_the_branches(any_of);
+        }
+        if let Some(Value::Array(one_of)) = schema_object.get("oneOf") {
+            let satisfiable_branches = one_of
+                .iter()
+                .filter(|subschema| {
+                    Self::from_schema(subschema).satisfiability() != Satisfiability::No
+                })
+                .count();
+            domain = domain.and_one_of_the_branches(one_of);
+            if satisfiable_branches > 1 {
+                // An instance valid against multiple branches is not valid against `oneOf`
+                domain.inexact = domain.types;
+            }
+        }
+        if let Some(not_schema) = schema_object.get("not") {
+            domain.and_not(not_schema);
+        }
+        if let Some(if_schema) = schema_object.get("if") {
+            domain.and_if(
+                if_schema,
+                schema_object.get("then"),
+                schema_object.get("else"),
+            );
+        }
+        domain
+    }
+
+    /// Restrict the domain according to the (non applicator) `keyword`
+    #[allow(clippy::too_many_lines)]
+    fn restrict(&mut self, schema_object: &'s Map<String, Value>, keyword: &str, value: &'s Value) {
+        match keyword {
+            "type" => {
+                self.types = self.types & PrimitiveTypesBitMap::from_schema_value(Some(value));
+            }
+            "const" => self.and_values(vec![value]),
+            "enum" => {
+                if let Value::Array(values) = value {
+                    self.and_values(values.iter().collect());
+                }
+            }
+            "maximum" | "minimum" => {
+                let exclusive_keyword = if keyword == "maximum" {
+                    "exclusiveMaximum"
+                } else {
+                    "exclusiveMinimum"
+                };
+                if let Some(number) = value.as_f64() {
+                    // Draft4 defines `exclusiveMaximum` and `exclusiveMinimum` as boolean modifiers
+                    let bound = Bound {
+                        value: number,
+                        exclusive: schema_object.get(exclusive_keyword) == Some(&Value::Bool(true)),
+                    };
+                    if keyword == "maximum" {
+                        self.number.restrict_upper(bound);
+                    } else {
+                        self.number.restrict_lower(bound);
+                    }
+                } else {
+                    self.inexact |= PrimitiveType::Number;
+                }
+            }
+            "exclusiveMaximum" | "exclusiveMinimum" => match value {
+                Value::Number(number) => {
+                    let bound = Bound {
+                        value: number.as_f64().unwrap_or(0.0),
+                        exclusive: true,
+                    };
+                    if keyword == "exclusiveMaximum" {
+                        self.number.restrict_upper(bound);
+                    } else {
+                        self.number.restrict_lower(bound);
+                    }
+                }
+                Value::Bool(_) => {}
+                _ => self.inexact |= PrimitiveType::Number,
+            },
+            "multipleOf" => {
+                if let Some(number) = value.as_f64() {
+                    self.number.multiple_of.push(number);
+                } else {
+                    self.inexact |= PrimitiveType::Number;
+                }
+            }
+            "maxLength" | "minLength" => {
+                if let Some(length) = as_length(value) {
+                    let mut constraint = LengthDomain::default();
+                    if keyword == "maxLength" {
+                        constraint.max = Some(length);
+                    } else {
+                        constraint.min = length;
+                    }
+                    self.string.and(&constraint);
+                } else {
+                    self.inexact |= PrimitiveType::String;
+                }
+            }
+            "contentEncoding" | "contentMediaType" | "pattern" => {
+                self.inexact |= PrimitiveType::String;
+            }
+            "format" => {
+                self.inexact |= PrimitiveType::Number;
+                self.inexact |= PrimitiveType::String;
+            }
+            "maxItems" | "minItems" => {
+                if let Some(length) = as_length(value) {
+                    let mut constraint = LengthDomain::default();
+                    if keyword == "maxItems" {
+                        constraint.max = Some(length);
+                    } else {
+                        constraint.min = length;
+                    }
+                    self.array.length.and(&constraint);
+                } else {
+                    self.inexact |= PrimitiveType::Array;
+                }
+            }
+            "items" => match value {
+                Value::Array(items) => self.array.tuples.push(Tuple {
+                    items,
+                    additional_items: schema_object.get("additionalItems"),
+                }),
+                _ => self.array.items.push(value),
+            },
+            "contains" => self.array.contains.push(value),
+            "uniqueItems" => self.array.unique_items |= value == &Value::Bool(true),
+            "maxProperties" | "minProperties" => {
+                if let Some(length) = as_length(value) {
+                    let mut constraint = LengthDomain::default();
+                    if keyword == "maxProperties" {
+                        constraint.max = Some(length);
+                    } else {
+                        constraint.min = length;
+                    }
+                    self.object.length.and(&constraint);
+                } else {
+                    self.inexact |= PrimitiveType::Object;
+                }
+            }
+            "required" => {
+                if let Value::Array(required) = value {
+                    self.object
+                        .required
+                        .extend(required.iter().filter_map(Value::as_str));
+                }
+            }
+            "properties" => {
+                self.object.groups.push(PropertiesGroup {
+                    properties: value.as_object(),
+                    pattern_properties: schema_object
+                        .get("patternProperties")
+                        .and_then(Value::as_object),
+                    additional_properties: schema_object.get("additionalProperties"),
+                });
+            }
+            // Groups are registered by `properties` keyword, if present
+            "additionalProperties" | "patternProperties"
+                if !schema_object.contains_key("properties")
+                    && (keyword == "additionalProperties"
+                        || !schema_object.contains_key("additionalProperties")) =>
+            {
+                self.object.groups.push(PropertiesGroup {
+                    properties: None,
+                    pattern_properties: schema_object
+                        .get("patternProperties")
+                        .and_then(Value::as_object),
+                    additional_properties: schema_object.get("additionalProperties"),
+                });
+            }
+            "propertyNames" => self.object.property_names.push(value),
+            "dependencies" => {
+                self.object.has_dependencies |= value
+                    .as_object()
+                    .is_some_and(|dependencies| !dependencies.is_empty());
+            }
+            // Applicators are handled by `from_schema_object` while all the other keywords
+            // are not recognised, so they are considered annotations
+            _ => {}
+        }
+    }
+
+    fn and_values(&mut self, values: Vec<&'s Value>) {
+        self.values = Some(match self.values.take() {
+            None => values,
+            Some(current) => current
+                .into_iter()
+                .filter(|value| values.iter().any(|other| same_value(value, other)))
+                .collect(),
+        });
+    }
+
+    /// Conjunction of `self` with `other`
+    pub(crate) fn and(&mut self, other: &Self) {
+        self.types = self.types & other.types;
+        self.inexact |= other.inexact;
+        self.number.and(&other.number);
+        self.string.and(&other.string);
+        self.array.and(&other.array);
+        self.object.and(&other.object);
+        if let Some(values) = &other.values {
+            self.and_values(values.clone());
+        }
+    }
+
+    /// Disjunction of `self` with `other`. The resulting domain is the smallest domain that
+    /// includes both `self` and `other`, so it is exact only for the types defined by one of the two.
+    fn or(&self, other: &Self) -> Self {
+        let common_types = self.types & other.types;
+        let mut inexact =
+            (self.inexact & self.types) | (other.inexact & other.types) | common_types;
+
+        macro_rules! pick {
+            ($field: ident, $primitive_type: expr) => {
+                if !self.types.contains($primitive_type) {
+                    other.$field.clone()
+                } else if !other.types.contains($primitive_type) {
+                    self.$field.clone()
+                } else {
+                    self.$field.hull(&other.$field)
+                }
+            };
+        }
+
+        let values = match (&self.values, &other.values) {
+            (Some(values), Some(other_values)) => {
+                let mut values = values.clone();
+                for value in other_values {
+                    if !values.contains(value) {
+                        values.push(value);
+                    }
+                }
+                Some(values)
+            }
+            (Some(_), None) => {
+                inexact |= self.types;
+                None
+            }
+            (None, Some(_)) => {
+                inexact |= other.types;
+                None
+            }
+            (None, None) => None,
+        };
+
+        let number = if self.types.contains(PrimitiveType::Integer)
+            && other.types.contains(PrimitiveType::Integer)
+        {
+            self.number.hull(&other.number)
+        } else {
+            pick!(number, PrimitiveType::Integer)
+        };
+
+        Self {
+            types: self.types | other.types,
+            inexact,
+            number,
+            string: pick!(string, PrimitiveType::String),
+            array: pick!(array, PrimitiveType::Array),
+            object: pick!(object, PrimitiveType::Object),
+            values,
+        }
+    }
+
+    /// Conjunction of `self` with the disjunction of the `branches` (as defined by `anyOf`)
+    fn and_one_of_the_branches(&self, branches: &'s [Value]) -> Self {
+        branches
+            .iter()
+            .map(|branch| {
+                let mut domain = self.clone();
+                domain.and(&Self::from_schema(branch));
+                domain
+            })
+            .fold(Self::none(), |acc, domain| acc.or(&domain))
+    }
+
+    fn and_not(&mut self, not_schema: &'s Value) {
+        match not_schema {
+            Value::Bool(false) => {}
+            _ if is::true_schema(not_schema) => self.types = PrimitiveTypesBitMap::default(),
+            Value::Object(not_schema_object)
+                if not_schema_object.len() == 1 && not_schema_object.contains_key("type") =>
+            {
+                // `{"not": {"type": ...}}` is the only case where the negation is precisely computable
+                self.types
+                    .remove_all(PrimitiveTypesBitMap::from_schema_value(
+                        not_schema_object.get("type"),
+                    ));
+            }
+            _ => self.inexact = self.types,
+        }
+    }
+
+    fn and_if(
+        &mut self,
+        if_schema: &'s Value,
+        then_schema: Option<&'s Value>,
+        else_schema: Option<&'s Value>,
+    ) {
+        if then_schema.is_none() && else_schema.is_none() {
+            return;
+        }
+        let if_domain = Self::from_schema(if_schema);
+        let then_domain = then_schema.map_or_else(Self::any, Self::from_schema);
+        let else_domain = else_schema.map_or_else(Self::any, Self::from_schema);
+
+        if is::true_schema(if_schema) {
+            self.and(&then_domain);
+        } else if is::false_schema(if_schema) {
+            self.and(&else_domain);
+        } else {
+            let mut matching_if = self.clone();
+            matching_if.and(&if_domain);
+            matching_if.and(&then_domain);
+            // We cannot model instances not valid against `if` schema
+            let mut not_matching_if = self.clone();
+            not_matching_if.and(&else_domain);
+            not_matching_if.inexact = not_matching_if.types;
+            *self = matching_if.or(&not_matching_if);
+        }
+    }
+
+    /// Satisfiability of a value, defined by `const` or `enum`, against the domain
+    fn value_satisfiability(&self, value: &Value) -> Satisfiability {
+        let kind = kind_of(value);
+        if !self.types.contains(kind) {
+            return Satisfiability::No;
+        }
+        let satisfiability = match value {
+            Value::Number(number) => {
+                let number = number.as_f64().unwrap_or(0.0);
+                if !self.number.contains(number)
+                    || self
+                        .number
+                        .multiple_of
+                        .iter()
+                        .any(|multiple_of| (number / multiple_of).fract() != 0.0)
+                {
+                    Satisfiability::No
+                } else {
+                    Satisfiability::Yes
+                }
+            }
+            Value::String(string) => {
+                if self.string.contains(string.chars().count() as u64) {
+                    Satisfiability::Yes
+                } else {
+                    Satisfiability::No
+                }
+            }
+            Value::Array(items) => {
+                if !self.array.length.contains(items.len() as u64)
+                    || (self.array.unique_items
+                        && items.iter().enumerate().any(|(index, item)| {
+                            items[..index].iter().any(|other| same_value(item, other))
+                        }))
+                {
+                    Satisfiability::No
+                } else {
+                    let mut satisfiability = Satisfiability::Yes;
+                    for (index, item) in items.iter().enumerate() {
+                        satisfiability = satisfiability
+                            .and(value_satisfies_all(&self.array.item_schemas(index), item));
+                    }
+                    if !self.array.contains.is_empty() {
+                        satisfiability = satisfiability.and(
+                            items
+                                .iter()
+                                .map(|item| value_satisfies_all(&self.array.contains, item))
+                                .fold(Satisfiability::No, Satisfiability::or),
+                        );
+                    }
+                    satisfiability
+                }
+            }
+            Value::Object(properties) => {
+                if !self.object.length.contains(properties.len() as u64)
+                    || self
+                        .object
+                        .required
+                        .iter()
+                        .any(|key| !properties.contains_key(*key))
+                {
+                    Satisfiability::No
+                } else {
+                    let mut satisfiability = if self.object.has_dependencies {
+                        Satisfiability::Unknown
+                    } else {
+                        Satisfiability::Yes
+                    };
+                    for (key, property_value) in properties {
+                        let (schemas, exact) = self.object.property_schemas(Some(key));
+                        let property_satisfiability = value_satisfies_all(&schemas, property_value);
+                        satisfiability = satisfiability.and(if exact {
+                            property_satisfiability
+                        } else {
+                            property_satisfiability.inexact()
+                        });
+                        satisfiability = satisfiability.and(value_satisfies_all(
+                            &self.object.property_names,
+                            &Value::String(key.clone()),
+                        ));
+                    }
+                    satisfiability
+                }
+            }
+            Value::Bool(_) | Value::Null => Satisfiability::Yes,
+        };
+        if self.inexact.contains(kind) {
+            satisfiability.inexact()
+        } else {
+            satisfiability
+        }
+    }
+
+    /// Satisfiability of the domain restricted to a single primitive type
+    fn kind_satisfiability(&self, kind: PrimitiveType) -> Satisfiability {
+        if !self.types.contains(kind) {
+            return Satisfiability::No;
+        }
+        let satisfiability = match kind {
+            PrimitiveType::Array => self.array.satisfiability(),
+            PrimitiveType::Boolean | PrimitiveType::Null => Satisfiability::Yes,
+            PrimitiveType::Integer => self.number.integer_satisfiability(),
+            PrimitiveType::Number => self.number.fractional_satisfiability(),
+            PrimitiveType::Object => self.object.satisfiability(),
+            PrimitiveType::String => {
+                if self.string.is_empty() {
+                    Satisfiability::No
+                } else {
+                    Satisfiability::Yes
+                }
+            }
+        };
+        if self.inexact.contains(kind) {
+            satisfiability.inexact()
+        } else {
+            satisfiability
+        }
+    }
+
+    /// Determine if there is at least one instance represented by the domain
+    pub(crate) fn satisfiability(&self) -> Satisfiability {
+        if let Some(values) = &self.values {
+            values
+                .iter()
+                .map(|value| self.value_satisfiability(value))
+                .fold(Satisfiability::No, Satisfiability::or)
+        } else {
+            KINDS
+                .iter()
+                .map(|kind| self.kind_satisfiability(*kind))
+                .fold(Satisfiability::No, Satisfiability::or)
+        }
+    }
+}
+
+/// Maximum amount of sample values generated for each primitive type
+static MAX_SAMPLES: usize = 8;
+
+/// Maximum length of the generated strings and arrays
+static MAX_SAMPLE_LENGTH: u64 = 256;
+
+/// Convert a float into a JSON number, preferring the integer representation if possible
+fn number_to_value(number: f64) -> Option<Value> {
+    if number.fract() == 0.0 && number.abs() < 2_f64.powi(53) {
+        // This is safe because we know that number is an integer that fits into the mantissa
+        #[allow(clippy::cast_possible_truncation)]
+        Some(Value::from(number as i64))
+    } else {
+        serde_json::Number::from_f64(number).map(Value::Number)
+    }
+}
+
+impl NumberDomain {
+    fn is_multiple(&self, number: f64) -> bool {
+        self.multiple_of
+            .iter()
+            .all(|multiple_of| (number / multiple_of).fract() == 0.0)
+    }
+
+    /// Numbers close to the bounds of the interval
+    fn sample_candidates(&self) -> Vec<f64> {
+        let mut candidates = vec![0.0, 0.5, -0.5];
+        for bound in self.lower.iter().chain(self.upper.iter()) {
+            candidates.extend(&[
+                bound.value,
+                bound.value.floor(),
+                bound.value.ceil(),
+                bound.value.floor() - 1.0,
+                bound.value.ceil() + 1.0,
+                bound.value - 0.5,
+                bound.value + 0.5,
+            ]);
+        }
+        if let (Some(lower), Some(upper)) = (self.lower, self.upper) {
+            // Halves are summed (instead of halving the sum) to avoid overflowing on large bounds
+            candidates.push(lower.value / 2.0 + upper.value / 2.0);
+        }
+        for multiple_of in &self.multiple_of {
+            candidates.extend(self.smallest_multiple(*multiple_of));
+        }
+        candidates
+    }
+
+    fn sample_values(&self, integer: bool) -> Vec<Value> {
+        let mut samples = Vec::new();
+        for candidate in self.sample_candidates() {
+            if samples.len() < MAX_SAMPLES
+                && (candidate.fract() == 0.0) == integer
+                && self.contains(candidate)
+                && self.is_multiple(candidate)
+            {
+                if let Some(sample) = number_to_value(candidate) {
+                    if !samples.contains(&sample) {
+                        samples.push(sample);
+                    }
+                }
+            }
+        }
+        samples
+    }
+}
+
+impl LengthDomain {
+    /// Lengths to be used to generate sample values, limited to `MAX_SAMPLE_LENGTH`
+    fn sample_lengths(&self, preferred_min: u64) -> Vec<u64> {
+        let min = self.min.max(preferred_min);
+        let mut lengths = vec![min, min.saturating_add(1)];
+        lengths.extend(self.max);
+        lengths.dedup();
+        lengths
+            .into_iter()
+            .filter(|length| self.contains(*length) && *length <= MAX_SAMPLE_LENGTH)
+            .collect()
+    }
+
+    /// Length ranges just outside of `self`
+    fn outer(&self) -> Vec<Self> {
+        let mut outer = Vec::new();
+        if self.min > 0 {
+            outer.push(Self {
+                min: 0,
+                max: Some(self.min.saturating_sub(1)),
+            });
+        }
+        if let Some(max) = self.max {
+            outer.push(Self {
+                min: max.saturating_add(1),
+                max: None,
+            });
+        }
+        outer
+    }
+
+    fn sample_strings(&self) -> Vec<Value> {
+        self.sample_lengths(0)
+            .into_iter()
+            .map(|length| Value::String("a".repeat(usize::try_from(length).unwrap_or(0))))
+            .collect()
+    }
+}
+
+impl ArrayDomain<'_> {
+    fn sample_values(&self, depth: usize) -> Vec<Value> {
+        let mut samples = Vec::new();
+        let preferred_min = u64::from(!self.contains.is_empty());
+        for length in self.length.sample_lengths(preferred_min) {
+            let length = usize::try_from(length).unwrap_or(0);
+            let item_samples: Vec<Vec<Value>> = (0..length)
+                .map(|index| {
+                    let mut schemas = self.item_schemas(index);
+                    if index == 0 {
+                        schemas.extend(self.contains.iter().copied());
+                    }
+                    Domain::from_schemas(&schemas).sample_values(depth)
+                })
+                .collect();
+            if item_samples.iter().any(Vec::is_empty) {
+                continue;
+            }
+            let base: Vec<Value> = item_samples.iter().map(|items| items[0].clone()).collect();
+            samples.push(Value::Array(base.clone()));
+            for (index, items) in item_samples.iter().enumerate() {
+                for item in items.iter().skip(1) {
+                    if samples.len() >= MAX_SAMPLES {
+                        return samples;
+                    }
+                    let mut variant = base.clone();
+                    variant[index] = item.clone();
+                    samples.push(Value::Array(variant));
+                }
+            }
+        }
+        samples
+    }
+}
+
+impl ObjectDomain<'_> {
+    fn property_samples(&self, key: Option<&str>, depth: usize) -> Vec<Value> {
+        Domain::from_schemas(&self.property_schemas(key).0).sample_values(depth)
+    }
+
+    fn sample_values(&self, depth: usize) -> Vec<Value> {
+        let mut base = Map::new();
+        for key in &self.required {
+            if let Some(sample) = self.property_samples(Some(key), depth).into_iter().next() {
+                let _ = base.insert((*key).to_string(), sample);
+            } else {
+                return Vec::new();
+            }
+        }
+
+        let mut samples = vec![base.clone()];
+        for key in &self.required {
+            for sample in self.property_samples(Some(key), depth).into_iter().skip(1) {
+                let mut variant = base.clone();
+                let _ = variant.insert((*key).to_string(), sample);
+                samples.push(variant);
+            }
+        }
+        let optional_keys = self
+            .listed_properties()
+            .into_iter()
+            .filter(|key| !self.required.contains(key))
+            .map(Some)
+            .chain(std::iter::once(None));
+        for key in optional_keys {
+            for sample in self.property_samples(key, depth) {
+                let mut variant = base.clone();
+                let _ = variant.insert(key.unwrap_or("additionalProperty").to_string(), sample);
+                samples.push(variant);
+            }
+        }
+
+        samples
+            .into_iter()
+            .filter(|sample| self.length.contains(sample.len() as u64))
+            .take(MAX_SAMPLES)
+            .map(Value::Object)
+            .collect()
+    }
+}
+
+impl Domain<'_> {
+    /// Check if the numeric and length bounds of `other` are implied by the bounds of `self`.
+    /// Bounds of primitive types not allowed by `self` are not relevant.
+    pub(crate) fn implies_bounds_of(&self, other: &Domain<'_>) -> bool {
+        let has_numbers = self.types.contains(PrimitiveType::Integer)
+            || self.types.contains(PrimitiveType::Number);
+        let implies = |bound: Option<Bound>,
+                       other_bound: Option<Bound>,
+                       implies: fn(Bound, Bound) -> bool| {
+            other_bound
+                .is_none_or(|other_bound| bound.is_some_and(|bound| implies(bound, other_bound)))
+        };
+        (!has_numbers
+            || (implies(self.number.lower, other.number.lower, Bound::implies_lower)
+                && implies(self.number.upper, other.number.upper, Bound::implies_upper)))
+            && (!self.types.contains(PrimitiveType::String) || self.string.implies(&other.string))
+            && (!self.types.contains(PrimitiveType::Array)
+                || self.array.length.implies(&other.array.length))
+            && (!self.types.contains(PrimitiveType::Object)
+                || self.object.length.implies(&other.object.length))
+    }
+
+    /// Domains containing the values just outside the numeric and length bounds of `self`.
+    /// They are useful to look for values accepted by a schema and not accepted by a more restrictive one.
+    pub(crate) fn outer_domains(&self) -> Vec<Domain<'static>> {
+        let flip = |bound: Bound| Bound {
+            value: bound.value,
+            exclusive: !bound.exclusive,
+        };
+        let mut domains = Vec::new();
+        if let Some(lower) = self.number.lower {
+            let mut domain = Domain::any();
+            domain.number.upper = Some(flip(lower));
+            domains.push(domain);
+        }
+        if let Some(upper) = self.number.upper {
+            let mut domain = Domain::any();
+            domain.number.lower = Some(flip(upper));
+            domains.push(domain);
+        }
+        for length in self.string.outer() {
+            let mut domain = Domain::any();
+            domain.string = length;
+            domains.push(domain);
+        }
+        for length in self.array.length.outer() {
+            let mut domain = Domain::any();
+            domain.array.length = length;
+            domains.push(domain);
+        }
+        for length in self.object.length.outer() {
+            let mut domain = Domain::any();
+            domain.object.length = length;
+            domains.push(domain);
+        }
+        domains
+    }
+
+    /// Generate values that are likely to be represented by the domain.
+    ///
+    /// NOTE: The values are not guaranteed to be valid, so they should be verified via `validate`
+    pub(crate) fn sample_values(&self, depth: usize) -> Vec<Value> {
+        if let Some(values) = &self.values {
+            return values.iter().map(|value| (*value).clone()).collect();
+        }
+        let mut samples = Vec::new();
+        for kind in KINDS {
+            if !self.types.contains(*kind) {
+                continue;
+            }
+            match kind {
+                PrimitiveType::Array if depth > 0 => {
+                    samples.extend(self.array.sample_values(depth.saturating_sub(1)));
+                }
+                PrimitiveType::Boolean => {
+                    samples.push(Value::Bool(false));
+                    samples.push(Value::Bool(true));
+                }
+                PrimitiveType::Integer => samples.extend(self.number.sample_values(true)),
+                PrimitiveType::Null => samples.push(Value::Null),
+                PrimitiveType::Number => samples.extend(self.number.sample_values(false)),
+                PrimitiveType::Object if depth > 0 => {
+                    samples.extend(self.object.sample_values(depth.saturating_sub(1)));
+                }
+                PrimitiveType::String => samples.extend(self.string.sample_strings()),
+                _ => {}
+            }
+        }
+        samples
+    }
+}
+
+/// Satisfiability of the conjunction of `schemas`
+pub(crate) fn satisfiability_of_all(schemas: &[&Value]) -> Satisfiability {
+    if schemas.iter().all(|schema| is::true_schema(schema)) {
+        // Shortcut needed to stop the recursion on unconstrained items and properties
+        return Satisfiability::Yes;
+    }
+    Domain::from_schemas(schemas).satisfiability()
+}
+
+/// Determine if `value` is valid against all the `schemas`
+fn value_satisfies_all(schemas: &[&Value], value: &Value) -> Satisfiability {
+    schemas
+        .iter()
+        .map(|schema| validate(schema, value))
+        .fold(Satisfiability::Yes, Satisfiability::and)
+}
+
+/// Determine if `value` is valid against `schema`.
+///
+/// `Satisfiability::Unknown` is returned if the validity depends on keywords that are not modelled (ie. `pattern`)
+pub(crate) fn validate(schema: &Value, value: &Value) -> Satisfiability {
+    let schema_object = match schema {
+        Value::Bool(true) => return Satisfiability::Yes,
+        Value::Bool(false) => return Satisfiability::No,
+        Value::Object(schema_object) => schema_object,
+        _ => return Satisfiability::Unknown,
+    };
+    let mut domain = Domain::from_schema_keywords(schema_object);
+    domain.and_values(vec![value]);
+    let mut result = domain.satisfiability();
+    if schema_object.contains_key("$ref") {
+        return result;
+    }
+
+    if let Some(Value::Array(all_of)) = schema_object.get("allOf") {
+        result = result.and(value_satisfies_all(
+            &all_of.iter().collect::<Vec<_>>(),
+            value,
+        ));
+    }
+    if let Some(Value::Array(any_of)) = schema_object.get("anyOf") {
+        result = result.and(
+            any_of
+                .iter()
+                .map(|subschema| validate(subschema, value))
+                .fold(Satisfiability::No, Satisfiability::or),
+        );
+    }
+    if let Some(Value::Array(one_of)) = schema_object.get("oneOf") {
+        let results: Vec<_> = one_of
+            .iter()
+            .map(|subschema| validate(subschema, value))
+            .collect();
+        let valid = results
+            .iter()
+            .filter(|item| **item == Satisfiability::Yes)
+            .count();
+        let maybe_valid = results
+            .iter()
+            .filter(|item| **item != Satisfiability::No)
+            .count();
+        result = result.and(if valid == 1 && maybe_valid == 1 {
+            Satisfiability::Yes
+        } else if valid > 1 || maybe_valid == 0 {
+            Satisfiability::No
+        } else {
+            Satisfiability::Unknown
+        });
+    }
+    if let Some(not_schema) = schema_object.get("not") {
+        result = result.and(match validate(not_schema, value) {
+            Satisfiability::Yes => Satisfiability::No,
+            Satisfiability::No => Satisfiability::Yes,
+            Satisfiability::Unknown => Satisfiability::Unknown,
+        });
+    }
+    if let Some(if_schema) = schema_object.get("if") {
+        let validate_branch = |branch: Option<&Value>| {
+            branch.map_or(Satisfiability::Yes, |branch| validate(branch, value))
+        };
+        let then_result = validate_branch(schema_object.get("then"));
+        let else_result = validate_branch(schema_object.get("else"));
+        result = result.and(match validate(if_schema, value) {
+            Satisfiability::Yes => then_result,
+            Satisfiability::No => else_result,
+            Satisfiability::Unknown if then_result == else_result => then_result,
+            Satisfiability::Unknown => Satisfiability::Unknown,
+        });
+    }
+    result
+}
+
+/// Equality of JSON values according to JSON Schema (ie. `1` and `1.0` are the same value)
+pub(crate) fn same_value(value: &Value, other: &Value) -> bool {
+    match (value, other) {
+        (Value::Number(number), Value::Number(other_number)) => {
+            number == other_number || number.as_f64() == other_number.as_f64()
+        }
+        (Value::Array(items), Value::Array(other_items)) => {
+            items.len() == other_items.len()
+                && items
+                    .iter()
+                    .zip(other_items)
+                    .all(|(item, other_item)| same_value(item, other_item))
+        }
+        (Value::Object(object), Value::Object(other_object)) => {
+            object.len() == other_object.len()
+                && object.iter().all(|(key, item)| {
+                    other_object
+                        .get(key)
+                        .is_some_and(|other_item| same_value(item, other_item))
+                })
+        }
+        _ => value == other,
+    }
+}
+
+/// Determine if there is at least one instance that is valid against `schema`.
+///
+/// The analysis is sound: `Satisfiability::Yes` and `Satisfiability::No` are reported only if
+/// the analysis has been able to prove it, otherwise `Satisfiability::Unknown` is returned.
+/// ```rust
+/// use jsonschema_equivalent::{is_satisfiable, Satisfiability};
+/// use serde_json::json;
+///
+/// assert_eq!(is_satisfiable(&json!({"type": "string", "minLength": 2, "maxLength": 1})), Satisfiability::No);
+/// assert_eq!(is_satisfiable(&json!({"type": "integer", "minimum": 1.5, "maximum": 2})), Satisfiability::Yes);
+/// assert_eq!(is_satisfiable(&json!({"type": "string", "pattern": "^a$"})), Satisfiability::Unknown);
+/// ```
+#[must_use]
+pub fn is_satisfiable(schema: &Value) -> Satisfiability {
+    Domain::from_schema(schema).satisfiability()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{is_satisfiable, same_value, validate, Satisfiability};
+    use serde_json::{json, Value};
+    use test_case::test_case;
+
+    #[test_case(&json!(true) => Satisfiability::Yes)]
+    #[test_case(&json!(false) => Satisfiability::No)]
+    #[test_case(&json!({}) => Satisfiability::Yes)]
+    #[test_case(&json!({"$ref": "#/definitions/a"}) => Satisfiability::Unknown)]
+    #[test_case(&json!({"type": []}) => Satisfiability::No)]
+    #[test_case(&json!({"type": "string", "x-custom": 1}) => Satisfiability::Yes)]
+    fn test_is_satisfiable_trivial_schemas(schema: &Value) -> Satisfiability {
+        is_satisfiable(schema)
+    }
+
+    #[test_case(&json!({"type": "number", "minimum": 2, "maximum": 1}) => Satisfiability::No)]
+    #[test_case(&json!({"type": "number", "minimum": 1, "maximum": 1}) => Satisfiability::Yes)]
+    #[test_case(&json!({"type": "number", "exclusiveMinimum": 1, "maximum": 1}) => Satisfiability::No)]
+    #[test_case(&json!({"type": "number", "minimum": 1, "maximum": 1, "exclusiveMaximum": true}) => Satisfiability::No)]
+    #[test_case(&json!({"type": "integer", "minimum": 1.2, "maximum": 1.8}) => Satisfiability::No)]
+    #[test_case(&json!({"type": "integer", "exclusiveMinimum": 1, "exclusiveMaximum": 2}) => Satisfiability::No)]
+    #[test_case(&json!({"type": "integer", "exclusiveMinimum": 1, "maximum": 2}) => Satisfiability::Yes)]
+    #[test_case(&json!({"type": "integer", "minimum": 1, "maximum": 4, "multipleOf": 5}) => Satisfiability::No)]
+    #[test_case(&json!({"type": "integer", "minimum": 1, "maximum": 5, "multipleOf": 5}) => Satisfiability::Yes)]
+    #[test_case(&json!({"type": "number", "multipleOf": 0.5, "minimum": 0.1, "maximum": 0.4}) => Satisfiability::No)]
+    #[test_case(&json!({"allOf": [{"multipleOf": 2}, {"multipleOf": 3}], "type": "integer"}) => Satisfiability::Unknown)]
+    #[test_case(&json!({"type": ["null", "number"], "minimum": 2, "maximum": 1}) => Satisfiability::Yes)]
+    #[test_case(&json!({"type": "number", "minimum": 3e-17, "maximum": 2e-17}) => Satisfiability::No)]
+    #[test_case(&json!({"type": "number", "minimum": 2e-17, "maximum": 3e-17}) => Satisfiability::Yes)]
+    #[test_case(&json!({"type": "number", "exclusiveMinimum": 2e-17, "maximum": 2e-17}) => Satisfiability::No)]
+    #[test_case(&json!({"type": "number", "minimum": 1e-17, "maximum": 1e-17, "exclusiveMaximum": true}) => Satisfiability::No)]
+    fn test_is_satisfiable_numbers(schema: &Value) -> Satisfiability {
+        is_satisfiable(schema)
+    }
+
+    #[test_case(&json!({"type": "string", "minLength": 2, "maxLength": 1}) => Satisfiability::No)]
+    #[test_case(&json!({"type": "string", "minLength": 1, "maxLength": 1}) => Satisfiability::Yes)]
+    #[test_case(&json!({"type": "string", "pattern": "^a+$"}) => Satisfiability::Unknown)]
+    #[test_case(&json!({"type": "string", "pattern": "^a+$", "minLength": 2, "maxLength": 1}) => Satisfiability::No)]
+    #[test_case(&json!({"minLength": 2, "maxLength": 1}) => Satisfiability::Yes)]
+    fn test_is_satisfiable_strings(schema: &Value) -> Satisfiability {
+        is_satisfiable(schema)
+    }
+
+    #[test_case(&json!({"type": "array", "minItems": 2, "maxItems": 1}) => Satisfiability::No)]
+    #[test_case(&json!({"type": "array", "items": false}) => Satisfiability::Yes)]
+    #[test_case(&json!({"type": "array", "items": false, "minItems": 1}) => Satisfiability::No)]
+    #[test_case(&json!({"type": "array", "items": [true, false], "minItems": 1}) => Satisfiability::Yes)]
+    #[test_case(&json!({"type": "array", "items": [true, false], "minItems": 2}) => Satisfiability::No)]
+    #[test_case(&json!({"type": "array", "items": [true], "additionalItems": false, "minItems": 2}) => Satisfiability::No)]
+    #[test_case(&json!({"type": "array", "contains": false}) => Satisfiability::No)]
+    #[test_case(&json!({"type": "array", "contains": {"type": "string"}, "items": {"type": "integer"}}) => Satisfiability::No)]
+    #[test_case(&json!({"type": "array", "contains": {"type": "string"}, "maxItems": 0}) => Satisfiability::No)]
+    #[test_case(&json!({"type": "array", "contains": {"type": "string"}, "items": [{"type": "integer"}]}) => Satisfiability::Yes)]
+    #[test_case(&json!({"type": "array", "items": {"const": 1}, "uniqueItems": true, "minItems": 2}) => Satisfiability::Unknown)]
+    fn test_is_satisfiable_arrays(schema: &Value) -> Satisfiability {
+        is_satisfiable(schema)
+    }
+
+    #[test_case(&json!({"type": "object", "minProperties": 2, "maxProperties": 1}) => Satisfiability::No)]
+    #[test_case(&json!({"type": "object", "required": ["a", "b"], "maxProperties": 1}) => Satisfiability::No)]
+    #[test_case(&json!({"type": "object", "required": ["a"], "properties": {"a": false}}) => Satisfiability::No)]
+    #[test_case(&json!({"type": "object", "required": ["a"], "properties": {"a": {"type": "string", "maxLength": 0}}}) => Satisfiability::Yes)]
+    #[test_case(&json!({"type": "object", "required": ["b"], "properties": {"a": true}, "additionalProperties": false}) => Satisfiability::No)]
+    #[test_case(&json!({"type": "object", "required": ["b"], "patternProperties": {"^b$": true}, "additionalProperties": false}) => Satisfiability::Yes)]
+    #[test_case(&json!({"type": "object", "required": ["b"], "patternProperties": {"^b+$": true}, "additionalProperties": false}) => Satisfiability::Unknown)]
+    #[test_case(&json!({"type": "object", "required": ["a"], "properties": {"a": true}, "patternProperties": {"^a$": false}}) => Satisfiability::No)]
+    #[test_case(&json!({"type": "object", "required": ["a"], "properties": {"a": true}, "patternProperties": {"a+": false}}) => Satisfiability::Unknown)]
+    #[test_case(&json!({"type": "object", "required": ["ab"], "properties": {"ab": {"type": "string"}}, "patternProperties": {"^a": {"type": "integer"}}}) => Satisfiability::No)]
+    #[test_case(&json!({"type": "object", "required": ["ba"], "patternProperties": {"^a": false}, "additionalProperties": {"type": "string"}}) => Satisfiability::Yes)]
+    #[test_case(&json!({"type": "object", "minProperties": 2, "properties": {"a": true}, "additionalProperties": false}) => Satisfiability::No)]
+    #[test_case(&json!({"type": "object", "minProperties": 2, "properties": {"a": true, "b": true}, "additionalProperties": false}) => Satisfiability::Yes)]
+    #[test_case(&json!({"type": "object", "minProperties": 1, "propertyNames": false}) => Satisfiability::No)]
+    #[test_case(&json!({"type": "object", "allOf": [{"required": ["a"]}, {"properties": {"a": {"type": "integer"}}}, {"properties": {"a": {"type": "string"}}}]}) => Satisfiability::No)]
+    fn test_is_satisfiable_objects(schema: &Value) -> Satisfiability {
+        is_satisfiable(schema)
+    }
+
+    #[test_case(&json!({"const": 1, "type": "string"}) => Satisfiability::No)]
+    #[test_case(&json!({"const": 1, "type": "integer"}) => Satisfiability::Yes)]
+    #[test_case(&json!({"const": 1.5, "type": "integer"}) => Satisfiability::No)]
+    #[test_case(&json!({"enum": [1, "a"], "type": "string", "minLength": 2}) => Satisfiability::No)]
+    #[test_case(&json!({"enum": [1, "ab"], "type": "string", "minLength": 2}) => Satisfiability::Yes)]
+    #[test_case(&json!({"enum": [1, 2], "const": 3}) => Satisfiability::No)]
+    #[test_case(&json!({"enum": ["abc"], "pattern": "^a"}) => Satisfiability::Unknown)]
+    #[test_case(&json!({"const": {"a": 1}, "properties": {"a": {"type": "string"}}}) => Satisfiability::No)]
+    #[test_case(&json!({"const": [1, 1], "uniqueItems": true}) => Satisfiability::No)]
+    #[test_case(&json!({"const": {"a": []}, "properties": {"a": true}, "patternProperties": {"^a$": {"type": "string"}}}) => Satisfiability::No)]
+    #[test_case(&json!({"const": {"a": "b"}, "properties": {"a": true}, "patternProperties": {"^a$": {"type": "string"}}}) => Satisfiability::Yes)]
+    #[test_case(&json!({"const": {"a": []}, "patternProperties": {"^a+$": {"type": "string"}}}) => Satisfiability::Unknown)]
+    #[test_case(&json!({"enum": []}) => Satisfiability::No)]
+    fn test_is_satisfiable_values(schema: &Value) -> Satisfiability {
+        is_satisfiable(schema)
+    }
+
+    #[test_case(&json!({"properties": {"a": true}, "patternProperties": {"^a$": false}}), &json!({"a": 1}) => Satisfiability::No)]
+    #[test_case(&json!({"properties": {"a": true}, "patternProperties": {"^b$": false}}), &json!({"a": 1}) => Satisfiability::Yes)]
+    #[test_case(&json!({"patternProperties": {"^a": {"type": "string"}}, "additionalProperties": false}), &json!({"ab": "c"}) => Satisfiability::Yes)]
+    #[test_case(&json!({"patternProperties": {"^a": {"type": "string"}}, "additionalProperties": false}), &json!({"b": "c"}) => Satisfiability::No)]
+    #[test_case(&json!({"allOf": [{"patternProperties": {"a": {"minimum": 2}}}]}), &json!({"ba": 1}) => Satisfiability::No)]
+    fn test_validate(schema: &Value, value: &Value) -> Satisfiability {
+        validate(schema, value)
+    }
+
+    #[test_case(&json!(1), &json!(1) => true)]
+    #[test_case(&json!(1), &json!(1.0) => true)]
+    #[test_case(&json!(1), &json!(1.5) => false)]
+    #[test_case(&json!(1), &json!("1") => false)]
+    #[test_case(&json!([1, {"a": 2.0}]), &json!([1.0, {"a": 2}]) => true)]
+    #[test_case(&json!([1, 2]), &json!([2, 1]) => false)]
+    #[test_case(&json!({"a": 1}), &json!({"a": 1, "b": 2}) => false)]
+    fn test_same_value(value: &Value, other: &Value) -> bool {
+        same_value(value, other)
+    }
+
+    #[test_case(&json!({"allOf": [{"type": "string"}, {"type": "number"}]}) => Satisfiability::No)]
+    #[test_case(&json!({"allOf": [{"minimum": 2}, {"maximum": 1}], "type": "number"}) => Satisfiability::No)]
+    #[test_case(&json!({"anyOf": [false, {"type": "string", "minLength": 2, "maxLength": 1}]}) => Satisfiability::No)]
+    #[test_case(&json!({"anyOf": [{"type": "integer", "minimum": 2}, {"type": "string"}], "maximum": 1}) => Satisfiability::Yes)]
+    #[test_case(&json!({"anyOf": [{"type": "integer", "minimum": 2}, {"type": "integer", "maximum": 0}], "minimum": 1, "maximum": 1.5}) => Satisfiability::Unknown)]
+    #[test_case(&json!({"oneOf": [false, {"type": "string"}]}) => Satisfiability::Yes)]
+    #[test_case(&json!({"oneOf": [{"type": "string"}, {"minLength": 1}]}) => Satisfiability::Unknown)]
+    #[test_case(&json!({"not": {}}) => Satisfiability::No)]
+    #[test_case(&json!({"not": {"type": "string"}, "type": "string"}) => Satisfiability::No)]
+    #[test_case(&json!({"not": {"type": "string"}, "type": ["null", "string"]}) => Satisfiability::Yes)]
+    #[test_case(&json!({"not": {"minimum": 1}}) => Satisfiability::Unknown)]
+    #[test_case(&json!({"if": true, "then": false}) => Satisfiability::No)]
+    #[test_case(&json!({"if": false, "then": false, "else": {"type": "null"}}) => Satisfiability::Yes)]
+    #[test_case(&json!({"if": {"type": "string"}, "then": {"minLength": 1}, "else": false}) => Satisfiability::Yes)]
+    #[test_case(&json!({"if": {"type": "string"}, "then": false, "else": false}) => Satisfiability::No)]
+    fn test_is_satisfiable_applicators(schema: &Value) -> Satisfiability {
+        is_satisfiable(schema)
+    }
+}
@@ -0,0 +1,342 @@
+//! Sharing of identical subschemas (hash-consing).
+//!
+//! Generated schemas might repeat identical subschemas many times (ie. the same address object
+//! defined for multiple properties). Moving such subschemas into `definitions` and referencing them
+//! via `$ref` reduces the size of the schema, without altering its validation outcome.
+//!
+//! The subschemas are identified with the same keyword classification used while optimising the schema.
+use crate::helpers::{
+    pointer,
+    subschemas::{for_each_subschema, for_each_subschema_with_pointer},
+};
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+
+/// Default minimum size, in bytes of the serialised subschema, of the subschemas to share
+pub const DEFAULT_SHARE_SUBSCHEMAS_MIN_SIZE: usize = 64;
+
+/// Prefix of the names of the generated `definitions`
+static DEFINITION_NAME_PREFIX: &str = "shared_";
+
+/// Checks if `schema` defines a new base URI
+fn defines_base_uri(schema: &Value) -> bool {
+    schema.as_object().is_some_and(|schema_object| {
+        schema_object.contains_key("$id") || schema_object.get("id").is_some_and(Value::is_string)
+    })
+}
+
+/// Checks if `schema`, or any of its subschemas, defines a new base URI.
+/// Moving such subschemas would alter the resolution of the references that they contain.
+fn has_base_uri(schema: &Value) -> bool {
+    if defines_base_uri(schema) {
+        return true;
+    }
+    let mut result = false;
+    for_each_subschema(schema, &mut |subschema| {
+        result = result || has_base_uri(subschema);
+    });
+    result
+}
+
+/// Call `visitor` on the subschemas of `schema` (excluding `schema` itself and the content of its `definitions`)
+/// that could be replaced by a reference to a shared definition, together with their JSON Pointer.
+///
+/// The subschemas defining a new base URI, and their descendants, are not visited as the references
+/// contained by them (including the one that would replace them) are resolved against a different base URI.
+fn for_each_replaceable_subschema<'s>(
+    schema: &'s Value,
+    schema_pointer: &str,
+    reference_targets: &[String],
+    visitor: &mut impl FnMut(&str, &'s Value),
+) {
+    let definitions_pointer = pointer::append(schema_pointer, "definitions");
+    for_each_subschema_with_pointer(
+        schema,
+        schema_pointer,
+        &|_| false,
+        &mut |subschema_pointer, subschema| {
+            if pointer::is_ancestor(&definitions_pointer, &subschema_pointer)
+                || defines_base_uri(subschema)
+            {
+                return;
+            }
+            if !pointer::is_targeted(&subschema_pointer, reference_targets) {
+                visitor(&subschema_pointer, subschema);
+            }
+            for_each_replaceable_subschema(
+                subschema,
+                &subschema_pointer,
+                reference_targets,
+                visitor,
+            );
+        },
+    );
+}
+
+/// Checks if sharing `schema` would not reduce the size of the schema (ie. boolean schemas or references)
+fn is_not_worth_sharing(schema: &Value) -> bool {
+    match schema {
+        Value::Object(schema_object) => {
+            schema_object.len() == 1 && schema_object.contains_key("$ref")
+        }
+        _ => true,
+    }
+}
+
+/// Count the occurrences of the replaceable subschemas (excluding `schema` itself), indexed by their serialisation
+fn count_subschemas(
+    schema: &Value,
+    schema_pointer: &str,
+    reference_targets: &[String],
+    counters: &mut HashMap<String, usize>,
+) {
+    for_each_replaceable_subschema(
+        schema,
+        schema_pointer,
+        reference_targets,
+        &mut |_, subschema| {
+            let counter = counters.entry(subschema.to_string()).or_insert(0);
+            *counter = counter.saturating_add(1);
+        },
+    );
+}
+
+/// Replace all the replaceable subschemas of `schema` (excluding `schema` itself) equal to `shared_subschema` with `reference`
+fn replace_subschemas(
+    schema: &mut Value,
+    schema_pointer: &str,
+    reference_targets: &[String],
+    shared_subschema: &Value,
+    reference: &Value,
+) {
+    let mut pointers = Vec::new();
+    for_each_replaceable_subschema(
+        schema,
+        schema_pointer,
+        reference_targets,
+        &mut |subschema_pointer, subschema| {
+            // Nested occurrences are not collected as they are going to be replaced together with their ancestor
+            if subschema == shared_subschema
+                && !pointers
+                    .iter()
+                    .any(|pointer: &String| pointer::is_ancestor(pointer, subschema_pointer))
+            {
+                pointers.push(subschema_pointer.to_string());
+            }
+        },
+    );
+    for subschema_pointer in pointers {
+        let relative_pointer = &subschema_pointer[schema_pointer.len()..];
+        if let Some(subschema) = schema.pointer_mut(relative_pointer) {
+            *subschema = reference.clone();
+        }
+    }
+}
+
+/// Identify the biggest subschema repeated at least twice and with serialisation at least `min_size` bytes long
+fn biggest_repeated_subschema(schema: &Value, min_size: usize) -> Option<Value> {
+    let mut reference_targets = Vec::new();
+    pointer::collect_reference_targets(schema, &mut reference_targets);
+
+    let mut counters = HashMap::new();
+    count_subschemas(schema, "", &reference_targets, &mut counters);
+    if let Some(Value::Object(definitions)) = schema.get("definitions") {
+        // The subschemas of the already present definitions could be shared as well,
+        // unless the definition defines a new base URI
+        for (name, definition) in definitions {
+            if defines_base_uri(definition) {
+                continue;
+            }
+            let definition_pointer = pointer::append("/definitions", name);
+            count_subschemas(
+                definition,
+                &definition_pointer,
+                &reference_targets,
+                &mut counters,
+            );
+        }
+    }
+
+    counters
+        .into_iter()
+        .filter(|(serialised_subschema, count)| {
+            *count > 1 && serialised_subschema.len() >= min_size
+        })
+        .filter_map(|(serialised_subschema, _)| {
+            let subschema: Value = serde_json::from_str(&serialised_subschema).ok()?;
+            if is_not_worth_sharing(&subschema) || has_base_uri(&subschema) {
+                None
+            } else {
+                Some((serialised_subschema, subschema))
+            }
+        })
+        .max_by(|(serialised_subschema1, _), (serialised_subschema2, _)| {
+            serialised_subschema1
+                .len()
+                .cmp(&serialised_subschema2.len())
+                // Prefer the smallest serialisation in order to have a deterministic output
+                .then_with(|| serialised_subschema2.cmp(serialised_subschema1))
+        })
+        .map(|(_, subschema)| subschema)
+}
+
+/// Name, not already present in `definitions`, to use for a new definition
+fn definition_name(definitions: &Map<String, Value>) -> String {
+    (1..=definitions.len().saturating_add(1))
+        .map(|index| format!("{DEFINITION_NAME_PREFIX}{index}"))
+        .find(|name| !definitions.contains_key(name))
+        .unwrap_or_default()
+}
+
+/// Move the identical subschemas, with serialisation at least `min_size` bytes long, into the
+/// `definitions` of `schema` and replace them with `$ref`s.
+/// Return true if schema modifications have been performed.
+///
+/// The process is the inverse of references inlining and it is meant to run after the optimisation
+/// (as the optimisation does not resolve references).
+/// Subschemas targeted by the already present references (ie. `#/properties/a`), and the subschemas
+/// contained by subschemas or `definitions` defining a new base URI (`$id`), are not moved.
+///
+/// NOTE: Subschemas are compared by their serialisation, so it is recommended to provide a schema in its canonical form
+/// (see [`jsonschema_equivalent_canonical`](crate::jsonschema_equivalent_canonical)).
+/// ```rust
+/// use jsonschema_equivalent::share_subschemas;
+/// use serde_json::json;
+///
+/// let mut schema = json!({
+///     "properties": {
+///         "billing": {"type": "object", "required": ["street"]},
+///         "shipping": {"type": "object", "required": ["street"]},
+///     },
+/// });
+/// assert!(share_subschemas(&mut schema, 32));
+/// assert_eq!(schema, json!({
+///     "definitions": {"shared_1": {"type": "object", "required": ["street"]}},
+///     "properties": {
+///         "billing": {"$ref": "#/definitions/shared_1"},
+///         "shipping": {"$ref": "#/definitions/shared_1"},
+///     },
+/// }));
+/// ```
+pub fn share_subschemas(schema: &mut Value, min_size: usize) -> bool {
+    if !schema.is_object() || !schema.get("definitions").is_none_or(Value::is_object) {
+        return false;
+    }
+
+    let mut updated_schema = false;
+    while let Some(shared_subschema) = biggest_repeated_subschema(schema, min_size) {
+        let mut reference_targets = Vec::new();
+        pointer::collect_reference_targets(schema, &mut reference_targets);
+        let Some(schema_object) = schema.as_object_mut() else {
+            break;
+        };
+        let definitions = schema_object
+            .entry("definitions")
+            .or_insert_with(|| Value::Object(Map::new()));
+        let Some(definitions) = definitions.as_object_mut() else {
+            break;
+        };
+        let name = definition_name(definitions);
+        let reference = serde_json::json!({"$ref": format!("#/definitions/{name}")});
+
+        for (definition_name, definition) in definitions.iter_mut() {
+            if defines_base_uri(definition) {
+                continue;
+            }
+            replace_subschemas(
+                definition,
+                &pointer::append("/definitions", definition_name),
+                &reference_targets,
+                &shared_subschema,
+                &reference,
+            );
+        }
+        let _ = definitions.insert(name, shared_subschema.clone());
+        replace_subschemas(
+            schema,
+            "",
+            &reference_targets,
+            &shared_subschema,
+            &reference,
+        );
+        updated_schema = true;
+    }
+    updated_schema
+}
+
+#[cfg(test)]
+mod tests {
+    use super::share_subschemas;
+    use serde_json::{json, Value};
+    use test_case::test_case;
+
+    #[test_case(json!(true), 0 => json!(true))]
+    #[test_case(json!({"items": {"type": "string"}, "not": {"type": "string"}}), 64 => json!({"items": {"type": "string"}, "not": {"type": "string"}}) ; "below the minimum size")]
+    #[test_case(json!({"enum": [{"type": "string"}, {"type": "string"}]}), 0 => json!({"enum": [{"type": "string"}, {"type": "string"}]}) ; "not subschemas")]
+    #[test_case(
+        json!({"items": {"type": "string"}, "not": {"type": "string"}}), 0
+        => json!({"definitions": {"shared_1": {"type": "string"}}, "items": {"$ref": "#/definitions/shared_1"}, "not": {"$ref": "#/definitions/shared_1"}})
+    )]
+    #[test_case(
+        json!({"definitions": {"shared_1": {}}, "anyOf": [{"minimum": 1}, {"not": {"minimum": 1}}]}), 0
+        => json!({"definitions": {"shared_1": {}, "shared_2": {"minimum": 1}}, "anyOf": [{"$ref": "#/definitions/shared_2"}, {"not": {"$ref": "#/definitions/shared_2"}}]})
+    )]
+    #[test_case(
+        json!({"properties": {
+            "a": {"properties": {"x": {"minimum": 1}}},
+            "b": {"properties": {"x": {"minimum": 1}}},
+            "c": {"minimum": 1},
+        }}), 0
+        => json!({
+            "definitions": {"shared_1": {"properties": {"x": {"$ref": "#/definitions/shared_2"}}}, "shared_2": {"minimum": 1}},
+            "properties": {
+                "a": {"$ref": "#/definitions/shared_1"},
+                "b": {"$ref": "#/definitions/shared_1"},
+                "c": {"$ref": "#/definitions/shared_2"},
+            },
+        })
+        ; "nested subschemas"
+    )]
+    #[test_case(
+        json!({"properties": {"a": {"$id": "a.json", "minimum": 1}, "b": {"$id": "a.json", "minimum": 1}}}), 0
+        => json!({"properties": {"a": {"$id": "a.json", "minimum": 1}, "b": {"$id": "a.json", "minimum": 1}}})
+        ; "subschemas with base URI"
+    )]
+    #[test_case(
+        json!({"properties": {"a": {"type": "string"}, "b": {"type": "string"}, "c": {"$ref": "#/properties/a"}}}), 0
+        => json!({"properties": {"a": {"type": "string"}, "b": {"type": "string"}, "c": {"$ref": "#/properties/a"}}})
+        ; "subschemas targeted by references"
+    )]
+    #[test_case(
+        json!({"properties": {"a": {"items": {"type": "string"}}, "b": {"items": {"type": "string"}}, "c": {"$ref": "#/properties/a/items"}}}), 0
+        => json!({"properties": {"a": {"items": {"type": "string"}}, "b": {"items": {"type": "string"}}, "c": {"$ref": "#/properties/a/items"}}})
+        ; "subschemas containing reference targets"
+    )]
+    #[test_case(
+        json!({"properties": {"a": {"$id": "a.json", "properties": {"x": {"minimum": 1}}}, "b": {"$id": "b.json", "properties": {"x": {"minimum": 1}}}}}), 0
+        => json!({"properties": {"a": {"$id": "a.json", "properties": {"x": {"minimum": 1}}}, "b": {"$id": "b.json", "properties": {"x": {"minimum": 1}}}}})
+        ; "subschemas within a base URI"
+    )]
+    #[test_case(
+        json!({"definitions": {"a": {"$id": "a.json", "properties": {"x": {"minimum": 1}, "y": {"minimum": 1}}}}, "properties": {"x": {"minimum": 1}}}), 0
+        => json!({"definitions": {"a": {"$id": "a.json", "properties": {"x": {"minimum": 1}, "y": {"minimum": 1}}}}, "properties": {"x": {"minimum": 1}}})
+        ; "definitions with base URI"
+    )]
+    fn test_share_subschemas(mut schema: Value, min_size: usize) -> Value {
+        let _ = share_subschemas(&mut schema, min_size);
+        schema
+    }
+
+    #[test_case(&json!({"type": "array", "items": {"type": "object", "required": ["a"]}, "additionalItems": {"type": "object", "required": ["a"]}}), &json!([{"a": 1}, {"a": 2}]))]
+    #[test_case(&json!({"type": "array", "items": {"type": "object", "required": ["a"]}, "additionalItems": {"type": "object", "required": ["a"]}}), &json!([{"a": 1}, {"b": 2}]))]
+    #[test_case(&json!({"properties": {"a": {"type": "integer"}, "b": {"type": "integer"}, "c": {"$ref": "#/properties/a"}, "d": {"type": "integer"}}}), &json!({"c": "string"}))]
+    #[test_case(&json!({"properties": {"a": {"type": "integer"}, "b": {"type": "integer"}, "c": {"$ref": "#/properties/a"}, "d": {"type": "integer"}}}), &json!({"c": 1}))]
+    fn test_share_subschemas_preserves_validation(schema: &Value, instance: &Value) {
+        let mut shared_schema = schema.clone();
+        assert!(share_subschemas(&mut shared_schema, 0));
+        assert_eq!(
+            jsonschema::is_valid(schema, instance),
+            jsonschema::is_valid(&shared_schema, instance)
+        );
+    }
+}
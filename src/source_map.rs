@@ -0,0 +1,569 @@
+//! Source map from the locations of an optimised schema to the locations of the original schema.
+//!
+//! The source map is maintained while the rules modify the schema (see [`Observer`]): every time a rule modifies
+//! a schema, the locations (schemas and keywords) of the modified schema are traced back to the locations they
+//! had before the modification. A location is traced back to
+//!  * the same location, if the rule did not modify it (or modified it in place, ie. `type` narrowed by `optimise_keyword_type`)
+//!  * the locations of the equal values removed by the rule, if the rule moved it (ie. `minimum` hoisted from the
+//!    `anyOf` branches by `factor_common_constraints`); keywords merged into the same keyword of the modified
+//!    schema (ie. `type` of an `allOf` item intersected by `flatten_all_of`) are traced back to both the locations
+//!  * the modified schema (or keyword) containing it, if the rule generated it (ie. `type` added by `simple_const_cleanup`)
+use crate::constants::{KEYWORDS_WITH_DIRECT_SUBSCHEMAS, KEYWORDS_WITH_SUBSCHEMAS};
+use crate::helpers::{
+    edits::Edits,
+    pointer,
+    subschemas::{for_each_subschema_with_pointer, Location},
+};
+use crate::keywords::Observer;
+use crate::Options;
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+/// Map from the JSON Pointers of the schemas and keywords of an optimised schema to the JSON Pointers
+/// of the original schema they come from.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct SourceMap {
+    mappings: BTreeMap<String, Vec<String>>,
+}
+
+impl SourceMap {
+    /// JSON Pointers of the original schema the location referenced by `pointer` comes from.
+    ///
+    /// Locations that are not schemas or keywords (ie. `/required/0`) are resolved via their closest ancestor.
+    #[must_use]
+    pub fn original_pointers(&self, pointer: &str) -> &[String] {
+        let mut pointer = pointer;
+        loop {
+            if let Some(original_pointers) = self.mappings.get(pointer) {
+                return original_pointers;
+            }
+            match pointer.rfind('/') {
+                Some(index) => pointer = &pointer[..index],
+                None => return &[],
+            }
+        }
+    }
+
+    /// Iterate over the JSON Pointers of the optimised schema and the JSON Pointers they come from
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &[String])> {
+        self.mappings
+            .iter()
+            .map(|(pointer, original_pointers)| (pointer.as_str(), original_pointers.as_slice()))
+    }
+
+    /// Mappings of the location referenced by `pointer` and of its descendants
+    fn subtree<'s>(
+        &'s self,
+        pointer: &'s str,
+    ) -> impl Iterator<Item = (&'s String, &'s Vec<String>)> + 's {
+        self.mappings
+            .range::<str, _>((
+                std::ops::Bound::Included(pointer),
+                std::ops::Bound::Unbounded,
+            ))
+            .take_while(move |(mapped_pointer, _)| mapped_pointer.starts_with(pointer))
+            .filter(move |(mapped_pointer, _)| {
+                mapped_pointer.as_str() == pointer || pointer::is_ancestor(pointer, mapped_pointer)
+            })
+    }
+
+    /// Remove, and return, the mappings of the location referenced by `pointer` and of its descendants
+    fn remove_subtree(&mut self, pointer: &str) -> BTreeMap<String, Vec<String>> {
+        let pointers: Vec<String> = self
+            .subtree(pointer)
+            .map(|(mapped_pointer, _)| mapped_pointer.clone())
+            .collect();
+        pointers
+            .into_iter()
+            .filter_map(|mapped_pointer| self.mappings.remove_entry(&mapped_pointer))
+            .collect()
+    }
+
+    /// Record that `pointer` comes from `original_pointers`
+    fn add(&mut self, pointer: &str, original_pointers: impl IntoIterator<Item = String>) {
+        let mapped_pointers = self.mappings.entry(pointer.to_string()).or_default();
+        for original_pointer in original_pointers {
+            if !mapped_pointers.contains(&original_pointer) {
+                mapped_pointers.push(original_pointer);
+            }
+        }
+    }
+}
+
+/// Location of a schema, or of a keyword, within a schema
+#[derive(Debug)]
+struct Node<'v> {
+    /// JSON Pointer of the location, relative to the schema
+    pointer: String,
+    /// Keyword, if the location is the one of a keyword
+    keyword: Option<&'v str>,
+    value: &'v Value,
+}
+
+/// Collect the locations of `schema`, referenced by `schema_pointer`, and of all its subschemas
+fn collect_nodes<'v>(
+    schema: &'v Value,
+    schema_pointer: String,
+    options: &Options,
+    nodes: &mut Vec<Node<'v>>,
+) {
+    if let Value::Object(schema_object) = schema {
+        for (keyword, value) in schema_object {
+            nodes.push(Node {
+                pointer: pointer::append(&schema_pointer, keyword),
+                keyword: Some(keyword),
+                value,
+            });
+        }
+    }
+    for_each_subschema_with_pointer(
+        schema,
+        &schema_pointer,
+        &|keyword| options.is_custom_subschema_keyword(keyword),
+        &mut |subschema_pointer, subschema| {
+            collect_nodes(subschema, subschema_pointer, options, nodes);
+        },
+    );
+    nodes.push(Node {
+        pointer: schema_pointer,
+        keyword: None,
+        value: schema,
+    });
+}
+
+/// Outcome of tracing back a location of the modified schema
+enum Provenance {
+    /// The location, and all its descendants, are not modified
+    Unchanged,
+    /// The location comes from `original_pointers`. Its descendants are traced back starting from `source`,
+    /// the location of the schema before the modification corresponding to it (if any).
+    Traced {
+        original_pointers: Vec<String>,
+        source: Option<String>,
+    },
+}
+
+/// Tracing of the locations of a schema, referenced by `pointer`, modified by the application of a rule
+struct Trace<'a> {
+    /// JSON Pointer of the modified schema
+    pointer: &'a str,
+    schema: &'a Value,
+    updated_schema: &'a Value,
+    /// Source map before the modification
+    previous_source_map: &'a SourceMap,
+    /// Locations of `schema` not modified by the rule
+    unmodified_nodes: Vec<Node<'a>>,
+    /// Locations of `schema` removed or modified by the rule
+    modified_nodes: Vec<Node<'a>>,
+    options: &'a Options,
+}
+
+impl<'a> Trace<'a> {
+    fn new(
+        pointer: &'a str,
+        schema: &'a Value,
+        updated_schema: &'a Value,
+        previous_source_map: &'a SourceMap,
+        options: &'a Options,
+    ) -> Self {
+        let mut nodes = Vec::new();
+        collect_nodes(schema, String::new(), options, &mut nodes);
+        let (modified_nodes, unmodified_nodes) = nodes
+            .into_iter()
+            .partition(|node| updated_schema.pointer(&node.pointer) != Some(node.value));
+        Self {
+            pointer,
+            schema,
+            updated_schema,
+            previous_source_map,
+            unmodified_nodes,
+            modified_nodes,
+            options,
+        }
+    }
+
+    /// Original pointers of the location referenced by `relative_pointer` before the modification
+    fn previous_original_pointers(&self, relative_pointer: &str) -> &'a [String] {
+        self.previous_source_map
+            .original_pointers(&format!("{}{relative_pointer}", self.pointer))
+    }
+
+    /// Trace back the location of `value`, referenced by `relative_pointer`.
+    /// `source` is the location, before the modification, corresponding to it and `parent_original_pointers`
+    /// are the original pointers of the location containing it.
+    fn provenance(
+        &self,
+        value: &Value,
+        relative_pointer: &str,
+        keyword: Option<&str>,
+        source: Option<&str>,
+        parent_original_pointers: &[String],
+    ) -> Provenance {
+        let source_value = source.and_then(|source| self.schema.pointer(source));
+        let mut moved_nodes: Vec<&Node<'_>> = self
+            .modified_nodes
+            .iter()
+            .filter(|node| node.keyword == keyword && node.value == value)
+            .collect();
+        // The keywords of the modified schema might result from the merge of the same keywords
+        // of its removed subschemas (ie. `type` intersected by `flatten_all_of`)
+        let merged_nodes: Vec<&Node<'_>> = match keyword {
+            Some(keyword)
+                if moved_nodes.is_empty() && pointer::tokens(relative_pointer).count() == 1 =>
+            {
+                self.modified_nodes
+                    .iter()
+                    .filter(|node| {
+                        node.keyword == Some(keyword)
+                            && pointer::tokens(&node.pointer).count() > 1
+                            && self.updated_schema.pointer(&node.pointer).is_none()
+                    })
+                    .collect()
+            }
+            _ => Vec::new(),
+        };
+        if source_value == Some(value) && merged_nodes.is_empty() {
+            return Provenance::Unchanged;
+        }
+        if moved_nodes.is_empty() && keyword.is_some() && source_value.is_none() {
+            // The keyword might have been copied (ie. `required` hoisted from an `allOf` item)
+            moved_nodes = self
+                .unmodified_nodes
+                .iter()
+                .filter(|node| node.keyword == keyword && node.value == value)
+                .collect();
+        }
+
+        let mut original_pointers = Vec::new();
+        let mut add = |pointers: &[String]| {
+            for pointer in pointers {
+                if !original_pointers.contains(pointer) {
+                    original_pointers.push(pointer.clone());
+                }
+            }
+        };
+        let source = match (source_value, moved_nodes.first()) {
+            // Keywords modified in place keep their location, while schemas might be moved as a whole
+            // (ie. the items of an array of subschemas are shifted)
+            (Some(_), _) if keyword.is_some() => {
+                add(self.previous_original_pointers(source.unwrap_or_default()));
+                source.map(str::to_string)
+            }
+            (_, Some(moved_node)) => Some(moved_node.pointer.clone()),
+            (Some(_), None) => {
+                add(self.previous_original_pointers(source.unwrap_or_default()));
+                source.map(str::to_string)
+            }
+            // Generated by the rule
+            (_, None) => {
+                add(parent_original_pointers);
+                None
+            }
+        };
+        moved_nodes
+            .iter()
+            .chain(&merged_nodes)
+            .for_each(|node| add(self.previous_original_pointers(&node.pointer)));
+
+        Provenance::Traced {
+            original_pointers,
+            source,
+        }
+    }
+
+    /// Copy the mappings of the location referenced by `source`, and its descendants, to `relative_pointer`
+    fn copy(&self, source: &str, relative_pointer: &str, source_map: &mut SourceMap) {
+        let source_pointer = format!("{}{source}", self.pointer);
+        let target_pointer = format!("{}{relative_pointer}", self.pointer);
+        source_map.add(
+            &target_pointer,
+            self.previous_source_map
+                .original_pointers(&source_pointer)
+                .iter()
+                .cloned(),
+        );
+        for (pointer, original_pointers) in self.previous_source_map.subtree(&source_pointer) {
+            if *pointer != source_pointer {
+                source_map.add(
+                    &format!("{target_pointer}{}", &pointer[source_pointer.len()..]),
+                    original_pointers.iter().cloned(),
+                );
+            }
+        }
+    }
+
+    /// Trace back `value`, referenced by `relative_pointer`, and record it into `source_map`.
+    /// Return the original pointers and the source of its descendants, if they need to be traced back.
+    fn trace_node(
+        &self,
+        value: &Value,
+        relative_pointer: &str,
+        keyword: Option<&str>,
+        source: Option<&str>,
+        parent_original_pointers: &[String],
+        source_map: &mut SourceMap,
+    ) -> Option<(Vec<String>, Option<String>)> {
+        match self.provenance(
+            value,
+            relative_pointer,
+            keyword,
+            source,
+            parent_original_pointers,
+        ) {
+            Provenance::Unchanged => {
+                self.copy(source.unwrap_or_default(), relative_pointer, source_map);
+                None
+            }
+            Provenance::Traced {
+                original_pointers,
+                source,
+            } => {
+                source_map.add(
+                    &format!("{}{relative_pointer}", self.pointer),
+                    original_pointers.iter().cloned(),
+                );
+                Some((original_pointers, source))
+            }
+        }
+    }
+
+    /// Trace back the keywords of `schema`, referenced by `relative_pointer`, and their subschemas
+    fn trace_schema(
+        &self,
+        schema: &Value,
+        relative_pointer: &str,
+        source: Option<&str>,
+        original_pointers: &[String],
+        source_map: &mut SourceMap,
+    ) {
+        let Value::Object(schema_object) = schema else {
+            return;
+        };
+        for (keyword, value) in schema_object {
+            let keyword_pointer = pointer::append(relative_pointer, keyword);
+            let Some((keyword_original_pointers, keyword_source)) = self.trace_node(
+                value,
+                &keyword_pointer,
+                Some(keyword),
+                source
+                    .map(|source| pointer::append(source, keyword))
+                    .as_deref(),
+                original_pointers,
+                source_map,
+            ) else {
+                continue;
+            };
+
+            if KEYWORDS_WITH_DIRECT_SUBSCHEMAS.contains(keyword.as_str())
+                || (keyword == "items" && !value.is_array())
+                || self.options.is_custom_subschema_keyword(keyword)
+            {
+                self.trace_schema(
+                    value,
+                    &keyword_pointer,
+                    keyword_source.as_deref(),
+                    &keyword_original_pointers,
+                    source_map,
+                );
+                continue;
+            }
+            if !KEYWORDS_WITH_SUBSCHEMAS.contains(keyword.as_str()) && keyword != "definitions" {
+                continue;
+            }
+            let subschemas: Vec<(String, &Value)> = match value {
+                Value::Object(subschemas) => subschemas
+                    .iter()
+                    .map(|(key, subschema)| (key.clone(), subschema))
+                    .collect(),
+                Value::Array(subschemas) => subschemas
+                    .iter()
+                    .enumerate()
+                    .map(|(index, subschema)| (index.to_string(), subschema))
+                    .collect(),
+                _ => Vec::new(),
+            };
+            for (key, subschema) in subschemas {
+                let subschema_pointer = pointer::append(&keyword_pointer, &key);
+                if let Some((subschema_original_pointers, subschema_source)) = self.trace_node(
+                    subschema,
+                    &subschema_pointer,
+                    None,
+                    keyword_source
+                        .as_deref()
+                        .map(|source| pointer::append(source, &key))
+                        .as_deref(),
+                    &keyword_original_pointers,
+                    source_map,
+                ) {
+                    self.trace_schema(
+                        subschema,
+                        &subschema_pointer,
+                        subschema_source.as_deref(),
+                        &subschema_original_pointers,
+                        source_map,
+                    );
+                }
+            }
+        }
+    }
+
+    /// Trace back all the locations of the modified schema
+    fn run(&self) -> SourceMap {
+        let mut source_map = SourceMap::default();
+        // The modified schema is always traced back to itself
+        let original_pointers = self.previous_original_pointers("").to_vec();
+        source_map.add(self.pointer, original_pointers.iter().cloned());
+        self.trace_schema(
+            self.updated_schema,
+            "",
+            Some(""),
+            &original_pointers,
+            &mut source_map,
+        );
+        source_map
+    }
+}
+
+/// Observer maintaining the [`SourceMap`] of a schema, referenced by `pointer`, while it is optimised
+#[derive(Debug)]
+struct Tracer<'o> {
+    pointer: String,
+    source_map: SourceMap,
+    options: &'o Options,
+}
+
+impl Observer for Tracer<'_> {
+    const OBSERVES_MODIFICATIONS: bool = true;
+
+    fn child(&self, location: Location<'_>) -> Self {
+        let pointer = location.pointer(&self.pointer);
+        let mut source_map = SourceMap::default();
+        for (subschema_pointer, original_pointers) in self.source_map.subtree(&pointer) {
+            source_map.add(subschema_pointer, original_pointers.iter().cloned());
+        }
+        Self {
+            pointer,
+            source_map,
+            options: self.options,
+        }
+    }
+
+    fn merge(&mut self, child: Self) {
+        let _ = self.source_map.remove_subtree(&child.pointer);
+        self.source_map.mappings.extend(child.source_map.mappings);
+    }
+
+    fn modified(&mut self, schema: &Value, updated_schema: &Value) {
+        let previous_source_map = SourceMap {
+            mappings: self.source_map.remove_subtree(&self.pointer),
+        };
+        let source_map = Trace::new(
+            &self.pointer,
+            schema,
+            updated_schema,
+            &previous_source_map,
+            self.options,
+        )
+        .run();
+        self.source_map.mappings.extend(source_map.mappings);
+    }
+}
+
+/// Generate an equivalent schema to the schema provided as input, applying only the rules enabled by `options`,
+/// together with the [`SourceMap`] tracing its locations back to the original schema.
+/// ```rust
+/// use jsonschema_equivalent::{jsonschema_equivalent_with_source_map, Options};
+/// use serde_json::json;
+///
+/// let (schema, source_map) = jsonschema_equivalent_with_source_map(
+///     &json!({"allOf": [{"type": "string"}, {"minLength": 1}]}),
+///     &Options::default(),
+/// );
+/// assert_eq!(schema, json!({"type": "string", "minLength": 1}));
+/// assert_eq!(source_map.original_pointers("/minLength"), ["/allOf/1/minLength"]);
+/// ```
+#[must_use]
+pub fn jsonschema_equivalent_with_source_map(
+    schema: &Value,
+    options: &Options,
+) -> (Value, SourceMap) {
+    let mut optimised_schema = schema.clone();
+    let prepared = crate::prepare_schema(&mut optimised_schema, options, &mut Edits::default());
+
+    // The policies applied before the optimisation only remove keywords, so the locations of the prepared
+    // schema are locations of the original schema as well
+    let mut nodes = Vec::new();
+    collect_nodes(&optimised_schema, String::new(), options, &mut nodes);
+    let mut tracer = Tracer {
+        pointer: String::new(),
+        source_map: SourceMap::default(),
+        options,
+    };
+    for node in nodes {
+        tracer.source_map.add(&node.pointer, [node.pointer.clone()]);
+    }
+
+    match prepared {
+        Ok(()) => {
+            let _ = crate::optimise_schema(&mut optimised_schema, options, &mut tracer);
+        }
+        Err(error) => {
+            log::warn!("Schema not optimised: {error}");
+            optimised_schema = schema.clone();
+        }
+    }
+    (optimised_schema, tracer.source_map)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::jsonschema_equivalent_with_source_map;
+    use crate::Options;
+    use serde_json::{json, Value};
+    use test_case::test_case;
+
+    #[test_case(&json!(false), "" => vec![""])]
+    #[test_case(&json!({"type": "string", "minimum": 1}), "/type" => vec!["/type"])]
+    #[test_case(&json!({"allOf": [{"type": "string"}, {"minLength": 1}]}), "/type" => vec!["/allOf/0/type"])]
+    #[test_case(&json!({"allOf": [{"type": "string"}, {"allOf": [{"minLength": 1}]}]}), "/minLength" => vec!["/allOf/1/allOf/0/minLength"])]
+    #[test_case(&json!({"if": false, "then": {"minLength": 1}, "else": {"maxLength": 1}}), "/maxLength" => vec!["/else/maxLength"])]
+    #[test_case(&json!({"type": "string", "allOf": [{"type": ["integer", "string"]}]}), "/type" => vec!["/type", "/allOf/0/type"])]
+    #[test_case(&json!({"type": ["integer", "string"], "const": "a"}), "/type" => vec!["/type"])]
+    #[test_case(&json!({"const": "a"}), "/type" => vec![""] ; "generated keyword")]
+    #[test_case(&json!({"properties": {"a/b": {"type": "string", "minimum": 1}}}), "/properties/a~1b/type" => vec!["/properties/a~1b/type"])]
+    #[test_case(&json!({"anyOf": [{"type": "string", "minimum": 1}, {"type": "number"}]}), "/anyOf/0" => vec!["/anyOf/0"])]
+    #[test_case(&json!({"anyOf": [{"type": "string", "minimum": 1}, {"type": "number"}]}), "/anyOf/1/type" => vec!["/anyOf/1/type"])]
+    #[test_case(&json!({"anyOf": [{"minimum": 1, "type": "number"}, {"minimum": 2, "type": "number", "maxLength": 3}]}), "/minimum" => vec!["/anyOf/0/minimum"])]
+    #[test_case(&json!({"anyOf": [{"minimum": 1, "type": "number"}, {"minimum": 2, "type": "number", "maxLength": 3}]}), "/type" => vec!["/anyOf/0/type", "/anyOf/1/type"])]
+    #[test_case(&json!({"anyOf": [{"type": "string", "minLength": 1}, {"type": "string", "maxLength": 3}]}), "/type" => vec!["/anyOf/0/type", "/anyOf/1/type"] ; "hoisted keyword")]
+    #[test_case(&json!({"anyOf": [{"type": "string", "minLength": 1}, {"type": "string", "maxLength": 3}]}), "/anyOf/1/maxLength" => vec!["/anyOf/1/maxLength"] ; "keyword of a branch with hoisted keywords")]
+    #[test_case(&json!({"allOf": [{"properties": {"a": {"type": "string"}}}, {"required": ["b"]}], "anyOf": [{"minProperties": 1}]}), "/required" => vec!["/allOf/1/required"] ; "copied keyword")]
+    #[test_case(&json!({"type": "object", "required": ["a", "b"]}), "/required/1" => vec!["/required"] ; "resolved via the closest ancestor")]
+    #[test_case(&json!({"type": "object"}), "/not/existing" => vec![""])]
+    fn test_original_pointers(schema: &Value, pointer: &str) -> Vec<String> {
+        crate::init_logger();
+        let (_, source_map) = jsonschema_equivalent_with_source_map(schema, &Options::default());
+        source_map.original_pointers(pointer).to_vec()
+    }
+
+    #[test]
+    fn test_every_location_is_mapped() {
+        let schema = json!({
+            "allOf": [{"properties": {"a": {"type": "string"}}}, {"properties": {"b": {}}, "required": ["b"]}],
+            "anyOf": [{"if": true, "then": {"minProperties": 1}}],
+        });
+        let (optimised_schema, source_map) =
+            jsonschema_equivalent_with_source_map(&schema, &Options::default());
+        for (pointer, original_pointers) in source_map.iter() {
+            assert!(optimised_schema.pointer(pointer).is_some(), "{}", pointer);
+            assert!(!original_pointers.is_empty(), "{}", pointer);
+            for original_pointer in original_pointers {
+                assert!(
+                    schema.pointer(original_pointer).is_some(),
+                    "{}",
+                    original_pointer
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,358 @@
+//! Partial evaluation of JSON Schemas.
+//!
+//! Instances are often known to have some properties before being validated (ie. the `kind` of a message
+//! is known from its routing). Specialising the schema for such facts removes the branches of `anyOf`,
+//! `oneOf` and `if` keywords that can not be relevant for the instances, so the residual schema is
+//! cheaper to validate.
+//!
+//! The residual schema validates as the original schema all the instances satisfying the facts.
+use crate::{
+    helpers::{is, pointer},
+    is_satisfiable, is_subschema, jsonschema_equivalent_with_options, Inclusion, Options,
+    PrimitiveType, Satisfiability,
+};
+use serde_json::{json, Map, Value};
+
+/// Known fact about the instances to validate.
+///
+/// The facts refer to the instance via JSON Pointers, whose tokens are considered property names.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Fact {
+    /// The value at `pointer` is equal to `value`. ie. `/kind` is equal to `"order"`
+    Equals {
+        /// JSON Pointer of the value within the instance
+        pointer: String,
+        /// Known value
+        value: Value,
+    },
+    /// The value at `pointer` has the primitive type `primitive_type`. ie. the instance (`""`) is an `"object"`
+    HasType {
+        /// JSON Pointer of the value within the instance
+        pointer: String,
+        /// Known primitive type (ie. [`PrimitiveType::Object`])
+        primitive_type: PrimitiveType,
+    },
+    /// The value at `pointer` is valid against `schema`
+    Satisfies {
+        /// JSON Pointer of the value within the instance
+        pointer: String,
+        /// Schema known to be satisfied
+        schema: Value,
+    },
+}
+
+impl Fact {
+    /// JSON Pointer of the value the fact refers to, and the schema satisfied by the value
+    fn pointer_and_schema(&self) -> (&str, Value) {
+        match self {
+            Self::Equals { pointer, value } => (pointer, json!({ "const": value })),
+            Self::HasType {
+                pointer,
+                primitive_type,
+            } => (pointer, json!({ "type": primitive_type.to_string() })),
+            Self::Satisfies { pointer, schema } => (pointer, schema.clone()),
+        }
+    }
+}
+
+/// Add to `facts_object` the constraint that the value at `fact_pointer` is valid against `constraint`
+fn add_fact(facts_object: &mut Map<String, Value>, fact_pointer: &str, constraint: Value) {
+    let mut schema_object = facts_object;
+    for token in pointer::tokens(fact_pointer) {
+        let _ = schema_object.insert("type".to_string(), json!("object"));
+        if let Value::Array(required) = schema_object
+            .entry("required")
+            .or_insert_with(|| Value::Array(Vec::with_capacity(1)))
+        {
+            if !required.iter().any(|property| property == &token) {
+                required.push(Value::String(token.clone()));
+            }
+        }
+        let Some(Value::Object(properties)) = schema_object
+            .entry("properties")
+            .or_insert_with(|| Value::Object(Map::new()))
+            .as_object_mut()
+            .map(|properties| properties.entry(token).or_insert_with(|| json!({})))
+        else {
+            return;
+        };
+        schema_object = properties;
+    }
+
+    match constraint {
+        Value::Object(constraint_object)
+            if constraint_object
+                .keys()
+                .all(|keyword| !schema_object.contains_key(keyword)) =>
+        {
+            schema_object.extend(constraint_object);
+        }
+        _ => {
+            // Conflicting keywords are combined via `allOf`, handled by the optimisation
+            if let Value::Array(all_of_items) = schema_object
+                .entry("allOf")
+                .or_insert_with(|| Value::Array(Vec::with_capacity(1)))
+            {
+                all_of_items.push(constraint);
+            }
+        }
+    }
+}
+
+/// Schema that is satisfied by the instances for which all the `facts` are true
+fn facts_schema(facts: &[Fact]) -> Value {
+    let mut facts_schema = Map::new();
+    for fact in facts {
+        let (fact_pointer, constraint) = fact.pointer_and_schema();
+        add_fact(&mut facts_schema, fact_pointer, constraint);
+    }
+    Value::Object(facts_schema)
+}
+
+/// Checks if no instance satisfying `facts` is valid against `schema`
+fn contradicts(schema: &Value, facts: &Value) -> bool {
+    is_satisfiable(&json!({"allOf": [schema, facts]})) == Satisfiability::No
+}
+
+/// Checks if all the instances satisfying `facts` are valid against `schema`
+fn is_implied(schema: &Value, facts: &Value) -> bool {
+    is_subschema(facts, schema) == Inclusion::Yes
+}
+
+/// Prune the `anyOf`/`oneOf` branches of `schema_object` contradicting `facts`.
+/// The method returns `None` if all the branches have been pruned (the schema is a `false` schema).
+fn prune_branches(schema_object: &mut Map<String, Value>, facts: &Value) -> Option<bool> {
+    let mut updated_schema = false;
+    for keyword in &["anyOf", "oneOf"] {
+        let Some(Value::Array(branches)) = schema_object.get_mut(*keyword) else {
+            continue;
+        };
+        let branches_count = branches.len();
+        for branch in branches.iter_mut() {
+            updated_schema |= prune(branch, facts);
+        }
+        // Branches contradicting the facts are never valid, so they do not alter `anyOf` and `oneOf` outcome
+        branches.retain(|branch| !is::false_schema(branch) && !contradicts(branch, facts));
+        updated_schema |= branches.len() != branches_count;
+        match branches.len() {
+            0 if branches_count > 0 => return None,
+            1 => {
+                // A single branch has to be valid, as for `allOf` keyword
+                let branch = branches.remove(0);
+                let _ = schema_object.remove(*keyword);
+                if let Value::Array(all_of_items) = schema_object
+                    .entry("allOf")
+                    .or_insert_with(|| Value::Array(Vec::with_capacity(1)))
+                {
+                    all_of_items.push(branch);
+                }
+                updated_schema = true;
+            }
+            _ => {}
+        }
+    }
+    Some(updated_schema)
+}
+
+/// Prune the subschemas of `schema` that are not relevant for the instances satisfying `facts`.
+/// The method returns true if the schema has been modified.
+fn prune(schema: &mut Value, facts: &Value) -> bool {
+    let Value::Object(schema_object) = schema else {
+        return false;
+    };
+
+    let mut updated_schema = false;
+    if let Some(Value::Array(all_of_items)) = schema_object.get_mut("allOf") {
+        for all_of_item in all_of_items {
+            updated_schema |= prune(all_of_item, facts);
+            // The `allOf` items implied by the facts are valid for all the relevant instances
+            if !is::true_schema(all_of_item) && is_implied(all_of_item, facts) {
+                *all_of_item = Value::Bool(true);
+                updated_schema = true;
+            }
+        }
+    }
+    for keyword in &["then", "else"] {
+        if let Some(subschema) = schema_object.get_mut(*keyword) {
+            updated_schema |= prune(subschema, facts);
+        }
+    }
+    if let (Some(Value::Object(properties)), Some(Value::Object(facts_properties))) =
+        (schema_object.get_mut("properties"), facts.get("properties"))
+    {
+        for (property, subschema) in properties {
+            if let Some(property_facts) = facts_properties.get(property) {
+                updated_schema |= prune(subschema, property_facts);
+            }
+        }
+    }
+
+    if let Some(if_schema) = schema_object.get_mut("if") {
+        if !if_schema.is_boolean() {
+            // The `if` outcome might be known, then `simplify_if` rule embeds the relevant branch
+            if contradicts(if_schema, facts) {
+                *if_schema = Value::Bool(false);
+                updated_schema = true;
+            } else if is_implied(if_schema, facts) {
+                *if_schema = Value::Bool(true);
+                updated_schema = true;
+            }
+        }
+    }
+    if let Some(not_schema) = schema_object.get("not") {
+        if contradicts(not_schema, facts) {
+            let _ = schema_object.remove("not");
+            updated_schema = true;
+        } else if is_implied(not_schema, facts) {
+            *schema = Value::Bool(false);
+            return true;
+        }
+    }
+
+    if let Some(updated_branches) = prune_branches(schema_object, facts) {
+        updated_schema || updated_branches
+    } else {
+        *schema = Value::Bool(false);
+        true
+    }
+}
+
+/// Specialise `schema` for the instances satisfying `facts`, as [`specialise`], applying only the rules
+/// enabled by `options`.
+#[must_use]
+pub fn specialise_with_options(schema: Value, facts: &[Fact], options: &Options) -> Value {
+    let facts_schema = jsonschema_equivalent_with_options(facts_schema(facts), options);
+    let mut residual_schema = Map::new();
+    let _ = residual_schema.insert(
+        "allOf".to_string(),
+        Value::Array(vec![schema, facts_schema.clone()]),
+    );
+    let mut residual_schema =
+        jsonschema_equivalent_with_options(Value::Object(residual_schema), options);
+    while prune(&mut residual_schema, &facts_schema) {
+        residual_schema = jsonschema_equivalent_with_options(residual_schema, options);
+    }
+    residual_schema
+}
+
+/// Specialise `schema` for the instances satisfying `facts` (partial evaluation).
+///
+/// The facts are injected as additional constraints, and the branches of `anyOf`, `oneOf`, `if` and `not`
+/// keywords that are decided by the facts are removed. The residual schema validates as `schema` all the
+/// instances satisfying the facts.
+/// ```rust
+/// use jsonschema_equivalent::{specialise, Fact};
+/// use serde_json::json;
+///
+/// let schema = json!({"oneOf": [
+///     {"type": "object", "properties": {"kind": {"const": "order"}, "id": {"type": "integer"}}, "required": ["kind", "id"]},
+///     {"type": "object", "properties": {"kind": {"const": "refund"}, "order": {"type": "integer"}}, "required": ["kind", "order"]},
+/// ]});
+/// let facts = [Fact::Equals { pointer: "/kind".to_string(), value: json!("order") }];
+/// assert_eq!(
+///     specialise(schema, &facts),
+///     json!({
+///         "type": "object",
+///         "required": ["kind", "id"],
+///         "allOf": [{"properties": {"kind": {"const": "order", "type": "string"}, "id": {"type": "integer"}}, "required": ["id"]}],
+///     }),
+/// );
+/// ```
+#[must_use]
+pub fn specialise(schema: Value, facts: &[Fact]) -> Value {
+    specialise_with_options(schema, facts, &Options::default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{facts_schema, specialise, Fact, PrimitiveType};
+    use serde_json::{json, Value};
+    use test_case::test_case;
+
+    fn kind_is(value: Value) -> Fact {
+        Fact::Equals {
+            pointer: "/kind".to_string(),
+            value,
+        }
+    }
+
+    #[test_case(&[] => json!({}))]
+    #[test_case(&[kind_is(json!("a"))] => json!({"type": "object", "required": ["kind"], "properties": {"kind": {"const": "a"}}}))]
+    #[test_case(&[Fact::HasType {pointer: String::new(), primitive_type: PrimitiveType::Object}] => json!({"type": "object"}))]
+    #[test_case(
+        &[Fact::Satisfies {pointer: "/a~1b/c".to_string(), schema: json!({"minimum": 1})}]
+        => json!({"type": "object", "required": ["a/b"], "properties": {"a/b": {"type": "object", "required": ["c"], "properties": {"c": {"minimum": 1}}}}})
+    )]
+    #[test_case(
+        &[kind_is(json!("a")), Fact::HasType {pointer: "/version".to_string(), primitive_type: PrimitiveType::Integer}, Fact::Satisfies {pointer: "/kind".to_string(), schema: json!({"const": "b"})}]
+        => json!({
+            "type": "object",
+            "required": ["kind", "version"],
+            "properties": {"kind": {"const": "a", "allOf": [{"const": "b"}]}, "version": {"type": "integer"}},
+        })
+    )]
+    fn test_facts_schema(facts: &[Fact]) -> Value {
+        facts_schema(facts)
+    }
+
+    #[test_case(json!({"type": "string"}), &[] => json!({"type": "string"}))]
+    #[test_case(json!({"type": "string"}), &[kind_is(json!("a"))] => json!(false))]
+    #[test_case(
+        json!({"anyOf": [{"properties": {"kind": {"const": "a"}}}, {"properties": {"kind": {"const": "b"}}, "required": ["b"]}]}),
+        &[kind_is(json!("b"))]
+        => json!({"type": "object", "required": ["kind", "b"], "allOf": [{"properties": {"kind": {"const": "b", "type": "string"}}, "required": ["b"]}]})
+    )]
+    #[test_case(
+        json!({"oneOf": [{"properties": {"kind": {"const": "a"}}}, {"properties": {"kind": {"const": "b"}}}]}),
+        &[kind_is(json!("c"))]
+        => json!(false)
+    )]
+    #[test_case(
+        json!({"if": {"properties": {"kind": {"const": "a"}}}, "then": {"required": ["a"]}, "else": {"required": ["b"]}}),
+        &[kind_is(json!("a"))]
+        => json!({"type": "object", "required": ["kind", "a"]})
+    )]
+    #[test_case(
+        json!({"if": {"properties": {"kind": {"const": "a"}}}, "then": {"required": ["a"]}, "else": {"required": ["b"]}}),
+        &[kind_is(json!("c"))]
+        => json!({"type": "object", "required": ["kind", "b"]})
+    )]
+    #[test_case(
+        json!({"properties": {"payload": {"anyOf": [{"type": "string"}, {"type": "object", "required": ["a"]}]}}}),
+        &[Fact::HasType {pointer: "/payload".to_string(), primitive_type: PrimitiveType::Object}]
+        => json!({"type": "object", "required": ["payload"], "allOf": [{"type": "object", "properties": {"payload": {"type": "object", "required": ["a"]}}}]})
+    )]
+    #[test_case(
+        json!({"not": {"properties": {"kind": {"const": "a"}}}}),
+        &[kind_is(json!("a"))]
+        => json!(false)
+    )]
+    fn test_specialise(schema: Value, facts: &[Fact]) -> Value {
+        crate::init_logger();
+        specialise(schema, facts)
+    }
+
+    #[test_case(
+        &json!({"oneOf": [
+            {"properties": {"kind": {"const": "a"}, "a": {"type": "string"}}, "required": ["kind"]},
+            {"properties": {"kind": {"const": "b"}, "a": {"type": "integer"}}, "required": ["kind"]},
+        ]}),
+        &[kind_is(json!("a"))],
+        &[json!({"kind": "a"}), json!({"kind": "a", "a": "a"}), json!({"kind": "a", "a": 1})]
+    )]
+    #[test_case(
+        &json!({"if": {"properties": {"kind": {"const": "a"}}}, "then": {"required": ["a"]}, "not": {"required": ["b"]}}),
+        &[kind_is(json!("a"))],
+        &[json!({"kind": "a"}), json!({"kind": "a", "a": 1}), json!({"kind": "a", "a": 1, "b": 1})]
+    )]
+    fn test_specialise_preserves_validation(schema: &Value, facts: &[Fact], instances: &[Value]) {
+        let residual_schema = specialise(schema.clone(), facts);
+        for instance in instances {
+            assert_eq!(
+                jsonschema::is_valid(schema, instance),
+                jsonschema::is_valid(&residual_schema, instance),
+                "{schema} and {residual_schema} disagree on {instance}"
+            );
+        }
+    }
+}
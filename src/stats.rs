@@ -0,0 +1,233 @@
+//! Statistics about the optimisation of a schema.
+//!
+//! The statistics allow to track how much the optimisation reduces a schema (see [`Stats`]) and which
+//! rules contribute to it (see [`RuleStats`]).
+use crate::helpers::{
+    edits::Edits,
+    subschemas::{for_each_subschema_with_pointer, Location},
+};
+use crate::keywords::Observer;
+use crate::Options;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::convert::TryFrom;
+use std::time::Duration;
+
+/// Size of a schema
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct SchemaStats {
+    /// Number of schemas, the schema itself and all its subschemas (including the ones in `definitions`)
+    pub nodes: usize,
+    /// Number of occurrences of each keyword across all the schemas
+    pub keywords: BTreeMap<String, usize>,
+    /// Number of bytes of the compact JSON serialisation of the schema
+    pub bytes: usize,
+}
+
+impl SchemaStats {
+    /// Compute the size of `schema`
+    fn new(schema: &Value, options: &Options) -> Self {
+        let mut schema_stats = Self {
+            nodes: 0,
+            keywords: BTreeMap::new(),
+            bytes: schema.to_string().len(),
+        };
+        schema_stats.count(schema, options);
+        schema_stats
+    }
+
+    /// Count `schema` and its subschemas
+    fn count(&mut self, schema: &Value, options: &Options) {
+        match schema {
+            Value::Bool(_) => self.nodes = self.nodes.saturating_add(1),
+            Value::Object(schema_object) => {
+                self.nodes = self.nodes.saturating_add(1);
+                for keyword in schema_object.keys() {
+                    let count = self.keywords.entry(keyword.clone()).or_default();
+                    *count = count.saturating_add(1);
+                }
+                for_each_subschema_with_pointer(
+                    schema,
+                    "",
+                    &|keyword| options.is_custom_subschema_keyword(keyword),
+                    &mut |_, subschema| self.count(subschema, options),
+                );
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Statistics about the applications of a rule
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct RuleStats {
+    /// Number of times the rule has been applied to a schema
+    pub applications: usize,
+    /// Number of applications that modified the schema
+    pub hits: usize,
+    /// Time spent applying the rule, measured around each application of the rule
+    /// (it does not require the `logging` feature)
+    pub elapsed: Duration,
+}
+
+/// Statistics about the optimisation of a schema
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Stats {
+    /// Size of the schema before the optimisation
+    pub before: SchemaStats,
+    /// Size of the schema after the optimisation
+    pub after: SchemaStats,
+    /// Number of optimisation rounds, the last one being the round that did not modify the schema anymore.
+    /// It is `0` if the schema could not be optimised (see [`crate::try_jsonschema_equivalent_with_options_ref`]).
+    pub rounds: usize,
+    /// Statistics of the applied rules, by name (see [`Options::rule_names`]).
+    pub rules: BTreeMap<&'static str, RuleStats>,
+}
+
+impl Stats {
+    /// Number of bytes removed from the serialised schema by the optimisation.
+    /// The value is negative if the optimisation increased the size of the schema.
+    #[must_use]
+    pub fn bytes_saved(&self) -> i64 {
+        i64::try_from(self.before.bytes)
+            .unwrap_or(i64::MAX)
+            .saturating_sub(i64::try_from(self.after.bytes).unwrap_or(i64::MAX))
+    }
+}
+
+/// Observer collecting the statistics of the rules applications
+#[derive(Debug, Default)]
+struct RulesObserver(BTreeMap<&'static str, RuleStats>);
+
+impl Observer for RulesObserver {
+    const OBSERVES_RULES: bool = true;
+
+    fn child(&self, _location: Location<'_>) -> Self {
+        Self::default()
+    }
+
+    fn merge(&mut self, child: Self) {
+        for (rule, child_rule_stats) in child.0 {
+            let rule_stats = self.0.entry(rule).or_default();
+            rule_stats.applications = rule_stats
+                .applications
+                .saturating_add(child_rule_stats.applications);
+            rule_stats.hits = rule_stats.hits.saturating_add(child_rule_stats.hits);
+            rule_stats.elapsed = rule_stats.elapsed.saturating_add(child_rule_stats.elapsed);
+        }
+    }
+
+    fn rule_applied(&mut self, rule: &'static str, is_schema_updated: bool, elapsed: Duration) {
+        let rule_stats = self.0.entry(rule).or_default();
+        rule_stats.applications = rule_stats.applications.saturating_add(1);
+        if is_schema_updated {
+            rule_stats.hits = rule_stats.hits.saturating_add(1);
+        }
+        rule_stats.elapsed = rule_stats.elapsed.saturating_add(elapsed);
+    }
+}
+
+/// Optimise input schema, as [`crate::jsonschema_equivalent_with_options_ref`], reporting statistics about the optimisation.
+/// ```rust
+/// use jsonschema_equivalent::{jsonschema_equivalent_with_stats_ref, Options};
+/// use serde_json::json;
+///
+/// let mut schema = json!({"type": "string", "minimum": 1, "allOf": [{"minLength": 1}]});
+/// let (schema, stats) = jsonschema_equivalent_with_stats_ref(&mut schema, &Options::default());
+/// assert_eq!(schema, &json!({"type": "string", "minLength": 1}));
+/// assert_eq!((stats.before.nodes, stats.after.nodes), (2, 1));
+/// assert_eq!(stats.before.keywords.get("minimum"), Some(&1));
+/// assert_eq!(stats.after.keywords.get("minimum"), None);
+/// assert_eq!(stats.rules["flatten_all_of"].hits, 1);
+/// assert!(stats.bytes_saved() > 0);
+/// ```
+#[must_use]
+pub fn jsonschema_equivalent_with_stats_ref<'s>(
+    schema: &'s mut Value,
+    options: &Options,
+) -> (&'s mut Value, Stats) {
+    let before = SchemaStats::new(schema, options);
+    let mut rules_observer = RulesObserver::default();
+    let rounds = match crate::prepare_schema(schema, options, &mut Edits::default()) {
+        Ok(()) => crate::optimise_schema(schema, options, &mut rules_observer),
+        Err(error) => {
+            log::warn!("Schema not optimised: {error}");
+            0
+        }
+    };
+    let stats = Stats {
+        before,
+        after: SchemaStats::new(schema, options),
+        rounds,
+        rules: rules_observer.0,
+    };
+    (schema, stats)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{jsonschema_equivalent_with_stats_ref, SchemaStats};
+    use crate::{jsonschema_equivalent_with_options, Options, UnknownKeywordPolicy};
+    use serde_json::{json, Value};
+    use test_case::test_case;
+
+    #[test_case(&json!(true) => (1, vec![]))]
+    #[test_case(&json!({"type": "string"}) => (1, vec![("type".to_string(), 1)]))]
+    #[test_case(
+        &json!({"properties": {"a": {"type": "string"}, "b": false}, "items": [{"type": "integer"}]})
+        => (4, vec![("items".to_string(), 1), ("properties".to_string(), 1), ("type".to_string(), 2)])
+    )]
+    #[test_case(
+        &json!({"definitions": {"a": {"minimum": 1}}, "const": {"type": "string"}})
+        => (2, vec![("const".to_string(), 1), ("definitions".to_string(), 1), ("minimum".to_string(), 1)])
+    )]
+    fn test_schema_stats(schema: &Value) -> (usize, Vec<(String, usize)>) {
+        let schema_stats = SchemaStats::new(schema, &Options::default());
+        assert_eq!(schema_stats.bytes, schema.to_string().len());
+        (
+            schema_stats.nodes,
+            schema_stats.keywords.into_iter().collect(),
+        )
+    }
+
+    #[test_case(&json!({}))]
+    #[test_case(&json!({"type": "string", "minimum": 1}))]
+    #[test_case(&json!({"allOf": [{"type": "string"}, {"properties": {"a": {"type": "integer", "minLength": 1}}}]}))]
+    fn test_stats_describe_the_optimisation(schema: &Value) {
+        crate::init_logger();
+        let mut processed_schema = schema.clone();
+        let (processed_schema, stats) =
+            jsonschema_equivalent_with_stats_ref(&mut processed_schema, &Options::default());
+        assert_eq!(
+            processed_schema,
+            &jsonschema_equivalent_with_options(schema.clone(), &Options::default())
+        );
+        assert_eq!(stats.before, SchemaStats::new(schema, &Options::default()));
+        assert_eq!(
+            stats.after,
+            SchemaStats::new(processed_schema, &Options::default())
+        );
+        assert!(stats.rounds > 0);
+        for rule_stats in stats.rules.values() {
+            assert!(rule_stats.hits <= rule_stats.applications);
+        }
+    }
+
+    #[test]
+    fn test_stats_count_rule_hits() {
+        let mut schema = json!({"properties": {"a": {"type": "string", "minimum": 1}, "b": {"type": "string", "maximum": 1}}});
+        let (_, stats) = jsonschema_equivalent_with_stats_ref(&mut schema, &Options::default());
+        assert_eq!(stats.rules["remove_extraneous_keys_keyword_type"].hits, 2);
+        assert_eq!(stats.rules["flatten_all_of"].hits, 0);
+    }
+
+    #[test]
+    fn test_stats_of_not_optimised_schema() {
+        let options = Options::new().with_unknown_keyword_policy(UnknownKeywordPolicy::Error);
+        let mut schema = json!({"minimun": 1});
+        let (_, stats) = jsonschema_equivalent_with_stats_ref(&mut schema, &options);
+        assert_eq!(stats.rounds, 0);
+        assert_eq!(stats.before, stats.after);
+        assert!(stats.rules.is_empty());
+    }
+}